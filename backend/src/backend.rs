@@ -1,28 +1,42 @@
+pub mod dependency;
+pub mod diagnostics;
 pub mod episode;
+pub mod episode_id;
+pub mod file_info;
+pub mod history;
+pub mod library;
 pub mod meta;
+pub mod metadata_store;
+pub mod process;
+pub mod progress;
+pub mod season;
 pub mod title;
+pub mod title_settings;
 pub mod video_metadata;
+pub mod watch_status;
+pub mod watcher;
 
+use crate::config::CFG_PATH;
 use crate::Config;
 use crate::Discord;
 use crate::Result;
+use crate::Season;
 use crate::Title;
+use crate::{History, HistoryEntry, Playback, Player};
+use crate::{Library, LibraryChange, MetadataStore, ProviderChain, SeriesInfo};
 
 use anyhow::bail;
 use core::fmt::Debug;
 use discord_sdk as ds;
-use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::{env, fs, path::PathBuf, process::Command};
-use tracing::warn;
+use std::time::SystemTime;
+use tracing::{info, warn};
 
-static SCRIPT_PATH: Lazy<PathBuf> = Lazy::new(|| {
-    confy::get_configuration_file_path("yama", "config")
-        .expect("No configuration path found.")
-        .parent()
-        .expect("No valid configuration path found.")
-        .join("scripts/save_info.lua")
-});
+/// How deep folders with other series inside are scanned for [`Titles`][Title].
+const MAX_DEPTH: usize = 4;
 
 /// [yama's] Backend, contains all the [`Titles`][Title] and utils to run this application.
 ///
@@ -34,17 +48,89 @@ pub struct Backend {
     /// Number of [`titles`][Title] this [`Backend`][Backend] has.
     pub count: usize,
     title_cache: Arc<[Arc<str>]>,
+    /// Enabled [`Libraries`][Library] with the [`MetadataStore`][MetadataStore] of their titles.
+    libraries: Vec<(Library, MetadataStore)>,
+    /// Sources of the [`SeriesInfo`][crate::SeriesInfo] of the titles.
+    provider: ProviderChain,
+    /// Folder with the [`Config`][Config] file, the [`History`][History] and the indexes of the libraries.
+    config_dir: PathBuf,
 }
 
 impl Backend {
     /// Creates a new [`Backend`][Backend] instance, it will find all the [`titles`][Title]
     /// in the folder specified in the [`Config`][Config] file, and download their [metadata]
-    /// with [`Anilist`][crate::Anilist] API.
+    /// with the [`MetadataProviders`][crate::MetadataProvider] of a [`ProviderChain`][ProviderChain].
     pub async fn new() -> Result<Self> {
-        let mut titles = Self::load_titles()?;
-        Self::download_titles_data(titles.as_mut_slice()).await;
+        Self::load(CFG_PATH.clone(), true).await
+    }
+
+    /// Creates a new [`Backend`][Backend] like [`new`][Backend::new], without connecting to Discord.
+    pub async fn without_discord() -> Result<Self> {
+        Self::load(CFG_PATH.clone(), false).await
+    }
+
+    /// Creates a new [`Backend`][Backend] like [`without_discord`][Backend::without_discord],
+    /// using the _config_dir_ folder instead of the one of the user.
+    pub async fn in_config_dir(config_dir: impl Into<PathBuf>) -> Result<Self> {
+        Self::load(config_dir.into(), false).await
+    }
+
+    async fn load(config_dir: PathBuf, discord: bool) -> Result<Self> {
+        let cfg: Config = confy::load_path(config_dir.join("config.toml"))?;
+        let libraries: Vec<(Library, MetadataStore)> = cfg
+            .libraries()
+            .into_iter()
+            .filter(|library| library.enabled)
+            .map(|library| {
+                let store = cfg.metadata_store(&config_dir, &library);
+                (library, store)
+            })
+            .collect();
+
+        if libraries.is_empty() {
+            warn!("No enabled libraries found.");
+            bail!("No Titles found.");
+        }
+
+        let provider = ProviderChain::default();
+        let mut titles = Self::load_titles(&config_dir, &libraries);
+
+        if titles.is_empty() {
+            warn!("No titles found in the libraries.");
+            bail!("No Titles found.");
+        }
+
+        Self::download_titles_data(titles.as_mut_slice(), &provider).await;
+
+        let ds_client = if discord {
+            Self::connect_discord().await
+        } else {
+            None
+        };
+
+        Ok(Self {
+            title_cache: Self::title_names(&titles),
+            count: titles.len(),
+            titles,
+            ds_client,
+            libraries,
+            provider,
+            config_dir,
+        })
+    }
+
+    /// Loads the [`Config`][Config] of this [`Backend`][Backend] again, it may have changed meanwhile.
+    pub fn config(&self) -> Result<Config> {
+        Ok(confy::load_path(self.config_dir.join("config.toml"))?)
+    }
+
+    /// Returns the [`History`][History] of the playbacks.
+    pub fn history(&self) -> History {
+        History::new(self.config_dir.join("history.jsonl"))
+    }
 
-        let ds_client = match Discord::new(ds::Subscriptions::ACTIVITY).await {
+    async fn connect_discord() -> Option<Discord> {
+        match Discord::new(ds::Subscriptions::ACTIVITY).await {
             Ok(user) => {
                 user.idle_activity().await;
                 Some(user)
@@ -53,52 +139,180 @@ impl Backend {
                 tracing::error!("Discord client failed to connect: {e}");
                 None
             }
-        };
+        }
+    }
 
-        Ok(Self {
-            title_cache: titles
-                .iter()
-                .map(|t| match &t.data {
-                    Some(data) => data.media.title.english.as_str().into(),
-                    None => t.name.clone(),
-                })
-                .collect(),
-            count: titles.len(),
-            titles,
-            ds_client,
-        })
+    fn title_names(titles: &[Title]) -> Arc<[Arc<str>]> {
+        titles.iter().map(Title::display_name).collect()
     }
 
-    fn load_titles() -> Result<Vec<Title>> {
-        let cfg: Config = confy::load("yama", "config")?;
+    /// Loads the [`Titles`][Title] of every enabled [`Library`][crate::Library].
+    ///
+    /// The titles of the libraries that are not available are the ones found the last time
+    /// they were, marked as offline.
+    fn load_titles(config_dir: &Path, libraries: &[(Library, MetadataStore)]) -> Vec<Title> {
+        let mut series: Vec<Title> = Vec::new();
+
+        for (library, store) in libraries {
+            let name: Arc<str> = Arc::from(library.name.as_str());
+
+            // An empty folder where there were titles is usually a mount point without its drive.
+            let known = library.load_index(config_dir).unwrap_or_default();
+            let folders = Self::get_title_folders(&library.path, 0)
+                .ok()
+                .filter(|folders| !folders.is_empty() || known.is_empty());
+
+            let titles: Vec<Title> = match folders {
+                Some(folders) => {
+                    if let Err(e) = library.save_index(config_dir, &folders) {
+                        warn!("Could not save the titles of {}: {e}", library.name);
+                    }
 
-        if cfg.series_path.is_none() {
-            warn!("No series path found.");
-            bail!("No Titles found.");
+                    folders
+                        .into_iter()
+                        .flat_map(|path| Title::new(path, store))
+                        .collect()
+                }
+                None => {
+                    warn!("The library {} is not available.", library.name);
+
+                    known
+                        .into_iter()
+                        .map(|path| Title::offline(path, store))
+                        .collect()
+                }
+            };
+
+            series.extend(titles.into_iter().map(|mut title| {
+                title.library = Some(name.clone());
+                title
+            }));
         }
 
-        let mut series: Vec<Title> = Self::get_files(&cfg.series_path.unwrap())?
-            .filter(|x| match fs::metadata(x) {
-                Ok(f) => f.is_dir(),
-                Err(_) => false,
-            })
-            .flat_map(Title::new)
-            .collect();
+        Self::sort_titles(&mut series);
+        series
+    }
 
-        series.sort_by(|a, b| alphanumeric_sort::compare_str(&a.name, &b.name));
-        Ok(series)
+    /// Sorts the [`Titles`][Title] by their folder name, the order of their indexes.
+    fn sort_titles(titles: &mut [Title]) {
+        titles.sort_by(|a, b| alphanumeric_sort::compare_str(&a.name, &b.name));
     }
 
-    async fn download_titles_data(titles: &mut [Title]) {
-        use crate::Anilist;
-        use iced::futures::future;
+    /// Returns the enabled [`Libraries`][Library].
+    pub fn libraries(&self) -> impl Iterator<Item = &Library> {
+        self.libraries.iter().map(|(library, _)| library)
+    }
+
+    /// Returns the folders of the enabled [`Libraries`][Library], to be watched for changes.
+    pub fn library_paths(&self) -> Vec<PathBuf> {
+        self.libraries
+            .iter()
+            .map(|(library, _)| library.path.clone())
+            .collect()
+    }
+
+    /// Updates the [`Titles`][Title] with the changed _paths_ found by a [`Watcher`][crate::Watcher].
+    ///
+    /// Titles whose folder was added or removed are sorted again, so the indexes of the others
+    /// may change, and only the titles with their [`Episodes`][crate::Episode] loaded are updated.
+    pub async fn apply_changes(&mut self, paths: &[PathBuf]) -> Vec<LibraryChange> {
+        let mut changes = Vec::new();
+        let previous: Vec<PathBuf> = self.titles.iter().map(|title| title.path.clone()).collect();
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+
+        for (library, store) in &self.libraries {
+            if !paths.iter().any(|path| path.starts_with(&library.path)) {
+                continue;
+            }
+
+            let Ok(folders) = Self::get_title_folders(&library.path, 0) else {
+                continue;
+            };
+
+            let in_library =
+                |title: &Title| title.library.as_deref() == Some(library.name.as_str());
+
+            // An empty folder where there were titles is usually a mount point without its drive.
+            if folders.is_empty() && self.titles.iter().any(in_library) {
+                warn!("The library {} is not available.", library.name);
+                continue;
+            }
+
+            if let Err(e) = library.save_index(&self.config_dir, &folders) {
+                warn!("Could not save the titles of {}: {e}", library.name);
+            }
+
+            self.titles.retain(|title| {
+                let kept = !in_library(title) || folders.contains(&title.path);
+                if !kept {
+                    info!("Title removed: {}.", title.name);
+                    removed.push(title.display_name());
+                }
+                kept
+            });
+
+            for folder in folders {
+                if self.titles.iter().any(|title| title.path == folder) {
+                    continue;
+                }
+
+                match Title::new(folder, store) {
+                    Ok(mut title) => {
+                        info!("New title found: {}.", title.name);
+                        title.library = Some(Arc::from(library.name.as_str()));
+
+                        if let Err(e) = title.fetch_info(&self.provider).await {
+                            warn!("{e}");
+                        }
+
+                        added.push(title.display_name());
+                        self.titles.push(title);
+                    }
+                    Err(e) => warn!("{e}"),
+                }
+            }
+        }
 
-        let api = Anilist::default();
+        if !added.is_empty() || !removed.is_empty() {
+            Self::sort_titles(&mut self.titles);
+
+            let moved = previous
+                .iter()
+                .map(|path| self.titles.iter().position(|title| &title.path == path))
+                .collect();
+            changes.push(LibraryChange::TitlesChanged(added, removed, moved));
+        }
+
+        for (title_number, title) in self.titles.iter_mut().enumerate() {
+            if !paths.iter().any(|path| path.starts_with(&title.path)) {
+                continue;
+            }
+
+            match title.sync_episodes().await {
+                Ok((added, removed)) => {
+                    if !added.is_empty() {
+                        changes.push(LibraryChange::EpisodesAdded(title_number, added));
+                    }
+                    if !removed.is_empty() {
+                        changes.push(LibraryChange::EpisodesRemoved(title_number, removed));
+                    }
+                }
+                Err(e) => warn!("{e}"),
+            }
+        }
+
+        self.count = self.titles.len();
+        self.title_cache = Self::title_names(&self.titles);
+        changes
+    }
+
+    async fn download_titles_data(titles: &mut [Title], provider: &ProviderChain) {
+        use iced::futures::future;
 
         let mut futs: Vec<_> = titles
             .iter_mut()
-            .enumerate()
-            .map(|(id, t)| api.try_query(t, id))
+            .map(|t| t.fetch_info(provider))
             .map(Box::pin)
             .collect();
 
@@ -115,59 +329,50 @@ impl Backend {
         }
     }
 
-    /// **[`Backend`][Backend] util:** Runs a secondary process given by a command.
-    /// (Windows and Linux compatibility only!)
-    pub fn run_process(cmd: &str) -> Result<()> {
-        #[cfg(target_os = "windows")]
-        {
-            let mut cmd = cmd.split(',');
-            let output = Command::new(cmd.next().unwrap())
-                .current_dir(env::current_dir()?)
-                .args(cmd)
-                .output()?;
-
-            if !output.status.success() {
-                bail!("Command failed: {}", String::from_utf8(output.stdout)?);
-            }
+    /// Returns the [`Title`][Title] folders of the library at _path_, the same ones that are loaded.
+    pub fn title_folders(path: &PathBuf) -> Result<Vec<PathBuf>> {
+        Self::get_title_folders(path, 0)
+    }
 
-            Ok(())
+    /// Returns the [`Title`][Title] folders inside a given directory.
+    ///
+    /// Folders with only other series inside (no files or season folders), like a franchise
+    /// with a folder for each of its series, are scanned recursively.
+    fn get_title_folders(path: &PathBuf, depth: usize) -> Result<Vec<PathBuf>> {
+        let mut titles = Vec::new();
+
+        for folder in Self::get_files(path)?.filter(|x| x.is_dir()) {
+            if depth < MAX_DEPTH && Self::is_collection(&folder) {
+                titles.extend(Self::get_title_folders(&folder, depth + 1)?);
+            } else {
+                titles.push(folder);
+            }
         }
 
-        #[cfg(not(target_os = "windows"))]
-        {
-            let output = Command::new("sh")
-                .current_dir(env::current_dir()?)
-                .args(["-c", cmd])
-                .output()?;
+        Ok(titles)
+    }
+
+    /// Checks if a folder only has other series folders inside.
+    fn is_collection(path: &PathBuf) -> bool {
+        let Ok(files) = Self::get_files(path) else {
+            return false;
+        };
 
-            if !output.status.success() {
-                bail!("Command failed: {}", String::from_utf8(output.stdout)?);
+        let mut has_folders = false;
+
+        for file in files {
+            let is_season = file
+                .file_name()
+                .is_some_and(|name| Season::from_folder(&name.to_string_lossy()).is_some());
+
+            if !file.is_dir() || is_season {
+                return false;
             }
 
-            Ok(())
+            has_folders = true;
         }
-    }
 
-    /// **[`Backend`][Backend] util:** Runs an instance of mpv with the given command.
-    /// The [`Episode`][crate::Episode] and it's starting time should be passes as a command.
-    pub fn run_mpv(command: &str) -> Result<()> {
-        let cfg: Config = confy::load("yama", "config")?;
-
-        let cmd = if cfg!(target_os = "windows") {
-            format!(
-                "mpv,--script={},--script-opts=save_info-min_time={},{command}",
-                SCRIPT_PATH.display(),
-                cfg.min_time
-            )
-        } else {
-            format!(
-                "mpv --script={} --script-opts=save_info-min_time={} {command}",
-                SCRIPT_PATH.display(),
-                cfg.min_time
-            )
-        };
-
-        Backend::run_process(&cmd)
+        has_folders
     }
 
     // #[cfg(not(target_os = "windows"))]
@@ -206,6 +411,123 @@ impl Backend {
             }))
     }*/
 
+    /// Returns the [`Player`][Player] for the indexed [`Title`][Title], its own
+    /// [`TitleSettings`][crate::TitleSettings] take precedence over the [`Config`][Config] one.
+    pub fn player(&self, title_number: usize) -> Result<Box<dyn Player>> {
+        if let Some(player) = self
+            .titles
+            .get(title_number)
+            .and_then(|title| title.settings.player.as_ref())
+        {
+            return Ok(player.player());
+        }
+
+        Ok(self.config()?.player.player())
+    }
+
+    /// Searches the series that may match the indexed [`Title`][Title], by _search_ or its folder name.
+    ///
+    /// Returns the searched name and the candidates, best matches first.
+    pub async fn search_matches(
+        &self,
+        title_number: usize,
+        search: Option<&str>,
+    ) -> Result<(Arc<str>, Vec<SeriesInfo>)> {
+        use crate::MetadataProvider;
+
+        let Some(title) = self.titles.get(title_number) else {
+            bail!("No title found at the index {title_number}");
+        };
+
+        let search: Arc<str> = search.map_or_else(|| title.name.clone(), Arc::from);
+        let covers = std::env::temp_dir().join("yama-covers");
+        let candidates = self.provider.search(&search, &covers).await?;
+
+        Ok((search, candidates))
+    }
+
+    /// Pins the _ids_ of the series chosen for the indexed [`Title`][Title], in its
+    /// [`TitleSettings`][crate::TitleSettings], and downloads its [metadata] again.
+    pub async fn pin_match(
+        &mut self,
+        title_number: usize,
+        ids: BTreeMap<String, String>,
+    ) -> Result<()> {
+        let Some(title) = self.titles.get_mut(title_number) else {
+            bail!("No title found at the index {title_number}");
+        };
+
+        title.settings.ids = ids;
+        title
+            .settings
+            .save(&title.metadata_dir.join("settings.json"))?;
+        title.fetch_info(&self.provider).await?;
+
+        self.title_cache = Self::title_names(&self.titles);
+        Ok(())
+    }
+
+    /// Sets the [`WatchStatus`][crate::WatchStatus] of the indexed [`Title`][Title], in its
+    /// [`TitleSettings`][crate::TitleSettings], or derives it from its progress again if it's [`None`][None].
+    pub fn set_status(
+        &mut self,
+        title_number: usize,
+        status: Option<crate::WatchStatus>,
+    ) -> Result<()> {
+        let Some(title) = self.titles.get_mut(title_number) else {
+            bail!("No title found at the index {title_number}");
+        };
+
+        title.settings.status = status;
+        title
+            .settings
+            .save(&title.metadata_dir.join("settings.json"))
+    }
+
+    /// Updates the [`Progress`][crate::Progress] of the indexed [`Title`][Title], after marking or watching its episodes.
+    pub fn update_progress(&mut self, title_number: usize) {
+        if let Some(title) = self.titles.get_mut(title_number) {
+            title.update_progress();
+        }
+    }
+
+    /// Saves the final state of the _playback_ of the video file at _path_, of the indexed
+    /// [`Title`][Title], and adds it to the [`History`][crate::History].
+    ///
+    /// Returns the number of the [`Episode`][crate::Episode], or [`None`][None] if it's not found.
+    pub fn finish_playback(
+        &mut self,
+        title_number: usize,
+        path: &Path,
+        started: SystemTime,
+        playback: &Playback,
+    ) -> Result<Option<usize>> {
+        let min_time = self.config()?.min_time as f64;
+        let history = self.history();
+
+        let Some(title) = self.titles.get_mut(title_number) else {
+            return Ok(None);
+        };
+        let Some(episode_number) = title.find_episode(path) else {
+            return Ok(None);
+        };
+
+        let title_name = title.display_name();
+        let Some(episode) = title.get_episode(episode_number) else {
+            return Ok(None);
+        };
+        info!("Finished watching: {}.", episode.name);
+        episode.update(playback, min_time)?;
+
+        let entry = HistoryEntry::new(&title_name, episode, started, playback);
+        if let Err(e) = history.append(&entry) {
+            warn!("Could not save the watch history: {e}");
+        }
+
+        title.update_progress();
+        Ok(Some(episode_number))
+    }
+
     /// Returns the specified [`Episode`][crate::Episode] or [`None`][None] if it doesn't exist.
     pub fn get_episode(
         &mut self,
@@ -222,32 +544,33 @@ impl Backend {
         self.title_cache.clone()
     }
 
-    /// Returns the name of the indexed [`Title`][Title].
-    /// ## Panics
-    /// May panic if `title_number` is out of bounds.
-    pub fn get_title_name(&self, title_number: usize) -> Arc<str> {
-        self.title_cache[title_number].clone()
+    /// Returns the name of the indexed [`Title`][Title] or [`None`][None] if it doesn't exist.
+    pub fn get_title_name(&self, title_number: usize) -> Option<Arc<str>> {
+        self.title_cache.get(title_number).cloned()
     }
 
-    /// Returns the data of the indexed [`Episode`][crate::Episode].
-    /// ## Panics
-    /// May panic if `title_number` or `episode_number` is out of bounds.
+    /// Returns the name and the time left of the indexed [`Episode`][crate::Episode],
+    /// or [`None`][None] if it doesn't exist or the episodes of its title aren't loaded.
     pub fn get_episode_data(
         &self,
         title_number: usize,
         episode_number: usize,
     ) -> Option<(Arc<str>, f64)> {
-        self.titles[title_number].episodes.as_ref().map(|episodes| {
-            let ep = &episodes[episode_number];
-            (
-                ep.name.clone(),
-                if ep.metadata.watched || ep.metadata.remaining == 0.00 {
-                    ep.metadata.duration
-                } else {
-                    ep.metadata.remaining
-                },
-            )
-        })
+        let ep = self
+            .titles
+            .get(title_number)?
+            .episodes
+            .as_ref()?
+            .get(episode_number)?;
+
+        Some((
+            ep.name.clone(),
+            if ep.metadata.watched || ep.metadata.remaining == 0.00 {
+                ep.metadata.duration
+            } else {
+                ep.metadata.remaining
+            },
+        ))
     }
 
     /// Takes a closure, applies it to the [`Titles`][Title] vector
@@ -0,0 +1,68 @@
+use crate::PlayerConfig;
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// An external program [yama] needs, and where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub program: String,
+    /// What the program is used for, like _Play the episodes_.
+    pub used_for: &'static str,
+    /// Where the program was found in the _PATH_, none if it's missing.
+    pub path: Option<PathBuf>,
+}
+
+impl Dependency {
+    /// Creates a new [`Dependency`][Dependency], searching the _program_ in the _PATH_.
+    pub fn new(program: impl Into<String>, used_for: &'static str) -> Self {
+        let program = program.into();
+        let path = std::env::var_os("PATH").and_then(|paths| find_program(&program, &paths));
+
+        Self {
+            program,
+            used_for,
+            path,
+        }
+    }
+
+    pub fn found(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Returns every [`Dependency`][Dependency] of [yama] with the media player of the _player_ config.
+    pub fn all(player: &PlayerConfig) -> Vec<Dependency> {
+        let mut dependencies = vec![
+            Self::new("ffprobe", "Read the duration of the episodes"),
+            Self::new("ffmpeg", "Generate the thumbnails of the episodes"),
+        ];
+
+        if let Some(program) = player.program() {
+            dependencies.push(Self::new(program, "Play the episodes"));
+        }
+
+        dependencies
+    }
+}
+
+/// Searches the _program_ in the list of folders of _paths_, formatted like the _PATH_.
+///
+/// A _program_ with a folder, like _./bin/mpv_ or _/usr/bin/mpv_, is only checked to exist.
+pub fn find_program(program: &str, paths: &OsStr) -> Option<PathBuf> {
+    let candidates = |dir: &Path| {
+        let path = dir.join(program);
+        let mut exe = path.clone().into_os_string();
+        exe.push(std::env::consts::EXE_SUFFIX);
+        [path, PathBuf::from(exe)]
+    };
+
+    if Path::new(program).components().count() > 1 {
+        return candidates(Path::new(""))
+            .into_iter()
+            .find(|path| path.is_file());
+    }
+
+    std::env::split_paths(paths)
+        .flat_map(|dir| candidates(&dir))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,291 @@
+use crate::config::CFG_PATH;
+use crate::{Config, Dependency, PlayerConfig, Process};
+
+use core::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Oldest mpv with the _--input-ipc-server_ option used to follow the playback.
+const MPV_MIN_VERSION: (u32, u32) = (0, 17);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    /// It works, but something may not.
+    Warning,
+    Failed,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Warning => "Warning",
+            CheckStatus::Failed => "Failed",
+        })
+    }
+}
+
+/// The result of a single check of the [`Diagnostics`][Diagnostics].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    /// What was found, like the version of a program.
+    pub details: String,
+    /// How to fix it, if it didn't pass.
+    pub remedy: Option<String>,
+}
+
+impl Check {
+    fn ok(name: impl Into<String>, details: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Ok,
+            details: details.into(),
+            remedy: None,
+        }
+    }
+
+    fn failed(
+        name: impl Into<String>,
+        details: impl Into<String>,
+        remedy: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Failed,
+            details: details.into(),
+            remedy: Some(remedy.into()),
+        }
+    }
+
+    /// Checks that the program of the _dependency_ was found and runs, reporting its version.
+    pub fn program(dependency: &Dependency) -> Self {
+        let name = dependency.program.as_str();
+
+        let Some(path) = &dependency.path else {
+            let remedy = match name {
+                "ffmpeg" | "ffprobe" => format!(
+                    "Install FFmpeg from https://ffmpeg.org/download.html, it includes both ffmpeg and ffprobe, and add its bin folder to the PATH. Without {name} yama can't {}.",
+                    dependency.used_for.to_lowercase()
+                ),
+                "mpv" => String::from(
+                    "Install mpv from https://mpv.io/installation/ and add it to the PATH, or choose another player in the config.",
+                ),
+                _ => format!(
+                    "Install {name} and add it to the PATH, or fix the player command in the config."
+                ),
+            };
+
+            return Self::failed(name, "Not found in the PATH", remedy);
+        };
+
+        // ffmpeg and ffprobe only understand _-version_.
+        let flag = match name {
+            "ffmpeg" | "ffprobe" => "-version",
+            _ => "--version",
+        };
+
+        match Process::new(path).arg(flag).run() {
+            Ok(output) => {
+                let version = String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+
+                Self::ok(name, format!("{version} ({})", path.display()))
+            }
+            Err(e) => Self::failed(
+                name,
+                format!("Found at {} but it failed to run: {e}", path.display()),
+                format!("Reinstall {name}, the one installed seems broken."),
+            ),
+        }
+    }
+
+    /// Checks that the mpv of the _version_ line, like _mpv 0.35.1 Copyright..._,
+    /// can report the playback position through its IPC server.
+    pub fn mpv_ipc(version: &str) -> Self {
+        const NAME: &str = "mpv IPC";
+
+        match parse_version(version) {
+            Some(found) if found >= MPV_MIN_VERSION => {
+                Self::ok(NAME, "The playback position can be followed")
+            }
+            Some((major, minor)) => Self::failed(
+                NAME,
+                format!("mpv {major}.{minor} has no --input-ipc-server"),
+                format!(
+                    "Update mpv to {}.{} or newer, otherwise the watched time of the episodes is not saved.",
+                    MPV_MIN_VERSION.0, MPV_MIN_VERSION.1
+                ),
+            ),
+            None => Self {
+                name: NAME.to_string(),
+                status: CheckStatus::Warning,
+                details: String::from("Unknown mpv version"),
+                remedy: Some(format!(
+                    "Make sure mpv is {}.{} or newer, otherwise the watched time of the episodes is not saved.",
+                    MPV_MIN_VERSION.0, MPV_MIN_VERSION.1
+                )),
+            },
+        }
+    }
+
+    /// Checks that the folder at _path_ exists, or can be created, and can be written.
+    pub fn folder(name: impl Into<String>, path: &Path, used_for: &str) -> Self {
+        let name = name.into();
+        let probe = path.join(".yama-doctor");
+
+        let res = fs::create_dir_all(path)
+            .and_then(|_| fs::write(&probe, b""))
+            .and_then(|_| fs::remove_file(&probe));
+
+        match res {
+            Ok(()) => Self::ok(name, path.display().to_string()),
+            Err(e) => Self::failed(
+                name,
+                format!("{} can't be written: {e}", path.display()),
+                format!(
+                    "Make sure {} is a folder your user can write, yama saves {used_for} there.",
+                    path.display()
+                ),
+            ),
+        }
+    }
+
+    /// Checks that the config file at _path_ can be read, the defaults are used while it's missing.
+    pub fn config_file(path: &Path) -> Self {
+        const NAME: &str = "Config file";
+
+        if !path.exists() {
+            return Self::ok(NAME, "Not created yet, the defaults are used");
+        }
+
+        match confy::load_path::<Config>(path) {
+            Ok(_) => Self::ok(NAME, path.display().to_string()),
+            Err(e) => Self::failed(
+                NAME,
+                format!("{} can't be loaded: {e}", path.display()),
+                format!(
+                    "Fix the error in {}, or delete it to start again with the defaults.",
+                    path.display()
+                ),
+            ),
+        }
+    }
+
+    /// Checks that the folders of the enabled libraries can be read.
+    pub fn libraries(cfg: &Config) -> Vec<Self> {
+        let libraries = cfg.libraries();
+
+        if libraries.is_empty() {
+            return vec![Self::failed(
+                "Libraries",
+                "There are no libraries",
+                "Add the folder with your series in the config.",
+            )];
+        }
+
+        libraries
+            .into_iter()
+            .filter(|library| library.enabled)
+            .map(|library| {
+                let name = format!("Library {}", library.name);
+
+                match fs::read_dir(&library.path) {
+                    Ok(_) => Self::ok(name, library.path.display().to_string()),
+                    Err(e) => Self::failed(
+                        name,
+                        format!("{} can't be read: {e}", library.path.display()),
+                        "Connect the drive of the library, fix its permissions or remove it in the config.",
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Every [`Check`][Check] of the external programs, folders and config [yama] needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    pub checks: Vec<Check>,
+}
+
+impl Diagnostics {
+    /// Runs every check, it blocks while the programs are launched to get their versions.
+    pub fn run(cfg: &Config) -> Self {
+        Self::in_config_dir(cfg, &CFG_PATH)
+    }
+
+    /// Runs every check like [`run`][Diagnostics::run], with the config of the _config_dir_ folder.
+    pub fn in_config_dir(cfg: &Config, config_dir: &Path) -> Self {
+        let mut checks: Vec<Check> = Dependency::all(&cfg.player)
+            .iter()
+            .map(Check::program)
+            .collect();
+
+        if cfg.player == PlayerConfig::Mpv {
+            if let Some(mpv) = checks
+                .iter()
+                .find(|check| check.name == "mpv" && check.status == CheckStatus::Ok)
+            {
+                checks.push(Check::mpv_ipc(&mpv.details));
+            }
+        }
+
+        checks.push(Check::folder(
+            "Config folder",
+            config_dir,
+            "its config, history and metadata",
+        ));
+        checks.push(Check::folder(
+            "Logs folder",
+            &config_dir.join("logs"),
+            "its logs",
+        ));
+        let config_file = Check::config_file(&config_dir.join("config.toml"));
+        let loaded = config_file.status != CheckStatus::Failed;
+        checks.push(config_file);
+
+        // The libraries of a config that can't be loaded are not the ones the user set.
+        if loaded {
+            checks.extend(Check::libraries(cfg));
+        }
+
+        Self { checks }
+    }
+
+    /// Returns `true` if no [`Check`][Check] failed.
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Failed)
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    /// A report with a check by line, followed by its remedy.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "[{}] {}: {}", check.status, check.name, check.details)?;
+
+            if let Some(remedy) = &check.remedy {
+                writeln!(f, "    -> {remedy}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the _major.minor_ version of a line like _mpv 0.35.1_ or _mpv v0.29.1-dirty_.
+fn parse_version(line: &str) -> Option<(u32, u32)> {
+    let version = line.split_whitespace().nth(1)?.trim_start_matches('v');
+    let mut parts = version.split(['.', '-']);
+
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
@@ -1,16 +1,30 @@
 use anyhow::{anyhow, bail};
 
 use crate::Result;
-use crate::{Backend, VideoMetadata};
+use crate::{EpisodeId, EpisodeKind, FileInfo, Playback, Player, Process, VideoMetadata};
 
 use core::fmt::Debug;
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{
+    fs,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Name of the file with the [`VideoMetadata`][VideoMetadata] inside each episode metadata folder.
+pub const METADATA_FILE: &str = "metadata.md";
 
 /// Contains all the information necessary to display an episode in [yama].
 #[derive(Debug)]
 pub struct Episode {
+    /// Position in its [`Title`][crate::Title], starting at 0, it's the index used everywhere else.
     pub number: u16,
+    /// Stable identifier of the video file, its metadata is stored under it.
+    pub id: EpisodeId,
     pub name: Arc<str>,
+    pub info: FileInfo,
+    /// Season of the [`Episode`][Episode], set by its [`Title`][crate::Title].
+    pub season: u16,
     pub metadata: VideoMetadata,
     pub thumbnail_path: PathBuf,
     pub metadata_path: PathBuf,
@@ -19,10 +33,13 @@ pub struct Episode {
 
 impl Episode {
     /// Creates a new [`Episode`][Episode] from the file specified by the _path_,
-    /// the episode number should also be specified.
+    /// the episode number and the metadata folder of its title should also be specified.
+    ///
+    /// Its metadata is stored in _episodes/{id}_, where _id_ is the [`EpisodeId`][EpisodeId]
+    /// of the file, so it follows the file when it's renamed or other episodes are added.
     ///
     /// If it returns an [`Error`][Error] then it's not a valid video file.
-    pub async fn new(path: &PathBuf, number: u16) -> Result<Episode> {
+    pub async fn new(path: &PathBuf, number: u16, metadata_dir: &Path) -> Result<Episode> {
         if !path.is_file() {
             bail!("The path {} is not a valid file.", path.display());
         }
@@ -35,56 +52,45 @@ impl Episode {
                 .unwrap_unchecked()
         });
 
-        // We know it has a parent folder, its the title folder.
-        let dir = path.parent().unwrap();
-
-        let md_folder = format!(".metadata/episode_{number}");
-        fs::create_dir_all(format!("{}/{}", dir.display(), &md_folder))?;
+        let id = EpisodeId::new(path)?;
+        let md_folder = metadata_dir.join("episodes").join(id.as_str());
+        fs::create_dir_all(&md_folder)?;
 
-        let metadata_path = dir.join(format!("{}/{}.md", &md_folder, name));
-        let thumbnail_path = dir.join(format!("{}/thumbnail.jpg", &md_folder));
+        let metadata_path = md_folder.join(METADATA_FILE);
+        let thumbnail_path = md_folder.join("thumbnail.jpg");
 
         if fs::metadata(&metadata_path).is_err() {
             let duration: f64 = ffprobe::ffprobe(path)
-                .map_err(
-                    |_| match fs::remove_dir(format!("{}/{}", dir.display(), &md_folder)) {
-                        Ok(_) => anyhow!("{} is not a valid video file.", path.display()),
-                        Err(e) => e.into(),
-                    },
-                )?
+                .map_err(|_| match fs::remove_dir(&md_folder) {
+                    Ok(_) => anyhow!("{} is not a valid video file.", path.display()),
+                    Err(e) => e.into(),
+                })?
                 .format
                 .get_duration()
-                .ok_or_else(
-                    || match fs::remove_dir(format!("{}/{}", dir.display(), &md_folder)) {
-                        Ok(_) => anyhow!("{} is not a valid video file.", path.display()),
-                        Err(e) => e.into(),
-                    },
-                )?
+                .ok_or_else(|| match fs::remove_dir(&md_folder) {
+                    Ok(_) => anyhow!("{} is not a valid video file.", path.display()),
+                    Err(e) => e.into(),
+                })?
                 .as_secs_f64();
 
             VideoMetadata::default_file(duration, &metadata_path)?
         }
 
         if fs::metadata(&thumbnail_path).is_err() {
-            let cmd = if cfg!(target_os = "windows") {
-                format!(
-                    "ffmpeg,-i,{},-vf,thumbnail,-frames:v,1,{},-f,mjpeg,-hide_banner,-nostdin,-nostats,-loglevel,quiet",
-                    path.display(),
-                    thumbnail_path.display(),
-                )
-            } else {
-                format!(
-                    "ffmpeg -i \"{}\" -vf \"thumbnail\" -frames:v 1 \"{}\" -f mjpeg -hide_banner -nostdin -nostats -loglevel quiet",
-                    path.display(),
-                    thumbnail_path.display(),
-                )
-            };
-
-            Backend::run_process(&cmd)?;
+            Process::new("ffmpeg")
+                .args(["-hide_banner", "-nostdin", "-nostats", "-loglevel", "error"])
+                .arg("-i")
+                .arg(path)
+                .args(["-vf", "thumbnail", "-frames:v", "1", "-f", "mjpeg"])
+                .arg(&thumbnail_path)
+                .run()?;
         }
 
         Ok(Episode {
             number,
+            id,
+            info: FileInfo::parse(&name),
+            season: 1,
             name,
             metadata: VideoMetadata::new(&metadata_path)?,
             thumbnail_path,
@@ -93,11 +99,11 @@ impl Episode {
         })
     }
 
-    /// Updates the [`VideoMetadata`][VideoMetadata] of this [`Episode`][Episode].
-    fn update(&mut self) -> Result<()> {
-        fs::rename(self.path.with_extension("md"), &self.metadata_path)?;
-        self.metadata = VideoMetadata::new(&self.metadata_path)?;
-        Ok(())
+    /// Updates the [`VideoMetadata`][VideoMetadata] of this [`Episode`][Episode] with the
+    /// final state of a [`Playback`][Playback] and saves it, see [`VideoMetadata::update`][VideoMetadata::update].
+    pub fn update(&mut self, playback: &Playback, min_time: f64) -> Result<()> {
+        self.metadata.update(playback, min_time);
+        VideoMetadata::create_file(&self.metadata, &self.metadata_path)
     }
 
     /// Marks the [`Episode`][Episode] as watched or not.
@@ -106,36 +112,40 @@ impl Episode {
         VideoMetadata::create_file(&self.metadata, &self.metadata_path)
     }
 
-    /// Runs the [`Episode`][Episode] in [mpv] on the current time,
+    /// Runs the [`Episode`][Episode] in the given [`Player`][Player] on the current time,
     /// or from the start if it has been already watched.
     ///
-    /// Returns [`Error`][Error] if [mpv] fails to launch it or
-    /// the [`VideoMetadata`][VideoMetadata] can not be updated.
-    pub fn run(&mut self) -> Result<()> {
-        let cmd = if cfg!(target_os = "windows") {
-            format!(
-                "--start={},{}",
-                if self.metadata.watched {
-                    0.00
-                } else {
-                    self.metadata.current
-                },
-                self.path.display()
-            )
+    /// The returned [`Future`][Future] doesn't borrow the [`Episode`][Episode], so it can be
+    /// spawned in the background, its [`Playback`][Playback] should be passed to [`Episode::update`].
+    pub fn play(
+        &self,
+        player: &dyn Player,
+    ) -> impl Future<Output = Result<Playback>> + Send + 'static {
+        let start = if self.metadata.watched {
+            0.00
         } else {
-            format!(
-                "--start={} \"{}\"",
-                if self.metadata.watched {
-                    0.00
-                } else {
-                    self.metadata.current
-                },
-                self.path.display()
-            )
+            self.metadata.current
         };
 
-        Backend::run_mpv(&cmd)?;
-        self.update()
+        player.play(&self.path, start)
+    }
+
+    /// Runs the [`Episode`][Episode] in the given [`Player`][Player] and waits until it's closed.
+    ///
+    /// Returns [`Error`][Error] if the [`Player`][Player] fails to launch it or
+    /// the [`VideoMetadata`][VideoMetadata] can not be updated.
+    pub async fn run(&mut self, player: &dyn Player, min_time: f64) -> Result<()> {
+        let playback = self.play(player).await?;
+        self.update(&playback, min_time)
+    }
+
+    /// Returns the number to show: the one in its file name, or its position if there's none,
+    /// so a missing episode doesn't renumber the ones after it.
+    pub fn shown_number(&self) -> f32 {
+        match (self.info.kind, self.info.episode) {
+            (EpisodeKind::Regular, Some(episode)) => episode,
+            _ => f32::from(self.number) + 1.0,
+        }
     }
 
     pub fn change_number(mut self, idx: usize) -> Self {
@@ -0,0 +1,58 @@
+use crate::Result;
+
+use core::fmt::{self, Debug, Display};
+use core::hash::Hasher;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use twox_hash::XxHash64;
+
+/// Bytes hashed from the start and the end of each file.
+const CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// Stable identifier of a video file that survives renames and re-sorting.
+///
+/// It's a hash of the file size with its first and last [`CHUNK_SIZE`][CHUNK_SIZE] bytes,
+/// so it only changes if the content of the file does, without reading whole videos.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct EpisodeId(Box<str>);
+
+impl EpisodeId {
+    /// Creates the [`EpisodeId`][EpisodeId] of the file specified by the _path_.
+    pub fn new(path: &Path) -> Result<EpisodeId> {
+        let mut file = File::open(path)?;
+        let size = file.metadata()?.len();
+
+        let mut hasher = XxHash64::with_seed(0);
+        hasher.write_u64(size);
+
+        let mut buffer = Vec::with_capacity(CHUNK_SIZE as usize);
+        file.by_ref().take(CHUNK_SIZE).read_to_end(&mut buffer)?;
+        hasher.write(&buffer);
+
+        if size > CHUNK_SIZE {
+            buffer.clear();
+            file.seek(SeekFrom::Start(
+                size.saturating_sub(CHUNK_SIZE).max(CHUNK_SIZE),
+            ))?;
+            file.take(CHUNK_SIZE).read_to_end(&mut buffer)?;
+            hasher.write(&buffer);
+        }
+
+        Ok(EpisodeId(
+            format!("{:016x}", hasher.finish()).into_boxed_str(),
+        ))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for EpisodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,359 @@
+use core::fmt::Debug;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::cmp::Ordering;
+
+static BRACKETS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\[\(\{【]([^\[\]\(\)\{\}【】]*)[\]\)\}】]").unwrap());
+static RESOLUTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^([0-9]{3,4}p|[0-9]{3,4}x[0-9]{3,4}|4k|uhd|fhd)$").unwrap());
+static CRC: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[0-9A-Fa-f]{8}$").unwrap());
+static SEASON_EPISODE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^S([0-9]{1,2})[ ._-]?E([0-9]{1,4}(?:\.[0-9])?)(?:v([0-9]))?$").unwrap()
+});
+static SEASON_X_EPISODE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^([0-9]{1,2})x([0-9]{1,3})(?:v([0-9]))?$").unwrap());
+static SEASON: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^S([0-9]{1,2})$").unwrap());
+static ORDINAL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^([0-9]{1,2})(?:st|nd|rd|th)$").unwrap());
+static PREFIXED_EPISODE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:E|EP|Ep\.|#)([0-9]{1,4}(?:\.[0-9])?)(?:v([0-9]))?$").unwrap()
+});
+static NUMBER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^([0-9]{1,4}(?:\.[0-9])?)(?:v([0-9]))?$").unwrap());
+static RANGE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^([0-9]{1,4})(?:v[0-9])?[-~]([0-9]{1,4})(?:v[0-9])?$").unwrap());
+static EXTRA: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(NCOP|NCED|OP|ED|OVA|OAD|SP|Specials?|Extras?|Bonus|Omake|Preview|PV|Trailer|CM)([0-9]{0,3})(?:v([0-9]))?$")
+        .unwrap()
+});
+
+/// Kind of video file, in the order they should be listed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EpisodeKind {
+    #[default]
+    Regular,
+    Special,
+    Ova,
+    Opening,
+    Ending,
+    /// Previews, trailers and other extras.
+    Extra,
+}
+
+impl EpisodeKind {
+    /// Returns the [`EpisodeKind`][EpisodeKind] for an extra keyword like `NCOP` or `OVA`.
+    fn from_keyword(keyword: &str) -> Self {
+        match keyword.to_ascii_lowercase().as_str() {
+            "ncop" | "op" => Self::Opening,
+            "nced" | "ed" => Self::Ending,
+            "ova" | "oad" => Self::Ova,
+            "sp" | "special" | "specials" => Self::Special,
+            _ => Self::Extra,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Regular => "Episode",
+            Self::Special => "Special",
+            Self::Ova => "OVA",
+            Self::Opening => "Opening",
+            Self::Ending => "Ending",
+            Self::Extra => "Extra",
+        }
+    }
+}
+
+/// Information found in the name of a video file, like [anitomy] does.
+///
+/// [anitomy]: https://github.com/erengy/anitomy
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileInfo {
+    /// Title of the series, as written in the file name.
+    pub title: Option<String>,
+    /// Release group, usually between brackets at the start of the name.
+    pub group: Option<String>,
+    pub season: Option<u16>,
+    pub episode: Option<f32>,
+    /// Last episode of a batch file, like `01-12`.
+    pub episode_end: Option<f32>,
+    pub version: Option<u8>,
+    pub resolution: Option<String>,
+    pub kind: EpisodeKind,
+}
+
+/// Where the episode number was found, explicit ones win over guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Confidence {
+    /// A number in the middle of the name.
+    Guess,
+    /// A number at the start of the name, like `01 - Episode Title`.
+    Leading,
+    /// A number after a dash, like `Title - 01`.
+    AfterDash,
+    /// A number with a prefix, like `E01` or `S01E01`.
+    Explicit,
+}
+
+impl FileInfo {
+    /// Parses a file name, it should be given without its extension.
+    pub fn parse(name: &str) -> Self {
+        let mut info = FileInfo::default();
+
+        // Tags between brackets: group, resolution, checksum, etc.
+        let mut rest = String::with_capacity(name.len());
+        let mut last = 0;
+
+        for (i, caps) in BRACKETS.captures_iter(name).enumerate() {
+            let tag = caps.get(0).unwrap();
+            let inner = caps[1].trim();
+
+            rest.push_str(&name[last..tag.start()]);
+            rest.push_str(" | ");
+            last = tag.end();
+
+            let is_first = i == 0 && name[..tag.start()].trim().is_empty();
+            let mut is_metadata = false;
+
+            for word in inner.split(|c: char| c.is_whitespace() || c == ',' || c == '_') {
+                if RESOLUTION.is_match(word) {
+                    info.resolution.get_or_insert_with(|| word.to_string());
+                    is_metadata = true;
+                } else if CRC.is_match(word) {
+                    is_metadata = true;
+                } else if let Some(caps) = EXTRA.captures(word) {
+                    info.kind = EpisodeKind::from_keyword(&caps[1]);
+                    is_metadata = true;
+                }
+            }
+
+            if is_first && !is_metadata && !inner.is_empty() && info.group.is_none() {
+                info.group = Some(inner.to_string());
+            }
+        }
+
+        rest.push_str(&name[last..]);
+
+        // Dots and underscores are used as spaces when there are no spaces.
+        let rest = if BRACKETS.replace_all(name, "").trim().contains(' ') {
+            rest.replace('_', " ")
+        } else {
+            rest.replace(['_', '.'], " ")
+        };
+
+        let words: Vec<&str> = rest.split_whitespace().collect();
+
+        let mut found: Option<(Confidence, usize, f32)> = None;
+        let mut title_end = words.len();
+
+        let mut set_episode = |info: &mut FileInfo,
+                               confidence: Confidence,
+                               idx: usize,
+                               episode: f32,
+                               version: Option<&str>| {
+            if found.is_none_or(|(c, _, _)| confidence > c)
+                || found.is_some_and(|(c, _, _)| confidence == c && c == Confidence::Guess)
+            {
+                found = Some((confidence, idx, episode));
+                info.episode = Some(episode);
+                info.version = version.and_then(|v| v.parse().ok());
+            }
+        };
+
+        let mut i = 0;
+        while i < words.len() {
+            let word = words[i].trim_matches(|c: char| c == ',' || c == '&');
+            let next = words.get(i + 1).copied();
+            let after_dash = i > 0 && matches!(words[i - 1], "-" | "–" | "~");
+
+            if let Some(caps) = SEASON_EPISODE.captures(word) {
+                info.season = caps[1].parse().ok();
+                let episode = caps[2].parse().unwrap_or_default();
+                set_episode(
+                    &mut info,
+                    Confidence::Explicit,
+                    i,
+                    episode,
+                    caps.get(3).map(|v| v.as_str()),
+                );
+                title_end = title_end.min(i);
+            } else if let Some(caps) = SEASON_X_EPISODE.captures(word) {
+                info.season = caps[1].parse().ok();
+                let episode = caps[2].parse().unwrap_or_default();
+                set_episode(
+                    &mut info,
+                    Confidence::Explicit,
+                    i,
+                    episode,
+                    caps.get(3).map(|v| v.as_str()),
+                );
+                title_end = title_end.min(i);
+            } else if let Some(caps) = SEASON.captures(word) {
+                info.season = caps[1].parse().ok();
+                title_end = title_end.min(i);
+            } else if word.eq_ignore_ascii_case("season") || word.eq_ignore_ascii_case("saison") {
+                if let Some(season) = next.and_then(|n| n.parse().ok()) {
+                    info.season = Some(season);
+                    title_end = title_end.min(i);
+                    i += 1;
+                } else if let Some(caps) = i.checked_sub(1).and_then(|p| ORDINAL.captures(words[p]))
+                {
+                    info.season = caps[1].parse().ok();
+                    title_end = title_end.min(i - 1);
+                }
+            } else if word.eq_ignore_ascii_case("episode") || word.eq_ignore_ascii_case("ep") {
+                if let Some(caps) = next.and_then(|n| NUMBER.captures(n)) {
+                    let episode = caps[1].parse().unwrap_or_default();
+                    set_episode(
+                        &mut info,
+                        Confidence::Explicit,
+                        i + 1,
+                        episode,
+                        caps.get(2).map(|v| v.as_str()),
+                    );
+                    title_end = title_end.min(i);
+                    i += 1;
+                }
+            } else if let Some(caps) = PREFIXED_EPISODE.captures(word) {
+                let episode = caps[1].parse().unwrap_or_default();
+                set_episode(
+                    &mut info,
+                    Confidence::Explicit,
+                    i,
+                    episode,
+                    caps.get(2).map(|v| v.as_str()),
+                );
+                title_end = title_end.min(i);
+            } else if let Some(caps) = EXTRA.captures(word) {
+                info.kind = EpisodeKind::from_keyword(&caps[1]);
+                if let Ok(episode) = caps[2].parse() {
+                    set_episode(
+                        &mut info,
+                        Confidence::Explicit,
+                        i,
+                        episode,
+                        caps.get(3).map(|v| v.as_str()),
+                    );
+                }
+                title_end = title_end.min(i);
+            } else if word.eq_ignore_ascii_case("creditless") {
+                match next.map(|n| n.to_ascii_lowercase()).as_deref() {
+                    Some("opening") => info.kind = EpisodeKind::Opening,
+                    Some("ending") => info.kind = EpisodeKind::Ending,
+                    _ => (),
+                }
+                title_end = title_end.min(i);
+            } else if RESOLUTION.is_match(word) {
+                info.resolution.get_or_insert_with(|| word.to_string());
+                title_end = title_end.min(i);
+            } else if let Some(caps) = RANGE.captures(word) {
+                let confidence = if after_dash {
+                    Confidence::AfterDash
+                } else {
+                    Confidence::Guess
+                };
+                let episode = caps[1].parse().unwrap_or_default();
+                set_episode(&mut info, confidence, i, episode, None);
+                info.episode_end = caps[2].parse().ok();
+                if after_dash {
+                    title_end = title_end.min(i - 1);
+                }
+            } else if let Some(caps) = NUMBER.captures(word) {
+                let is_year = word.len() == 4 && (word.starts_with("19") || word.starts_with("20"));
+                let is_leading = words[..i].iter().all(|w| *w == "|");
+
+                let confidence = if after_dash {
+                    title_end = title_end.min(i - 1);
+                    Some(Confidence::AfterDash)
+                } else if is_leading {
+                    Some(Confidence::Leading)
+                } else if !is_year {
+                    Some(Confidence::Guess)
+                } else {
+                    None
+                };
+
+                if let Some(confidence) = confidence {
+                    let episode = caps[1].parse().unwrap_or_default();
+                    let version = caps.get(2).map(|v| v.as_str());
+                    set_episode(&mut info, confidence, i, episode, version);
+                }
+            } else if word == "|" && words[..i].iter().any(|w| *w != "|") {
+                // Tags between brackets end the title.
+                title_end = title_end.min(i);
+            }
+
+            i += 1;
+        }
+
+        if let Some((Confidence::Guess | Confidence::Leading, idx, _)) = found {
+            title_end = title_end.min(idx);
+        }
+
+        if info.episode.is_some() && info.episode_end.is_some() && info.episode_end <= info.episode
+        {
+            info.episode_end = None;
+        }
+
+        let title = words[..title_end]
+            .iter()
+            .copied()
+            .filter(|w| *w != "|")
+            .collect::<Vec<_>>()
+            .join(" ");
+        let title = title.trim_matches(|c: char| c.is_whitespace() || c == '-' || c == '|');
+
+        if !title.is_empty() {
+            info.title = Some(title.to_string());
+        }
+
+        info
+    }
+
+    /// Compares two [`FileInfos`][FileInfo] in the order they should be listed:
+    /// by kind, season, episode and version. Files without an episode number go last.
+    pub fn cmp_order(&self, other: &Self) -> Ordering {
+        self.kind
+            .cmp(&other.kind)
+            .then(self.season.unwrap_or(1).cmp(&other.season.unwrap_or(1)))
+            .then(match (self.episode, other.episode) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then(self.version.unwrap_or(1).cmp(&other.version.unwrap_or(1)))
+    }
+
+    /// Formats the [`FileInfo`][FileInfo] into a pretty [`str`][str], only with the values found.
+    pub fn to_str(&self) -> Box<str> {
+        let mut lines = Vec::new();
+
+        match (self.episode, self.episode_end) {
+            (Some(start), Some(end)) => {
+                lines.push(format!("{}: {start}-{end}", self.kind.to_str()))
+            }
+            (Some(episode), None) => lines.push(format!("{}: {episode}", self.kind.to_str())),
+            (None, _) if self.kind != EpisodeKind::Regular => {
+                lines.push(self.kind.to_str().to_string())
+            }
+            (None, _) => (),
+        }
+
+        if let Some(season) = self.season {
+            lines.push(format!("Season: {season}"));
+        }
+        if let Some(version) = self.version {
+            lines.push(format!("Version: {version}"));
+        }
+        if let Some(resolution) = &self.resolution {
+            lines.push(format!("Resolution: {resolution}"));
+        }
+        if let Some(group) = &self.group {
+            lines.push(format!("Group: {group}"));
+        }
+
+        lines.join("\n").into_boxed_str()
+    }
+}
@@ -0,0 +1,184 @@
+use crate::config::CFG_PATH;
+use crate::{Episode, Playback, Result};
+
+use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// A single playback of an [`Episode`][Episode], as recorded in the [`History`][History].
+/// Serialized for easy parsing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub title: String,
+    pub episode: String,
+    /// Unix time, in seconds, when the playback started.
+    pub started_at: u64,
+    /// Unix time, in seconds, when the playback ended.
+    pub ended_at: u64,
+    /// Position, in seconds, where the playback started.
+    pub start: f64,
+    /// Position, in seconds, where the playback ended, if the player reported it.
+    pub end: Option<f64>,
+    /// The episode was watched until the end.
+    pub completed: bool,
+}
+
+impl HistoryEntry {
+    /// Creates a new [`HistoryEntry`][HistoryEntry] of a playback that started at _started_ and ends now.
+    ///
+    /// The _episode_ should be already updated with the final state of the _playback_.
+    pub fn new(title: &str, episode: &Episode, started: SystemTime, playback: &Playback) -> Self {
+        Self {
+            title: title.to_string(),
+            episode: episode.name.to_string(),
+            started_at: unix_time(started),
+            ended_at: unix_time(SystemTime::now()),
+            start: playback.start,
+            end: playback.position,
+            completed: episode.metadata.watched,
+        }
+    }
+
+    /// Returns the UTC date and time when the playback started, like _2023-09-01 21:30_.
+    pub fn date(&self) -> String {
+        let days = (self.started_at / 86_400) as i64;
+        let minutes = self.started_at % 86_400 / 60;
+
+        // Days since 1970-01-01 to a civil date, from Howard Hinnant's date algorithms.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        format!(
+            "{year}-{month:02}-{day:02} {:02}:{:02}",
+            minutes / 60,
+            minutes % 60
+        )
+    }
+}
+
+/// Formats to export the [`History`][History] to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    Json,
+    Csv,
+}
+
+impl HistoryFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            HistoryFormat::Json => "json",
+            HistoryFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Append-only log of every playback, stored with one json-formatted [`HistoryEntry`][HistoryEntry] per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl Default for History {
+    /// The _history.jsonl_ file in the config folder.
+    fn default() -> Self {
+        Self::new(CFG_PATH.join("history.jsonl"))
+    }
+}
+
+impl History {
+    /// Creates a new [`History`][History] stored at _path_, the file is created by the first [`append`][History::append].
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Adds the _entry_ at the end of the [`History`][History].
+    pub fn append(&self, entry: &HistoryEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        Ok(writeln!(file, "{}", serde_json::to_string(entry)?)?)
+    }
+
+    /// Returns every [`HistoryEntry`][HistoryEntry], the oldest first.
+    ///
+    /// Lines that can not be parsed, like one cut by a crash, are skipped.
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!("Skipped a history entry: {e}");
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Writes every [`HistoryEntry`][HistoryEntry] to the file at _path_ in the given _format_.
+    pub fn export(&self, path: &Path, format: HistoryFormat) -> Result<()> {
+        let entries = self.entries()?;
+
+        let content = match format {
+            HistoryFormat::Json => serde_json::to_string_pretty(&entries)?,
+            HistoryFormat::Csv => {
+                let mut csv =
+                    String::from("title,episode,started_at,ended_at,start,end,completed\n");
+
+                for e in &entries {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{:.1},{},{}\n",
+                        csv_field(&e.title),
+                        csv_field(&e.episode),
+                        e.started_at,
+                        e.ended_at,
+                        e.start,
+                        e.end.map(|end| format!("{end:.1}")).unwrap_or_default(),
+                        e.completed
+                    ));
+                }
+
+                csv
+            }
+        };
+
+        Ok(fs::write(path, content)?)
+    }
+}
+
+/// Quotes a CSV field if it has commas, quotes or line breaks.
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
@@ -0,0 +1,70 @@
+use crate::Result;
+
+use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A named folder with [`Titles`][crate::Title], like an internal drive, a USB drive or a NAS mount.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Library {
+    pub name: String,
+    pub path: PathBuf,
+    /// Disabled libraries are not scanned.
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Library {
+    /// Creates a new enabled [`Library`][Library].
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            enabled: true,
+        }
+    }
+
+    /// Path of the file with the known [`Titles`][crate::Title] of the [`Library`][Library].
+    fn index_path(&self, config_dir: &Path) -> PathBuf {
+        config_dir
+            .join("libraries")
+            .join(format!("{}.json", sanitize(&self.name)))
+    }
+
+    /// Saves the title folders found in the [`Library`][Library] in the _config_dir_ folder,
+    /// so they can still be listed while it's offline.
+    pub fn save_index(&self, config_dir: &Path, titles: &[PathBuf]) -> Result<()> {
+        let relative: Vec<&Path> = titles
+            .iter()
+            .filter_map(|path| path.strip_prefix(&self.path).ok())
+            .collect();
+
+        let path = self.index_path(config_dir);
+        fs::create_dir_all(path.parent().unwrap())?;
+        Ok(fs::write(path, serde_json::to_string_pretty(&relative)?)?)
+    }
+
+    /// Returns the title folders found the last time the [`Library`][Library] was available.
+    pub fn load_index(&self, config_dir: &Path) -> Result<Vec<PathBuf>> {
+        let relative: Vec<PathBuf> =
+            serde_json::from_str(&fs::read_to_string(self.index_path(config_dir))?)?;
+        Ok(relative.iter().map(|path| self.path.join(path)).collect())
+    }
+}
+
+/// Replaces the characters that can not be used on file names.
+pub(crate) fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect()
+}
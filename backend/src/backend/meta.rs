@@ -1,4 +1,5 @@
 use crate::Episode;
+use crate::Season;
 use crate::Title;
 
 use std::path::Path;
@@ -7,6 +8,7 @@ use std::sync::Arc;
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum MetaType {
     Title,
+    Season,
     Episode,
 }
 
@@ -26,31 +28,29 @@ pub trait Meta {
 
 impl Meta for Title {
     fn thumbnail(&self) -> Option<Arc<Path>> {
-        if let Some(data) = self.data.as_ref() {
-            return Some(Arc::from(data.thumbnail_path.as_path()));
-        }
-
-        None
+        let thumbnail = self.info.as_ref()?.images.thumbnail.as_ref()?;
+        Some(Arc::from(thumbnail.as_path()))
     }
 
     fn description(&self) -> Arc<str> {
-        if let Some(data) = &self.data {
-            return Arc::from(format!(
-                "{}\n\nStudio: {}",
-                data.media.to_str(),
-                data.studio
-            ));
+        let mut description = match &self.info {
+            Some(info) => info.to_str().into_string(),
+            None => String::from("No description found..."),
+        };
+
+        if let Some(library) = &self.library {
+            description.push_str(&format!("\nLibrary: {library}"));
         }
 
-        Arc::from("No description found...")
+        if self.offline {
+            description.push_str("\n\nOffline: its library is not available.");
+        }
+
+        Arc::from(description)
     }
 
     fn title(&self) -> Arc<str> {
-        if let Some(data) = &self.data {
-            return Arc::from(data.media.title.english.clone());
-        }
-
-        self.name.clone()
+        self.display_name()
     }
 
     fn mtype(&self) -> MetaType {
@@ -64,7 +64,13 @@ impl Meta for Episode {
     }
 
     fn description(&self) -> Arc<str> {
-        Arc::from(self.metadata.to_str())
+        let info = self.info.to_str();
+
+        if info.is_empty() {
+            return Arc::from(self.metadata.to_str());
+        }
+
+        Arc::from(format!("{}\n{}", self.metadata.to_str(), info))
     }
 
     fn title(&self) -> Arc<str> {
@@ -75,3 +81,21 @@ impl Meta for Episode {
         MetaType::Episode
     }
 }
+
+impl Meta for Season {
+    fn thumbnail(&self) -> Option<Arc<Path>> {
+        Some(Arc::from(self.thumbnail_path.as_path()))
+    }
+
+    fn description(&self) -> Arc<str> {
+        Arc::from(format!("Episodes: {}", self.episodes.len()))
+    }
+
+    fn title(&self) -> Arc<str> {
+        self.name.clone()
+    }
+
+    fn mtype(&self) -> MetaType {
+        MetaType::Season
+    }
+}
@@ -0,0 +1,79 @@
+use crate::Result;
+
+use core::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Where the metadata of each [`Title`][crate::Title] is stored: [`VideoMetadata`][crate::VideoMetadata],
+/// [`SeriesInfo`][crate::SeriesInfo] downloads, thumbnails and settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MetadataStore {
+    /// A hidden _.metadata_ folder inside each title.
+    #[default]
+    Local,
+    /// A single folder outside of the _library_, for read-only or shared series folders.
+    /// Each title is stored by its path relative to the _library_.
+    Central { root: PathBuf, library: PathBuf },
+}
+
+impl MetadataStore {
+    /// Returns the folder with the metadata of the title specified by the _path_.
+    pub fn title_dir(&self, path: &Path) -> PathBuf {
+        match self {
+            MetadataStore::Local => path.join(".metadata"),
+            MetadataStore::Central { root, library } => match path.strip_prefix(library) {
+                Ok(relative) => root.join(relative),
+                Err(_) => root.join(path.file_name().unwrap_or_default()),
+            },
+        }
+    }
+
+    /// Imports the _.metadata_ folder of the title specified by the _path_ into the central store.
+    ///
+    /// It's only done once, when the title is not in the store yet. The _.metadata_ folder is copied,
+    /// not moved, so it also works on read-only folders.
+    /// Returns `true` if something was imported.
+    pub fn import(&self, path: &Path) -> Result<bool> {
+        let MetadataStore::Central { .. } = self else {
+            return Ok(false);
+        };
+
+        let local = path.join(".metadata");
+        let central = self.title_dir(path);
+
+        if central.exists() || !local.is_dir() {
+            return Ok(false);
+        }
+
+        // Copied to a temporary folder first, so a failed import is retried the next time.
+        let mut partial = central.clone().into_os_string();
+        partial.push(".importing");
+        let partial = PathBuf::from(partial);
+        if partial.exists() {
+            fs::remove_dir_all(&partial)?;
+        }
+
+        copy_dir(&local, &partial)?;
+        fs::rename(&partial, &central)?;
+
+        info!("Imported {} into {}", local.display(), central.display());
+        Ok(true)
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)?.flatten() {
+        let target = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use crate::Result;
+
+use anyhow::{anyhow, bail};
+use core::fmt::Debug;
+use std::ffi::{OsStr, OsString};
+use std::process::{ExitStatus, Output, Stdio};
+use tokio::io::AsyncReadExt;
+use tokio::task::JoinHandle;
+
+/// Only the end of the _stderr_ is kept, that's where the reason of the failure usually is.
+const STDERR_LIMIT: usize = 4096;
+
+/// Windows flag to avoid opening a console window for each process.
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// A secondary process built argument by argument.
+///
+/// Each argument is passed as is to the program, it never goes through a shell,
+/// so paths with quotes, `$` or backticks are safe to use.
+#[derive(Debug, Clone)]
+pub struct Process {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl Process {
+    /// Creates a new [`Process`][Process] for the given _program_.
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            program: program.as_ref().to_owned(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Adds an argument to the [`Process`][Process].
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    /// Adds multiple arguments to the [`Process`][Process].
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_owned()));
+        self
+    }
+
+    /// Returns the name of the program to be used on errors.
+    pub fn program(&self) -> String {
+        self.program.to_string_lossy().into_owned()
+    }
+
+    /// Runs the [`Process`][Process] and blocks until it finishes.
+    ///
+    /// Returns [`Error`][anyhow::Error] with the process _stderr_ if it fails.
+    pub fn run(&self) -> Result<Output> {
+        let mut cmd = std::process::Command::new(&self.program);
+        cmd.args(&self.args).stdin(Stdio::null());
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| anyhow!("Could not launch {}: {e}", self.program()))?;
+
+        check(&self.program(), output.status, &output.stderr)?;
+        Ok(output)
+    }
+
+    /// Spawns the [`Process`][Process] in the background, its _stderr_ is collected
+    /// while it runs to be reported if it fails.
+    pub fn spawn(&self) -> Result<Running> {
+        let mut cmd = tokio::process::Command::new(&self.program);
+        cmd.args(&self.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow!("Could not launch {}: {e}", self.program()))?;
+
+        let stderr = child.stderr.take().map(|mut stderr| {
+            tokio::spawn(async move {
+                let mut buffer = Vec::new();
+                let mut chunk = [0; 1024];
+
+                while let Ok(n @ 1..) = stderr.read(&mut chunk).await {
+                    buffer.extend_from_slice(&chunk[..n]);
+                    buffer.drain(..buffer.len().saturating_sub(STDERR_LIMIT));
+                }
+
+                buffer
+            })
+        });
+
+        Ok(Running {
+            program: self.program(),
+            child,
+            stderr,
+        })
+    }
+}
+
+/// A [`Process`][Process] running in the background.
+#[derive(Debug)]
+pub struct Running {
+    program: String,
+    child: tokio::process::Child,
+    stderr: Option<JoinHandle<Vec<u8>>>,
+}
+
+impl Running {
+    /// Returns the [`ExitStatus`][ExitStatus] if the process already finished, without blocking.
+    pub fn try_wait(&mut self) -> Result<Option<ExitStatus>> {
+        Ok(self.child.try_wait()?)
+    }
+
+    /// Waits until the process finishes.
+    ///
+    /// Returns [`Error`][anyhow::Error] with the process _stderr_ if it fails.
+    pub async fn wait(mut self) -> Result<()> {
+        let status = self.child.wait().await?;
+
+        let stderr = match self.stderr {
+            Some(stderr) => stderr.await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        check(&self.program, status, &stderr)
+    }
+}
+
+/// Returns [`Error`][anyhow::Error] with the end of the _stderr_ if the process failed.
+fn check(program: &str, status: ExitStatus, stderr: &[u8]) -> Result<()> {
+    if status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&stderr[stderr.len().saturating_sub(STDERR_LIMIT)..]);
+    let stderr = stderr.trim();
+
+    if stderr.is_empty() {
+        bail!("{program} exited with {status}");
+    }
+
+    bail!("{program} exited with {status}: {stderr}")
+}
@@ -0,0 +1,72 @@
+use crate::{EpisodeId, Result};
+
+use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// How many [`Episodes`][crate::Episode] a [`Title`][crate::Title] has and how many were watched,
+/// saved so it's known before they are loaded. Serialized for easy parsing.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub episodes: u16,
+    pub watched: u16,
+    /// Unix time, in seconds, of the last time one of its episodes was watched.
+    #[serde(default)]
+    pub last_watched: Option<u64>,
+    /// The [`Episode`][crate::Episode] to continue watching, see [`next_episode`][next_episode].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<NextEpisode>,
+}
+
+/// An [`Episode`][crate::Episode] to continue watching.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct NextEpisode {
+    /// Position of the episode when it was saved, the files may have changed since then.
+    pub number: u16,
+    /// Stable identifier of the episode, to find it again once the episodes are loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<EpisodeId>,
+    pub name: String,
+    /// Seconds already watched, zero if it was never started.
+    pub current: u32,
+}
+
+impl Progress {
+    /// Creates a new [`Progress`][Progress] from a json-formatted file at _path_.
+    pub fn new(path: &Path) -> Result<Progress> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Saves the [`Progress`][Progress] as a json-formatted file to the referenced _path_.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(fs::write(path, serde_json::to_string(self)?)?)
+    }
+
+    /// Number of episodes not watched yet.
+    pub fn unwatched(&self) -> u16 {
+        self.episodes.saturating_sub(self.watched)
+    }
+}
+
+/// Returns the index of the episode to continue watching, given if each episode was watched
+/// and the last time it was.
+///
+/// It's the last episode watched if it was left halfway, or the next one not watched yet after it.
+/// Titles never watched have none.
+pub fn next_episode(episodes: impl IntoIterator<Item = (bool, Option<u64>)>) -> Option<usize> {
+    let episodes: Vec<_> = episodes.into_iter().collect();
+
+    let (last, _) = episodes
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &(_, last_watched))| Some((idx, last_watched?)))
+        .max_by_key(|&(_, last_watched)| last_watched)?;
+
+    match episodes[last] {
+        (false, _) => Some(last),
+        (true, _) => episodes[last + 1..]
+            .iter()
+            .position(|&(watched, _)| !watched)
+            .map(|idx| last + 1 + idx),
+    }
+}
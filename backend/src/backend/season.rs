@@ -0,0 +1,81 @@
+use crate::{Episode, EpisodeKind, FileInfo};
+
+use core::fmt::Debug;
+use core::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Season number used for specials, OVAs and other extras.
+pub const SPECIALS: u16 = 0;
+
+/// A group of consecutive [`Episodes`][Episode] of a [`Title`][crate::Title].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Season {
+    pub number: u16,
+    pub name: Arc<str>,
+    /// Indexes of its [`Episodes`][Episode] in the [`Title`][crate::Title] list.
+    pub episodes: Range<usize>,
+    /// Thumbnail of its first [`Episode`][Episode].
+    pub thumbnail_path: PathBuf,
+}
+
+impl Season {
+    /// Returns the season number of a folder name like `Season 2`, `S02` or `Specials`.
+    pub fn from_folder(name: &str) -> Option<u16> {
+        let info = FileInfo::parse(name);
+
+        match info.kind {
+            EpisodeKind::Regular => info.season,
+            _ => Some(SPECIALS),
+        }
+    }
+
+    /// Returns the season number of the [`Episode`][Episode], from the closest season folder
+    /// between it and the _title_ folder or from its file name.
+    /// Files without a season are part of the first one.
+    pub fn number_of(title: &Path, episode: &Episode) -> u16 {
+        let folder = episode
+            .path
+            .parent()
+            .and_then(|parent| parent.strip_prefix(title).ok())
+            .and_then(|relative| {
+                relative
+                    .components()
+                    .rev()
+                    .find_map(|folder| Self::from_folder(&folder.as_os_str().to_string_lossy()))
+            });
+
+        match (folder, episode.info.kind) {
+            (Some(season), _) => season,
+            (None, EpisodeKind::Regular) => episode.info.season.unwrap_or(1),
+            (None, _) => SPECIALS,
+        }
+    }
+
+    /// Compares two season numbers in the order they should be listed, specials go last.
+    pub fn cmp_number(a: u16, b: u16) -> std::cmp::Ordering {
+        (a == SPECIALS, a).cmp(&(b == SPECIALS, b))
+    }
+
+    /// Groups a list of [`Episodes`][Episode], already sorted by season, into [`Seasons`][Season].
+    pub fn group(episodes: &[Episode]) -> Vec<Season> {
+        let mut seasons: Vec<Season> = Vec::new();
+
+        for (idx, episode) in episodes.iter().enumerate() {
+            match seasons.last_mut() {
+                Some(season) if season.number == episode.season => season.episodes.end = idx + 1,
+                _ => seasons.push(Season {
+                    number: episode.season,
+                    name: Arc::from(match episode.season {
+                        SPECIALS => "Specials".to_string(),
+                        number => format!("Season {number}"),
+                    }),
+                    episodes: idx..idx + 1,
+                    thumbnail_path: episode.thumbnail_path.clone(),
+                }),
+            }
+        }
+
+        seasons
+    }
+}
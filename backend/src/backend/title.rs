@@ -1,11 +1,20 @@
-use crate::networking::anilist::Data;
+use crate::backend::episode::METADATA_FILE;
+use crate::backend::progress::{self, NextEpisode};
 use crate::Result;
-use crate::{Backend, Episode};
+use crate::{Backend, Episode, EpisodeId, MetadataProvider, MetadataStore, Season, SeriesInfo};
+use crate::{Progress, TitleSettings, WatchStatus};
 
 use anyhow::bail;
 use core::fmt::Debug;
-use std::{fs, path::PathBuf, sync::Arc};
-use tracing::error;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{error, warn};
+
+/// How deep the subfolders of a title are scanned for episodes, like _Season 1/Extras_.
+const MAX_DEPTH: usize = 2;
 
 /// Contains all the information necessary to display a title in [yama].
 #[derive(Debug)]
@@ -13,18 +22,34 @@ pub struct Title {
     /// Number of [`episodes`][Episode] this [`Title`][Title] has.
     pub count: u16,
     pub name: Arc<str>,
-    /// [Metadata] of this [`Title`][Title],
-    pub data: Option<Data>,
+    /// Information found by a [`MetadataProvider`][crate::MetadataProvider].
+    pub info: Option<SeriesInfo>,
     pub episodes: Option<Vec<Episode>>,
+    /// [`Seasons`][Season] of the loaded [`Episodes`][Episode], in the same order.
+    pub seasons: Vec<Season>,
+    /// Settings that override the [`Config`][crate::Config] ones for this [`Title`][Title].
+    pub settings: TitleSettings,
+    /// [`Progress`][Progress] of the last time its [`Episodes`][Episode] were loaded, if they ever were.
+    pub progress: Option<Progress>,
     episodes_cache: Option<Arc<[Arc<str>]>>,
     pub path: PathBuf,
+    /// Name of the [`Library`][crate::Library] this [`Title`][Title] was found in.
+    pub library: Option<Arc<str>>,
+    /// The [`Library`][crate::Library] of this [`Title`][Title] is not available,
+    /// its [`Episodes`][Episode] can not be loaded.
+    pub offline: bool,
+    /// Folder with the metadata of this [`Title`][Title], see [`MetadataStore`][MetadataStore].
+    pub metadata_dir: PathBuf,
+    /// Unix time, in seconds, of the last change of its folder, like adding an episode.
+    pub added: Option<u64>,
 }
 
 impl Title {
     /// Creates a new [`Title`][Title] for the folder specified by the _path_,
+    /// its metadata is kept in the given [`MetadataStore`][MetadataStore].
     ///
     /// If it returns an [`Error`][Error] then it's not a valid folder.
-    pub fn new(path: PathBuf) -> Result<Title> {
+    pub fn new(path: PathBuf, store: &MetadataStore) -> Result<Title> {
         if !path.is_dir() {
             bail!("The path {} is not a valid folder.", path.display());
         }
@@ -37,27 +62,155 @@ impl Title {
                 .unwrap_unchecked()
         });
 
-        fs::create_dir_all(format!("{}/.metadata/", path.display()))?;
+        if let Err(e) = store.import(&path) {
+            warn!("Could not import the metadata of {}: {e}", path.display());
+        }
+
+        let metadata_dir = store.title_dir(&path);
+        fs::create_dir_all(&metadata_dir)?;
+
+        let settings =
+            TitleSettings::new(&metadata_dir.join("settings.json")).unwrap_or_else(|e| {
+                warn!("Invalid settings for {}: {e}", path.display());
+                TitleSettings::default()
+            });
+
+        let progress = Progress::new(&metadata_dir.join("progress.json")).ok();
+        let added = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|time| time.as_secs());
 
         Ok(Title {
+            added,
             name,
+            settings,
+            progress,
             path,
+            metadata_dir,
+            library: None,
+            offline: false,
             count: 0,
-            data: None,
+            info: None,
             episodes: None,
+            seasons: Vec::new(),
             episodes_cache: None,
         })
     }
 
+    /// Creates a new offline [`Title`][Title] for a folder that can not be reached,
+    /// it only uses the metadata that is still available.
+    pub fn offline(path: PathBuf, store: &MetadataStore) -> Title {
+        let name = Arc::from(path.file_name().unwrap_or_default().to_string_lossy());
+        let metadata_dir = store.title_dir(&path);
+        let settings = TitleSettings::new(&metadata_dir.join("settings.json")).unwrap_or_default();
+        let progress = Progress::new(&metadata_dir.join("progress.json")).ok();
+
+        Title {
+            added: None,
+            name,
+            settings,
+            progress,
+            path,
+            metadata_dir,
+            library: None,
+            offline: true,
+            count: 0,
+            info: None,
+            episodes: None,
+            seasons: Vec::new(),
+            episodes_cache: None,
+        }
+    }
+
+    /// Returns the files inside the _path_ folder and its subfolders, like _Season 1_ or _Specials_.
+    pub fn get_video_files(path: &PathBuf) -> Result<Vec<PathBuf>> {
+        Self::get_video_files_at(path, 0)
+    }
+
+    /// Linked files are followed but not linked folders, which could link back to the title.
+    fn get_video_files_at(path: &PathBuf, depth: usize) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+
+        for path in Backend::get_files(path)? {
+            let metadata = match path.symlink_metadata() {
+                Ok(f) if f.is_symlink() => path.metadata().ok().filter(|f| f.is_file()),
+                res => res.ok(),
+            };
+
+            match metadata {
+                Some(f) if f.is_file() => files.push(path),
+                Some(f) if f.is_dir() && depth < MAX_DEPTH => {
+                    files.extend(Self::get_video_files_at(&path, depth + 1)?)
+                }
+                _ => (),
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Moves the metadata folders of older versions, named after the position of the episode
+    /// (_episode_{number}_), to the ones named after its [`EpisodeId`][EpisodeId].
+    ///
+    /// The old folders are matched to the video _paths_ by the name of their _.md_ file,
+    /// the ones without a matching video are left untouched.
+    pub fn migrate_metadata(&self, paths: &[PathBuf]) {
+        let Ok(entries) = fs::read_dir(&self.metadata_dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let is_index_folder = entry.file_name().to_str().is_some_and(|name| {
+                name.strip_prefix("episode_")
+                    .is_some_and(|n| n.parse::<u16>().is_ok())
+            });
+
+            if is_index_folder && entry.path().is_dir() {
+                if let Err(e) = Self::migrate_folder(&entry.path(), paths) {
+                    warn!("Could not migrate {}: {e}", entry.path().display());
+                }
+            }
+        }
+    }
+
+    fn migrate_folder(old: &Path, paths: &[PathBuf]) -> Result<()> {
+        let Some(md) = fs::read_dir(old)?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "md"))
+        else {
+            return Ok(());
+        };
+
+        let Some(path) = paths.iter().find(|path| path.file_stem() == md.file_stem()) else {
+            return Ok(());
+        };
+
+        let new = old
+            .with_file_name("episodes")
+            .join(EpisodeId::new(path)?.as_str());
+
+        if new.exists() {
+            bail!("{} already exists.", new.display());
+        }
+
+        fs::create_dir_all(old.with_file_name("episodes"))?;
+        fs::rename(old, &new)?;
+        fs::rename(new.join(md.file_name().unwrap()), new.join(METADATA_FILE))?;
+        Ok(())
+    }
+
     #[allow(dead_code)]
     /// Checks if this [`Title`][Title] was properly loaded or its missing some meta-files.
     fn is_loaded(&self) -> bool {
-        let cant_episodes = match fs::read_to_string(self.path.join(".metadata/files.md")) {
+        let cant_episodes = match fs::read_to_string(self.metadata_dir.join("files.md")) {
             Ok(paths) => paths.lines().count(),
             Err(_) => return false,
         };
 
-        let dir = self.path.join(".metadata");
+        let dir = &self.metadata_dir;
         let mut metafolders: usize = 0;
         let mut metafiles: usize = 0;
 
@@ -87,22 +240,22 @@ impl Title {
     pub async fn load_episodes(&mut self, refresh: bool) -> Result<()> {
         use iced::futures::future;
 
+        if self.offline {
+            bail!("{} is offline, its library is not available.", self.name);
+        }
+
         if refresh || self.episodes.is_none() {
             let episodes: Vec<Episode> = {
-                let mut paths: Vec<PathBuf> = Backend::get_files(&self.path)?
-                    .filter(|x| match x.metadata() {
-                        Ok(f) => f.is_file(),
-                        Err(_) => false,
-                    })
-                    .collect();
+                let mut paths: Vec<PathBuf> = Self::get_video_files(&self.path)?;
 
                 paths.sort_by(|a, b| alphanumeric_sort::compare_path(a, b));
+                self.migrate_metadata(&paths);
 
                 let mut episodes = Vec::with_capacity(paths.len());
                 let mut futs: Vec<_> = paths
                     .iter()
                     .enumerate()
-                    .map(|(i, path)| Episode::new(path, i as u16))
+                    .map(|(i, path)| Episode::new(path, i as u16, &self.metadata_dir))
                     .map(Box::pin)
                     .collect();
 
@@ -119,23 +272,149 @@ impl Title {
                     }
                 }
 
-                episodes.sort_by(|a, b| a.number.cmp(&b.number));
-                episodes = episodes
-                    .into_iter()
-                    .enumerate()
-                    .map(|(idx, ep)| ep.change_number(idx))
-                    .collect();
                 episodes
             };
 
-            self.count = episodes.len() as u16;
-            self.episodes_cache = Some(episodes.iter().map(|e| e.name.clone()).collect());
-            self.episodes = Some(episodes)
+            self.set_episodes(episodes);
+        }
+
+        Ok(())
+    }
+
+    /// Loads the new video files and drops the removed ones, keeping the already loaded [`Episodes`][Episode].
+    ///
+    /// Returns the names of the added and removed [`Episodes`][Episode], it does nothing if
+    /// they were not loaded yet.
+    pub async fn sync_episodes(&mut self) -> Result<(Vec<Arc<str>>, Vec<Arc<str>>)> {
+        if self.offline || self.episodes.is_none() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let paths = Self::get_video_files(&self.path)?;
+        let (mut episodes, removed): (Vec<Episode>, Vec<Episode>) = self
+            .episodes
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .partition(|episode| paths.contains(&episode.path));
+
+        let new_paths: Vec<&PathBuf> = paths
+            .iter()
+            .filter(|path| !episodes.iter().any(|episode| &episode.path == *path))
+            .collect();
+        let mut added = Vec::new();
+
+        for path in new_paths {
+            // Files still being copied may not be valid yet, the next change will try again.
+            match Episode::new(path, 0, &self.metadata_dir).await {
+                Ok(episode) => {
+                    added.push(episode.name.clone());
+                    episodes.push(episode);
+                }
+                Err(e) => warn!("{e}"),
+            }
+        }
+
+        self.set_episodes(episodes);
+        Ok((
+            added,
+            removed.into_iter().map(|episode| episode.name).collect(),
+        ))
+    }
+
+    /// Sorts and numbers the [`Episodes`][Episode] by season, and sets them as this title ones.
+    fn set_episodes(&mut self, mut episodes: Vec<Episode>) {
+        for episode in episodes.iter_mut() {
+            episode.season = Season::number_of(&self.path, episode);
+        }
+
+        // The listing follows the season folders and the season and episode found on the file names.
+        episodes.sort_by(|a, b| {
+            Season::cmp_number(a.season, b.season)
+                .then_with(|| a.info.cmp_order(&b.info))
+                .then_with(|| alphanumeric_sort::compare_path(&a.path, &b.path))
+        });
+
+        let episodes: Vec<Episode> = episodes
+            .into_iter()
+            .enumerate()
+            .map(|(idx, ep)| ep.change_number(idx))
+            .collect();
+
+        self.seasons = Season::group(&episodes);
+        self.count = episodes.len() as u16;
+        self.episodes_cache = Some(episodes.iter().map(|e| e.name.clone()).collect());
+        self.episodes = Some(episodes);
+        self.update_progress();
+    }
+
+    /// Counts the watched [`Episodes`][Episode] and saves the [`Progress`][Progress] if it changed.
+    pub fn update_progress(&mut self) {
+        let Some(episodes) = &self.episodes else {
+            return;
+        };
+
+        let next = progress::next_episode(
+            episodes
+                .iter()
+                .map(|e| (e.metadata.watched, e.metadata.last_watched)),
+        )
+        .map(|idx| NextEpisode {
+            number: idx as u16,
+            id: Some(episodes[idx].id.clone()),
+            name: episodes[idx].name.to_string(),
+            current: episodes[idx].metadata.current as u32,
+        });
+
+        let progress = Progress {
+            next,
+            episodes: episodes.len() as u16,
+            watched: episodes.iter().filter(|e| e.metadata.watched).count() as u16,
+            last_watched: episodes
+                .iter()
+                .filter_map(|e| e.metadata.last_watched)
+                .max(),
+        };
+
+        if self.progress.as_ref() != Some(&progress) {
+            if let Err(e) = progress.save(&self.metadata_dir.join("progress.json")) {
+                warn!("Could not save the progress of {}: {e}", self.name);
+            }
+
+            self.progress = Some(progress);
         }
+    }
 
+    /// Asks the _provider_ for the [`SeriesInfo`][SeriesInfo] of this [`Title`][Title].
+    pub async fn fetch_info(&mut self, provider: &dyn MetadataProvider) -> Result<()> {
+        let info = provider
+            .series_info(&self.name, &self.settings.ids, &self.metadata_dir)
+            .await?;
+
+        self.info = Some(info);
         Ok(())
     }
 
+    /// Returns the [`WatchStatus`][WatchStatus] set in its settings,
+    /// or the one [derived][WatchStatus::derive] from its [`Progress`][Progress].
+    pub fn status(&self) -> WatchStatus {
+        self.settings.status.unwrap_or_else(|| {
+            WatchStatus::derive(
+                self.progress
+                    .as_ref()
+                    .map(|progress| (progress.watched as usize, progress.episodes as usize)),
+            )
+        })
+    }
+
+    /// Returns the name to show, the one found by a [`MetadataProvider`][crate::MetadataProvider] or its folder one.
+    pub fn display_name(&self) -> Arc<str> {
+        match self.info.as_ref().and_then(|info| info.title()) {
+            Some(title) => Arc::from(title),
+            None => self.name.clone(),
+        }
+    }
+
     /// Returns a copy of this title [`Episodes`][Episode] names to be shared with the [frontend] thread.
     pub fn cache(&self) -> Arc<[Arc<str>]> {
         match &self.episodes_cache {
@@ -158,6 +437,23 @@ impl Title {
         }
     }
 
+    /// Returns the number of the [`Episode`][Episode] of the video file at _path_, if it's loaded.
+    pub fn find_episode(&self, path: &Path) -> Option<usize> {
+        self.episodes
+            .as_ref()?
+            .iter()
+            .position(|episode| episode.path == path)
+    }
+
+    /// Returns the number of the [`Episode`][Episode] with the given [`EpisodeId`][EpisodeId],
+    /// or [`None`][None] if it's not found or the episodes aren't loaded.
+    pub fn find_episode_id(&self, id: &EpisodeId) -> Option<usize> {
+        self.episodes
+            .as_ref()?
+            .iter()
+            .position(|episode| &episode.id == id)
+    }
+
     /// Returns the specified [`Episode`][Episode] or [`None`][None] if it doesn't exist.
     pub fn get_episode(&mut self, number: usize) -> Option<&mut Episode> {
         self.episodes.as_mut()?.get_mut(number)
@@ -173,6 +469,7 @@ impl Title {
             }
         }
 
+        self.update_progress();
         Ok(())
     }
 }
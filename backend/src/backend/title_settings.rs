@@ -0,0 +1,39 @@
+use crate::player::PlayerConfig;
+use crate::{Result, WatchStatus};
+
+use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Per-[`Title`][crate::Title] settings that override the [`Config`][crate::Config] ones.
+/// Serialized for easy editing.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default, rename_all = "snake_case")]
+pub struct TitleSettings {
+    /// Media player used for the episodes of this title.
+    pub player: Option<PlayerConfig>,
+    /// IDs of the series chosen in the match picker, keyed by the name of their
+    /// [`MetadataProvider`][crate::MetadataProvider]. They are used instead of searching by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub ids: BTreeMap<String, String>,
+    /// Status set from the title list, if it's not derived from the progress of its episodes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<WatchStatus>,
+}
+
+impl TitleSettings {
+    /// Creates a new [`TitleSettings`][TitleSettings] from a json-formatted file,
+    /// or the default settings if the file doesn't exist.
+    pub fn new(path: &Path) -> Result<TitleSettings> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TitleSettings::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Saves the [`TitleSettings`][TitleSettings] as a json-formatted file to the referenced _path_.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(fs::write(path, serde_json::to_string_pretty(self)?)?)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::Playback;
 use crate::Result;
 
 use core::fmt::Debug;
@@ -13,6 +14,12 @@ pub struct VideoMetadata {
     pub current: f64,
     pub remaining: f64,
     pub watched: bool,
+    /// Position where the last playback started.
+    #[serde(default)]
+    pub started: f64,
+    /// Unix time, in seconds, of the last time it was played or marked as watched.
+    #[serde(default)]
+    pub last_watched: Option<u64>,
 }
 
 impl VideoMetadata {
@@ -70,9 +77,52 @@ impl VideoMetadata {
         Ok(file.write_all(parsed.as_bytes())?)
     }
 
+    /// Updates the [`VideoMetadata`][VideoMetadata] with the final state of a [`Playback`][Playback].
+    ///
+    /// The episode is considered watched if it reached the end,
+    /// or less than _min_time_ seconds were left to finish it, if its duration is known.
+    /// If its position is unknown, only when it was watched is updated.
+    pub fn update(&mut self, playback: &Playback, min_time: f64) {
+        if playback.duration > 0.0 {
+            self.duration = playback.duration;
+        }
+
+        self.last_watched = Some(now());
+        let position = match playback.position {
+            Some(position) => position,
+            None if playback.eof => self.duration,
+            // Nothing tells where it was left, so the progress stays as it was.
+            None => return,
+        };
+
+        // Without a duration nothing tells how much is left, only reaching the end does.
+        let known = self.duration > 0.0;
+        let position = if known {
+            position.min(self.duration)
+        } else {
+            position
+        };
+
+        self.started = playback.start;
+        self.remaining = (self.duration - position).max(0.0);
+        self.watched = playback.eof || (known && self.remaining < min_time);
+        self.current = if self.watched { 0.0 } else { position };
+    }
+
     /// Marks the [`VideoMetadata`][VideoMetadata] as watched or not.
     pub fn as_watched(&mut self) {
         self.watched = !self.watched;
-        self.current = if !self.watched { 0.0 } else { self.duration }
+        self.current = if !self.watched { 0.0 } else { self.duration };
+
+        if self.watched {
+            self.last_watched = Some(now());
+        }
     }
 }
+
+/// Returns the current Unix time in seconds.
+pub(crate) fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
@@ -0,0 +1,80 @@
+use core::fmt::{self, Debug};
+use serde::{Deserialize, Serialize};
+
+/// Title-level status, set in the [`TitleSettings`][crate::TitleSettings] of a [`Title`][crate::Title]
+/// or [derived][WatchStatus::derive] from the progress of its episodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchStatus {
+    Watching,
+    Completed,
+    OnHold,
+    Dropped,
+    PlanToWatch,
+}
+
+impl WatchStatus {
+    pub const ALL: [WatchStatus; 5] = [
+        WatchStatus::Watching,
+        WatchStatus::Completed,
+        WatchStatus::OnHold,
+        WatchStatus::Dropped,
+        WatchStatus::PlanToWatch,
+    ];
+
+    /// Returns the status given by the number of episodes watched and the number of episodes:
+    /// none watched is [`PlanToWatch`][WatchStatus::PlanToWatch], all of them is
+    /// [`Completed`][WatchStatus::Completed] and otherwise it's [`Watching`][WatchStatus::Watching].
+    ///
+    /// Titles never loaded are [`PlanToWatch`][WatchStatus::PlanToWatch].
+    pub fn derive(progress: Option<(usize, usize)>) -> Self {
+        match progress {
+            Some((0, _)) | None => WatchStatus::PlanToWatch,
+            Some((watched, episodes)) if watched >= episodes => WatchStatus::Completed,
+            Some(_) => WatchStatus::Watching,
+        }
+    }
+
+    /// Returns the following status to set, to cycle between them.
+    ///
+    /// After the last one comes [`None`][None], to go back to the derived status.
+    pub fn next(status: Option<Self>) -> Option<Self> {
+        match status {
+            None => Some(Self::ALL[0]),
+            Some(status) => {
+                let idx = Self::ALL.iter().position(|&s| s == status).unwrap_or(0);
+                Self::ALL.get(idx + 1).copied()
+            }
+        }
+    }
+
+    /// Parses a status ignoring case, spaces, dashes and underscores, like _on-hold_ or _"Plan to watch"_.
+    pub fn parse(status: &str) -> Option<Self> {
+        let status: String = status
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+
+        Self::ALL.into_iter().find(|s| {
+            s.to_string()
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+                == status
+        })
+    }
+}
+
+impl fmt::Display for WatchStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WatchStatus::Watching => "Watching",
+            WatchStatus::Completed => "Completed",
+            WatchStatus::OnHold => "On Hold",
+            WatchStatus::Dropped => "Dropped",
+            WatchStatus::PlanToWatch => "Plan to Watch",
+        })
+    }
+}
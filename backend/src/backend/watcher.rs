@@ -0,0 +1,100 @@
+use crate::Result;
+
+use core::fmt::Debug;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// A change in the [`Titles`][crate::Title] found by a [`Watcher`][Watcher].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LibraryChange {
+    /// Titles were added or removed and sorted again, so their indexes changed.
+    /// Args: (Added title names, Removed title names, New index of each previous title, if it's still there)
+    TitlesChanged(Vec<Arc<str>>, Vec<Arc<str>>, Vec<Option<usize>>),
+    /// Args: (Title index, Episode names)
+    EpisodesAdded(usize, Vec<Arc<str>>),
+    /// Args: (Title index, Episode names)
+    EpisodesRemoved(usize, Vec<Arc<str>>),
+}
+
+/// Time without changes before they are reported, copying a folder triggers lots of events.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Watches the folders of the [`Libraries`][crate::Library] for new, removed or renamed files.
+#[derive(Debug)]
+pub struct Watcher {
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::UnboundedReceiver<PathBuf>,
+    debounce: Duration,
+}
+
+impl Watcher {
+    /// Creates a new [`Watcher`][Watcher] for the given folders and their subfolders.
+    ///
+    /// Folders that can not be watched, like an unplugged drive, are skipped.
+    pub fn new(roots: &[PathBuf]) -> Result<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let watched = roots.to_vec();
+
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) => {
+                    let paths = event.paths.into_iter();
+
+                    for path in paths.filter(|path| !is_hidden(&watched, path)) {
+                        let _ = sender.send(path);
+                    }
+                }
+                Err(e) => warn!("Library watcher error: {e}"),
+            })?;
+
+        for root in roots {
+            if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+                warn!("Could not watch {}: {e}", root.display());
+            }
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            receiver,
+            debounce: DEBOUNCE,
+        })
+    }
+
+    /// Changes the time without changes to wait before reporting them.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Waits for the next changes, once there are no more for the debounce time.
+    ///
+    /// Returns the changed paths, or [`None`][None] if the [`Watcher`][Watcher] stopped.
+    pub async fn next(&mut self) -> Option<Vec<PathBuf>> {
+        let mut paths = BTreeSet::from([self.receiver.recv().await?]);
+
+        while let Ok(Some(path)) = tokio::time::timeout(self.debounce, self.receiver.recv()).await {
+            paths.insert(path);
+        }
+
+        Some(paths.into_iter().collect())
+    }
+}
+
+/// Checks if any part of the path inside its library is hidden, like the _.metadata_ folders
+/// written by [yama]. The folders of the library itself may be hidden, like _~/.local/share/anime_.
+fn is_hidden(roots: &[PathBuf], path: &Path) -> bool {
+    let inside = roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+
+    inside.components().any(|component| match component {
+        Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+        _ => false,
+    })
+}
@@ -1,8 +1,13 @@
+use crate::backend::library::sanitize;
+use crate::{Library, MetadataStore, PlayerConfig};
+
+use core::fmt;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
 
-static CFG_PATH: Lazy<PathBuf> = Lazy::new(|| {
+pub(crate) static CFG_PATH: Lazy<PathBuf> = Lazy::new(|| {
     confy::get_configuration_file_path("yama", "config")
         .expect("No configuration path found.")
         .parent()
@@ -11,22 +16,199 @@ static CFG_PATH: Lazy<PathBuf> = Lazy::new(|| {
 });
 
 /// [yama's] Config
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Folder of older versions, it's added to the [`libraries`][Config::libraries] when they are changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub series_path: Option<PathBuf>,
     pub theme_path: PathBuf,
     pub min_time: f32,
+    /// Stores the metadata in the config folder instead of a _.metadata_ folder inside each title.
+    #[serde(default)]
+    pub central_metadata: bool,
+    /// Order of the title list.
+    #[serde(default)]
+    pub title_sort: TitleSort,
+    /// Folders to scan for titles.
+    #[serde(default)]
+    pub libraries: Vec<Library>,
+    /// Media player used to watch the episodes.
+    #[serde(default)]
+    pub player: PlayerConfig,
+    /// HTTP API to control yama from other devices, like a phone.
+    #[serde(default)]
+    pub api: ApiConfig,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        //TODO! - 0.8.0: Ask user for default path.
-        //std::fs::create_dir_all("./series").unwrap();
-
+        // The libraries are chosen in the first launch setup.
         Self {
             series_path: None,
             theme_path: CFG_PATH.join("themes/iced.json"),
             min_time: 10.0,
+            central_metadata: false,
+            title_sort: TitleSort::default(),
+            libraries: Vec::new(),
+            player: PlayerConfig::default(),
+            api: ApiConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Returns all the [`Libraries`][Library], including the _series_path_ of older versions.
+    pub fn libraries(&self) -> Vec<Library> {
+        let mut libraries = self.libraries.clone();
+
+        if let Some(path) = &self.series_path {
+            if !libraries.iter().any(|library| &library.path == path) {
+                libraries.insert(0, Library::new(Self::library_name(path), path));
+            }
+        }
+
+        libraries
+    }
+
+    /// Adds a new [`Library`][Library] for the folder specified by the _path_,
+    /// named after the folder. Returns `false` if it was already added.
+    pub fn add_library(&mut self, path: PathBuf) -> bool {
+        self.libraries = self.libraries();
+        self.series_path = None;
+
+        if self.libraries.iter().any(|library| library.path == path) {
+            return false;
+        }
+
+        let name = Self::library_name(&path);
+        let mut unique = name.clone();
+        let mut n = 2;
+
+        while self.libraries.iter().any(|library| library.name == unique) {
+            unique = format!("{name} {n}");
+            n += 1;
+        }
+
+        self.libraries.push(Library::new(unique, path));
+        true
+    }
+
+    /// Removes the indexed [`Library`][Library], as listed by [`libraries`][Config::libraries].
+    pub fn remove_library(&mut self, index: usize) {
+        self.libraries = self.libraries();
+        self.series_path = None;
+
+        if index < self.libraries.len() {
+            self.libraries.remove(index);
+        }
+    }
+
+    /// Enables or disables the indexed [`Library`][Library], as listed by [`libraries`][Config::libraries].
+    pub fn toggle_library(&mut self, index: usize) {
+        self.libraries = self.libraries();
+        self.series_path = None;
+
+        if let Some(library) = self.libraries.get_mut(index) {
+            library.enabled = !library.enabled;
         }
     }
+
+    fn library_name(path: &std::path::Path) -> String {
+        match path.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => path.display().to_string(),
+        }
+    }
+
+    /// Returns the [`MetadataStore`][MetadataStore] used for the titles of the _library_,
+    /// the central one is inside the _config_dir_ folder.
+    pub fn metadata_store(&self, config_dir: &Path, library: &Library) -> MetadataStore {
+        match self.central_metadata {
+            true => MetadataStore::Central {
+                root: config_dir.join("metadata").join(sanitize(&library.name)),
+                library: library.path.clone(),
+            },
+            false => MetadataStore::Local,
+        }
+    }
+}
+
+/// Config of the local HTTP API, it's only started if it's _enabled_.
+///
+/// The default _address_ only accepts connections from the same computer, one like
+/// _0.0.0.0:7777_ accepts them from the LAN but it needs a _token_ to be started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub address: SocketAddr,
+    /// Required as `Authorization: Bearer <token>` in every request, if it's set.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Web pages allowed to call the API from a browser, like _http://dashboard.local:8123_.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, 7777)),
+            token: None,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+/// Orders of the title list, the titles that can't be compared, like the ones never watched
+/// when sorting by [`RecentlyWatched`][TitleSort::RecentlyWatched], keep their folder order at the end.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleSort {
+    /// Folder name.
+    #[default]
+    Folder,
+    /// Name found by a [`MetadataProvider`][crate::MetadataProvider].
+    Title,
+    RecentlyWatched,
+    /// Last change of the folder first.
+    RecentlyAdded,
+    /// Fewest episodes left to watch first, the finished titles go last.
+    Unwatched,
+    /// Best score first.
+    Score,
+    /// Newest first.
+    Year,
+}
+
+impl TitleSort {
+    pub const ALL: [TitleSort; 7] = [
+        TitleSort::Folder,
+        TitleSort::Title,
+        TitleSort::RecentlyWatched,
+        TitleSort::RecentlyAdded,
+        TitleSort::Unwatched,
+        TitleSort::Score,
+        TitleSort::Year,
+    ];
+
+    /// Returns the following [`TitleSort`][TitleSort], to cycle between them.
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|&sort| sort == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for TitleSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TitleSort::Folder => "Folder name",
+            TitleSort::Title => "Title",
+            TitleSort::RecentlyWatched => "Recently watched",
+            TitleSort::RecentlyAdded => "Recently added",
+            TitleSort::Unwatched => "Episodes left",
+            TitleSort::Score => "Score",
+            TitleSort::Year => "Year",
+        })
+    }
 }
@@ -1,15 +1,35 @@
 mod backend;
 mod config;
 mod networking;
+mod player;
 
-pub use self::config::Config;
+pub use self::config::{ApiConfig, Config, TitleSort};
+pub use backend::dependency::{find_program, Dependency};
+pub use backend::diagnostics::{Check, CheckStatus, Diagnostics};
 pub use backend::episode::Episode;
+pub use backend::episode_id::EpisodeId;
+pub use backend::file_info::{EpisodeKind, FileInfo};
+pub use backend::history::{History, HistoryEntry, HistoryFormat};
+pub use backend::library::Library;
 pub use backend::meta::Meta;
 pub use backend::meta::MetaType;
+pub use backend::metadata_store::MetadataStore;
+pub use backend::process::{Process, Running};
+pub use backend::progress::{next_episode, NextEpisode, Progress};
+pub use backend::season::Season;
 pub use backend::title::Title;
+pub use backend::title_settings::TitleSettings;
 pub use backend::video_metadata::VideoMetadata;
+pub use backend::watch_status::WatchStatus;
+pub use backend::watcher::{LibraryChange, Watcher};
 pub use backend::Backend;
 pub use networking::anilist::Anilist;
 pub use networking::discord::{Discord, APP_ID};
+pub use networking::provider::{
+    MetadataProvider, ProviderChain, SeriesImages, SeriesInfo, SeriesTitles,
+};
+pub use player::mpv::{MpvEvent, MpvIpc};
+pub use player::vlc::VlcRc;
+pub use player::{CommandTemplate, Mpv, Playback, Player, PlayerConfig, Vlc};
 
 pub type Result<T> = anyhow::Result<T>;
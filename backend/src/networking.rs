@@ -1,2 +1,3 @@
 pub mod anilist;
 pub mod discord;
+pub mod provider;
@@ -2,14 +2,17 @@ pub mod query;
 
 pub use query::*;
 
-use crate::backend::title::Title;
-use crate::Result;
+use crate::{MetadataProvider, Result, SeriesInfo};
 
 use anyhow::bail;
 use hyper::{body::Buf, client::HttpConnector, Body, Client, Method, Request, Response};
 use hyper_tls::HttpsConnector;
-use serde_json::json;
-use std::{ffi::OsString, path::Path};
+use iced::futures::future::{self, BoxFuture};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
 /// [`Client`] connected to the Anilist API.
 #[derive(Debug)]
@@ -32,11 +35,9 @@ impl Anilist {
         Anilist { client }
     }
 
-    /// POST the [`QUERY`] to the Anilist API with the title name as it's variable.
-    ///
-    /// Downloads a json-file and a jpg-file.
-    async fn query(&self, path: &Path, title_search: &str, id: usize) -> Result<Data> {
-        let json = json!({"query": QUERY, "variables": {"search": title_search}});
+    /// POST a _query_, with the [`MEDIA`] fragment, to the Anilist API.
+    async fn post<T: DeserializeOwned>(&self, query: &str, variables: Value) -> Result<T> {
+        let json = json!({"query": format!("{query}{MEDIA}"), "variables": variables});
 
         let req = Request::builder()
             .method(Method::POST)
@@ -47,32 +48,82 @@ impl Anilist {
 
         let resp = self.client.request(req).await?;
         let body = hyper::body::aggregate(resp).await?;
-        let result: Query = serde_json::from_reader(body.reader())?;
+        Ok(serde_json::from_reader(body.reader())?)
+    }
+
+    /// POST the [`QUERY`] to the Anilist API with the pinned _id_, or the title name, as it's variable.
+    ///
+    /// Downloads a jpg-file and a json-file into the metadata folder at _path_.
+    async fn query(&self, path: &Path, title_search: &str, id: Option<&str>) -> Result<SeriesInfo> {
+        let variables = match id {
+            Some(id) => json!({ "id": id.parse::<usize>()? }),
+            None => json!({ "search": title_search }),
+        };
+
+        let result: Query = self.post(QUERY, variables).await?;
 
         let content = serde_json::to_string_pretty(&result)?;
-        std::fs::write(path.join(".metadata").join("data.json"), content)?;
+        let mut info = SeriesInfo::from(result.data.media);
 
-        let mut data = result.data.download_image(self, path).await?;
-        data.set_id(id);
-        data.find_studio();
-        data.clean_description();
+        // The json-file is written last, so a failed download is tried again the next time.
+        if let Some(url) = info.images.banner.as_ref().or(info.images.cover.as_ref()) {
+            info.images.thumbnail = Some(self.download_image(url, path).await?);
+            info!("Image downloaded for: {title_search}");
+        }
 
-        Ok(data)
+        std::fs::write(path.join("data.json"), content)?;
+        Ok(info)
     }
 
-    /// Grabs the json-file and a jpg-file from a previously made [`Query`].
-    fn cached_query(&self, path: &Path, id: usize) -> Result<Data> {
-        let content = std::fs::read_to_string(path.join(".metadata").join("data.json"))?;
-
+    /// Grabs the json-file and a jpg-file from a previously made [`Query`] in the metadata folder at _path_.
+    fn cached_query(path: &Path) -> Result<SeriesInfo> {
+        let content = std::fs::read_to_string(path.join("data.json"))?;
         let result: Query = serde_json::from_str(&content)?;
 
-        let mut data = result.data;
-        data.set_id(id);
-        data.find_studio();
-        data.clean_description();
-        data.set_thumbnail_path(path.join(".metadata").join("thumbnail.jpg"));
+        let mut info = SeriesInfo::from(result.data.media);
+        let thumbnail = path.join("thumbnail.jpg");
+        info.images.thumbnail = thumbnail.exists().then_some(thumbnail);
+
+        Ok(info)
+    }
+
+    /// Downloads the image at _url_ as the thumbnail of the metadata folder at _path_.
+    async fn download_image(&self, url: &str, path: &Path) -> Result<PathBuf> {
+        self.download(url, path.join("thumbnail.jpg")).await
+    }
+
+    /// Downloads the cover of a candidate of [`search`][MetadataProvider::search] into the _covers_ folder,
+    /// unless it was already downloaded.
+    async fn download_cover(&self, info: &mut SeriesInfo, covers: &Path) {
+        let (Some(url), Some(id)) = (&info.images.cover, info.ids.get(self.name())) else {
+            return;
+        };
+
+        let name_file = covers.join(format!("{}-{id}.jpg", self.name()));
+
+        if name_file.exists() {
+            info.images.thumbnail = Some(name_file);
+            return;
+        }
+
+        match self.download(url, name_file).await {
+            Ok(name_file) => info.images.thumbnail = Some(name_file),
+            Err(e) => warn!("Could not download the cover of {id}: {e}"),
+        }
+    }
+
+    async fn download(&self, url: &str, name_file: PathBuf) -> Result<PathBuf> {
+        use tokio::io::AsyncWriteExt;
+        use tokio_stream::StreamExt;
+
+        let mut body = self.get_body(url).await?.into_body();
+        let mut file = tokio::fs::File::create(&name_file).await?;
 
-        Ok(data)
+        while let Some(chunk) = body.next().await {
+            file.write_all(&chunk?).await?;
+        }
+
+        Ok(name_file)
     }
 
     /// GET Request of the indicated url.
@@ -81,33 +132,67 @@ impl Anilist {
         let res = self.client.get(uri).await?;
         Ok(res)
     }
+}
 
-    /// Checks if a [`Query`] was previously made for this [`Title`] or makes a new one.
-    pub async fn try_query(&self, title: &mut Title, id: usize) -> Result<()> {
-        let path = title.path.as_path();
-        let search = &title.name;
+impl MetadataProvider for Anilist {
+    fn name(&self) -> &'static str {
+        "anilist"
+    }
 
-        if let Ok(files) = std::fs::read_dir(path.join(".metadata")) {
-            let files: Vec<_> = files
-                .into_iter()
-                .flatten()
-                .map(|file| file.file_name())
-                .collect();
+    /// Checks if a [`Query`] was previously made in the metadata folder at _dir_ or makes a new one.
+    ///
+    /// A previous [`Query`] of other media than the pinned one is made again.
+    fn series_info<'a>(
+        &'a self,
+        search: &'a str,
+        ids: &'a BTreeMap<String, String>,
+        dir: &'a Path,
+    ) -> BoxFuture<'a, Result<SeriesInfo>> {
+        Box::pin(async move {
+            if !dir.is_dir() {
+                bail!("{} is not a valid folder.", dir.display());
+            }
 
-            let was_downloaded = files.contains(&OsString::from("thumbnail.jpg"))
-                && files.contains(&OsString::from("data.json"));
+            let pinned = ids.get(self.name());
 
-            title.data = if was_downloaded {
-                self.cached_query(path, id)
-            } else {
-                self.query(path, search, id).await
+            if dir.join("data.json").exists() {
+                match Self::cached_query(dir) {
+                    Ok(info) if pinned.is_none_or(|id| info.ids.get(self.name()) == Some(id)) => {
+                        return Ok(info)
+                    }
+                    Ok(_) => (),
+                    Err(e) => warn!("Invalid data.json in {}: {e}", dir.display()),
+                }
             }
-            .ok();
-        }
 
-        match title.data {
-            Some(_) => Ok(()),
-            None => bail!("Failed query of: {}", search),
-        }
+            self.query(dir, search, pinned.map(String::as_str)).await
+        })
+    }
+
+    fn search<'a>(
+        &'a self,
+        search: &'a str,
+        covers: &'a Path,
+    ) -> BoxFuture<'a, Result<Vec<SeriesInfo>>> {
+        Box::pin(async move {
+            let result: Search = self.post(SEARCH, json!({ "search": search })).await?;
+            let mut candidates: Vec<SeriesInfo> = result
+                .data
+                .page
+                .media
+                .into_iter()
+                .map(SeriesInfo::from)
+                .collect();
+
+            std::fs::create_dir_all(covers)?;
+            future::join_all(
+                candidates
+                    .iter_mut()
+                    .map(|info| self.download_cover(info, covers)),
+            )
+            .await;
+
+            Ok(candidates)
+        })
     }
 }
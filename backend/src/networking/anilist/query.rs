@@ -1,33 +1,57 @@
-use crate::Anilist;
-use crate::Result;
+use crate::{SeriesImages, SeriesInfo, SeriesTitles};
 
 use aho_corasick::AhoCorasick;
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
-use tracing::info;
+use std::collections::BTreeMap;
 
-/// Anilist query
+/// Anilist query of a single media, by its id or its name.
 pub const QUERY: &str = r#"
+query ($id: Int, $search: String) {
+  Media (id: $id, search: $search, type: ANIME) {
+    ...media
+  }
+}
+"#;
+
+/// Anilist query of the media that may have a name, best matches first.
+pub const SEARCH: &str = r#"
 query ($search: String) {
-  Media (search: $search, type: ANIME) {
-    id,
-    title {
-      romaji,
-      english,
-      native,
-    },
-    description,
-    genres,
-    bannerImage,
-    studios {
-        edges {
-          isMain,
-          node {
-            name
-          }
+  Page (perPage: 10) {
+    media (search: $search, type: ANIME) {
+      ...media
+    }
+  }
+}
+"#;
+
+/// Fields of a media used by the [`QUERY`] and [`SEARCH`] queries.
+pub const MEDIA: &str = r#"
+fragment media on Media {
+  id,
+  idMal,
+  title {
+    romaji,
+    english,
+    native,
+  },
+  description,
+  genres,
+  bannerImage,
+  coverImage {
+    large
+  },
+  episodes,
+  seasonYear,
+  format,
+  averageScore,
+  studios {
+      edges {
+        isMain,
+        node {
+          name
         }
       }
-  }
+    }
 }
 "#;
 
@@ -42,32 +66,60 @@ pub struct Query {
 #[serde(rename_all = "PascalCase")]
 pub struct Data {
     pub media: Media,
-    #[serde(skip)]
-    pub thumbnail_path: PathBuf,
-    #[serde(skip)]
-    pub id: usize,
-    #[serde(skip)]
-    pub studio: String,
+}
+
+/// Serialized search of Anilist.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Search {
+    pub data: SearchData,
+}
+
+/// Serialized search data of Anilist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct SearchData {
+    pub page: Page,
+}
+
+/// Serialized page of Anilist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Page {
+    pub media: Vec<Media>,
 }
 
 /// Serialized media of Anilist.
+///
+/// Most of its fields may be null, and the ones added later are missing in older downloads.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Media {
-    id: usize,
+    pub id: usize,
+    pub id_mal: Option<usize>,
     pub title: Title,
-    pub description: String,
+    pub description: Option<String>,
+    #[serde(default)]
     pub genres: Vec<String>,
-    pub banner_image: String,
+    pub banner_image: Option<String>,
+    pub cover_image: Option<CoverImage>,
+    pub episodes: Option<u16>,
+    pub season_year: Option<u16>,
+    pub format: Option<String>,
+    pub average_score: Option<u8>,
     pub studios: Studio,
 }
 
 /// Serialized titles of Anilist.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Title {
-    pub romaji: String,
-    pub english: String,
-    pub native: String,
+    pub romaji: Option<String>,
+    pub english: Option<String>,
+    pub native: Option<String>,
+}
+
+/// Serialized cover image of Anilist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CoverImage {
+    pub large: Option<String>,
 }
 
 /// Serialized studios of Anilist.
@@ -90,65 +142,54 @@ pub struct Node {
     pub name: String,
 }
 
-impl Data {
-    pub async fn download_image(mut self, api: &Anilist, path: &Path) -> Result<Self> {
-        use tokio::io::AsyncWriteExt;
-        use tokio_stream::StreamExt;
-
-        let resp = api.get_body(&self.media.banner_image).await?;
-        let mut body = resp.into_body();
-
-        let name_file = path.join(".metadata").join("thumbnail.jpg");
-        let mut file = tokio::fs::File::create(&name_file).await?;
-
-        while let Some(chunk) = body.next().await {
-            let chunk = chunk?;
-            file.write_all(&chunk).await?;
-        }
-
-        info!("Image downloaded for: {}", self.media.title.english);
-        self.set_thumbnail_path(name_file);
-        Ok(self)
-    }
-
-    pub fn set_thumbnail_path(&mut self, path: PathBuf) -> &mut Self {
-        self.thumbnail_path = path;
-        self
-    }
-
-    pub fn set_id(&mut self, id: usize) -> &mut Self {
-        self.id = id;
-        self
-    }
-
-    pub fn clean_description(&mut self) -> &mut Self {
+impl Media {
+    /// Removes the html tags of the description.
+    fn clean_description(&self) -> String {
         let ac =
             AhoCorasick::new(["<b>", "</b>", "<i>", "</i>", "<br>\n<br>", "<br><br>"]).unwrap();
-        self.media.description =
-            ac.replace_all(&self.media.description, &["", "", "", "", "\n", "\n"]);
 
-        self
+        ac.replace_all(
+            self.description.as_deref().unwrap_or_default(),
+            &["", "", "", "", "\n", "\n"],
+        )
     }
 
-    pub fn find_studio(&mut self) -> &mut Self {
-        for studio in &self.media.studios.edges {
-            if studio.is_main {
-                self.studio = studio.node.name.clone();
-                break;
-            }
-        }
-
-        self
+    fn find_studio(&self) -> Option<String> {
+        self.studios
+            .edges
+            .iter()
+            .find(|studio| studio.is_main)
+            .map(|studio| studio.node.name.clone())
     }
 }
 
-impl Media {
-    pub fn to_str(&self) -> Box<str> {
-        format!(
-            "Description: {}\n\nGenres: {}",
-            self.description.trim(),
-            self.genres.join(", ")
-        )
-        .into_boxed_str()
+impl From<Media> for SeriesInfo {
+    fn from(media: Media) -> Self {
+        let mut ids = BTreeMap::from([(String::from("anilist"), media.id.to_string())]);
+
+        if let Some(id) = media.id_mal {
+            ids.insert(String::from("mal"), id.to_string());
+        }
+
+        Self {
+            synopsis: media.clean_description(),
+            studio: media.find_studio(),
+            titles: SeriesTitles {
+                english: media.title.english,
+                romaji: media.title.romaji,
+                native: media.title.native,
+            },
+            genres: media.genres,
+            images: SeriesImages {
+                banner: media.banner_image,
+                cover: media.cover_image.and_then(|cover| cover.large),
+                thumbnail: None,
+            },
+            episodes: media.episodes,
+            year: media.season_year,
+            format: media.format,
+            score: media.average_score,
+            ids,
+        }
     }
 }
@@ -0,0 +1,163 @@
+use crate::Result;
+
+use anyhow::bail;
+use core::fmt::Debug;
+use iced::futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Information about a series, independent of the [`MetadataProvider`][MetadataProvider] it came from.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesInfo {
+    pub titles: SeriesTitles,
+    pub synopsis: String,
+    pub genres: Vec<String>,
+    /// Main animation studio.
+    pub studio: Option<String>,
+    pub images: SeriesImages,
+    /// Number of episodes, if it's known.
+    pub episodes: Option<u16>,
+    /// Year it started airing.
+    pub year: Option<u16>,
+    /// Like _TV_, _MOVIE_ or _OVA_.
+    pub format: Option<String>,
+    /// Average score, out of a hundred.
+    pub score: Option<u8>,
+    /// IDs on the sites that know this series, keyed by its name, like _anilist_ or _mal_.
+    pub ids: BTreeMap<String, String>,
+}
+
+/// Names of a series.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesTitles {
+    pub english: Option<String>,
+    pub romaji: Option<String>,
+    pub native: Option<String>,
+}
+
+/// Images of a series.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesImages {
+    pub banner: Option<String>,
+    pub cover: Option<String>,
+    /// Local copy of one of them, shown as the thumbnail of the [`Title`][crate::Title].
+    pub thumbnail: Option<PathBuf>,
+}
+
+impl SeriesInfo {
+    /// Returns the name to show, the english one if there's one.
+    pub fn title(&self) -> Option<&str> {
+        let titles = &self.titles;
+
+        [&titles.english, &titles.romaji, &titles.native]
+            .into_iter()
+            .flatten()
+            .map(|title| title.as_str())
+            .find(|title| !title.is_empty())
+    }
+
+    pub fn to_str(&self) -> Box<str> {
+        let mut description = format!(
+            "Description: {}\n\nGenres: {}",
+            self.synopsis.trim(),
+            self.genres.join(", ")
+        );
+
+        if let Some(studio) = &self.studio {
+            description.push_str(&format!("\n\nStudio: {studio}"));
+        }
+
+        description.into_boxed_str()
+    }
+}
+
+/// A [trait] for the sources of [`SeriesInfo`][SeriesInfo], like [`Anilist`][crate::Anilist].
+pub trait MetadataProvider: Debug + Send + Sync {
+    /// Name of the provider, used as the key of its IDs in [`SeriesInfo`][SeriesInfo].
+    fn name(&self) -> &'static str;
+
+    /// Searches for the series named _search_, or the one with its ID in the pinned _ids_.
+    ///
+    /// What it downloads is saved in the metadata folder at _dir_, and reused by the next searches.
+    fn series_info<'a>(
+        &'a self,
+        search: &'a str,
+        ids: &'a BTreeMap<String, String>,
+        dir: &'a Path,
+    ) -> BoxFuture<'a, Result<SeriesInfo>>;
+
+    /// Returns the series that may be named _search_, best matches first,
+    /// with their covers downloaded into the _covers_ folder.
+    fn search<'a>(
+        &'a self,
+        _search: &'a str,
+        _covers: &'a Path,
+    ) -> BoxFuture<'a, Result<Vec<SeriesInfo>>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+}
+
+/// [`MetadataProviders`][MetadataProvider] asked in priority order, until one of them finds the series.
+#[derive(Debug)]
+pub struct ProviderChain {
+    providers: Vec<Box<dyn MetadataProvider>>,
+}
+
+impl Default for ProviderChain {
+    fn default() -> Self {
+        Self::new(vec![Box::<crate::Anilist>::default()])
+    }
+}
+
+impl ProviderChain {
+    /// Creates a new [`ProviderChain`][ProviderChain], the first providers have priority.
+    pub fn new(providers: Vec<Box<dyn MetadataProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+impl MetadataProvider for ProviderChain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+
+    fn series_info<'a>(
+        &'a self,
+        search: &'a str,
+        ids: &'a BTreeMap<String, String>,
+        dir: &'a Path,
+    ) -> BoxFuture<'a, Result<SeriesInfo>> {
+        Box::pin(async move {
+            for provider in &self.providers {
+                match provider.series_info(search, ids, dir).await {
+                    Ok(info) => return Ok(info),
+                    Err(e) => warn!("{} could not find {search}: {e}", provider.name()),
+                }
+            }
+
+            bail!("Failed query of: {search}")
+        })
+    }
+
+    /// Returns the candidates of every provider, in priority order.
+    fn search<'a>(
+        &'a self,
+        search: &'a str,
+        covers: &'a Path,
+    ) -> BoxFuture<'a, Result<Vec<SeriesInfo>>> {
+        Box::pin(async move {
+            let mut candidates = Vec::new();
+
+            for provider in &self.providers {
+                match provider.search(search, covers).await {
+                    Ok(found) => candidates.extend(found),
+                    Err(e) => warn!("{} could not search {search}: {e}", provider.name()),
+                }
+            }
+
+            Ok(candidates)
+        })
+    }
+}
@@ -0,0 +1,111 @@
+pub mod command;
+pub mod mpv;
+pub mod vlc;
+
+pub use command::CommandTemplate;
+pub use mpv::Mpv;
+pub use vlc::Vlc;
+
+use crate::Result;
+
+use core::fmt::Debug;
+use iced::futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The state of an episode playback, reported by a [`Player`][Player] once it's closed.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Playback {
+    /// Position where the playback started.
+    pub start: f64,
+    /// Last known position of the playback, or [`None`][None] if the [`Player`][Player] can't report it.
+    pub position: Option<f64>,
+    /// Duration of the video, or zero if the [`Player`][Player] doesn't know it.
+    pub duration: f64,
+    pub paused: bool,
+    /// If the episode was played until the end.
+    pub eof: bool,
+}
+
+impl Playback {
+    /// Creates a new [`Playback`][Playback] starting at the given position.
+    pub fn new(start: f64) -> Self {
+        Self {
+            start,
+            position: Some(start),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new [`Playback`][Playback] starting at the given position,
+    /// of a [`Player`][Player] that can't report where it ended.
+    pub fn unknown(start: f64) -> Self {
+        Self {
+            start,
+            ..Default::default()
+        }
+    }
+
+    /// Updates the [`Playback`][Playback] with a received [`MpvEvent`][mpv::MpvEvent].
+    pub fn apply(&mut self, event: mpv::MpvEvent) {
+        use mpv::MpvEvent;
+
+        match event {
+            MpvEvent::Position(position) => self.position = Some(position),
+            MpvEvent::Pause(paused) => self.paused = paused,
+            MpvEvent::Duration(duration) => self.duration = duration,
+            MpvEvent::EndFile { eof } => self.eof |= eof,
+        }
+    }
+
+    /// Seconds left to finish the episode, if its position is known.
+    pub fn remaining(&self) -> Option<f64> {
+        self.position
+            .map(|position| (self.duration - position).max(0.0))
+    }
+}
+
+/// A [trait] for the media players able to play an [`Episode`][crate::Episode].
+pub trait Player: Debug + Send + Sync {
+    /// Plays the video at _path_ from _start_ seconds.
+    ///
+    /// The returned [`Future`][std::future::Future] resolves with the final [`Playback`][Playback]
+    /// once the player is closed, and doesn't borrow the [`Player`][Player] so it can be spawned in the background.
+    fn play(&self, path: &Path, start: f64) -> BoxFuture<'static, Result<Playback>>;
+}
+
+/// Media player choice, set in the [`Config`][crate::Config] and overridable for each [`Title`][crate::Title].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PlayerConfig {
+    #[default]
+    Mpv,
+    Vlc,
+    /// Custom command, see [`CommandTemplate`][CommandTemplate] for its placeholders.
+    Command {
+        template: String,
+    },
+}
+
+impl PlayerConfig {
+    /// Returns the [`Player`][Player] for this choice.
+    pub fn player(&self) -> Box<dyn Player> {
+        match self {
+            Self::Mpv => Box::new(Mpv),
+            Self::Vlc => Box::new(Vlc),
+            Self::Command { template } => Box::new(CommandTemplate::new(template.as_str())),
+        }
+    }
+
+    /// Returns the program launched by this choice, none if the command is invalid.
+    pub fn program(&self) -> Option<String> {
+        match self {
+            Self::Mpv => Some(String::from("mpv")),
+            Self::Vlc => Some(String::from("vlc")),
+            Self::Command { template } => CommandTemplate::new(template.as_str())
+                .args(Path::new(""), 0.0)
+                .ok()
+                .map(|args| args[0].to_string_lossy().into_owned()),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::{Playback, Player};
+use crate::Process;
+use crate::Result;
+
+use anyhow::{anyhow, bail};
+use core::fmt::Debug;
+use iced::futures::future::BoxFuture;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// A media player launched from a command template, like `vlc --start-time={start} {path}`.
+///
+/// The template is split into arguments by whitespace (quotes can be used to keep spaces)
+/// and the placeholders are replaced on each argument, it never goes through a shell
+/// (see [`Process`][Process]):
+/// - `{path}`: Path of the video file.
+/// - `{start}`: Position in seconds where the playback should start.
+///
+/// There is no way to ask an arbitrary player for its position, so the
+/// end position is unknown and the progress of the episode is left as it was.
+#[derive(Debug, Clone)]
+pub struct CommandTemplate {
+    template: String,
+}
+
+impl CommandTemplate {
+    /// Creates a new [`CommandTemplate`][CommandTemplate] from a template.
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    /// Returns the arguments of the command (including the program) to play
+    /// the video at _path_ from _start_ seconds.
+    pub fn args(&self, path: &Path, start: f64) -> Result<Vec<OsString>> {
+        let args: Vec<OsString> = split(&self.template)?
+            .into_iter()
+            .map(|arg| match arg.as_str() {
+                // Keep the path untouched, it may not be valid UTF-8.
+                "{path}" => path.as_os_str().to_owned(),
+                _ => arg
+                    .replace("{path}", &path.to_string_lossy())
+                    .replace("{start}", &start.to_string())
+                    .into(),
+            })
+            .collect();
+
+        if args.is_empty() {
+            bail!("The player command is empty.");
+        }
+
+        Ok(args)
+    }
+
+    async fn run(args: Vec<OsString>, path: PathBuf, start: f64) -> Result<Playback> {
+        Process::new(&args[0])
+            .args(&args[1..])
+            .spawn()?
+            .wait()
+            .await
+            .map_err(|e| anyhow!("Could not play {}: {e}", path.display()))?;
+
+        Ok(Playback::unknown(start))
+    }
+}
+
+impl Player for CommandTemplate {
+    fn play(&self, path: &Path, start: f64) -> BoxFuture<'static, Result<Playback>> {
+        match self.args(path, start) {
+            Ok(args) => Box::pin(Self::run(args, path.to_path_buf(), start)),
+            Err(e) => Box::pin(async { Err(e) }),
+        }
+    }
+}
+
+/// Splits a command template into its arguments.
+/// Whitespaces inside single or double quotes are kept.
+fn split(template: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+
+    for c in template.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        bail!("Unclosed quote in the player command: {template}");
+    }
+
+    args.extend(current);
+    Ok(args)
+}
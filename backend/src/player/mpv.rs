@@ -0,0 +1,247 @@
+use super::{Playback, Player};
+use crate::Result;
+use crate::{Process, Running};
+
+use anyhow::bail;
+use core::fmt::Debug;
+use iced::futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tracing::{debug, info, warn};
+
+/// Properties observed on every mpv session, the index is used as the observer id.
+const OBSERVED_PROPERTIES: [&str; 3] = ["time-pos", "pause", "duration"];
+
+/// Time given to mpv to create its IPC socket before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static SESSION_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Events sent by mpv through its JSON-IPC socket while an episode is playing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MpvEvent {
+    /// Args: (Seconds since the start of the video)
+    Position(f64),
+    /// Args: (Is paused)
+    Pause(bool),
+    /// Args: (Duration in seconds)
+    Duration(f64),
+    /// The video was unloaded, `eof` is true if it was played until the end.
+    EndFile { eof: bool },
+}
+
+/// Raw JSON message sent by mpv, it can be an event or a reply to a command.
+#[derive(Deserialize, Debug)]
+struct RawMessage {
+    event: Option<String>,
+    name: Option<String>,
+    data: Option<Value>,
+    reason: Option<String>,
+}
+
+impl RawMessage {
+    /// Converts the raw message into a [`MpvEvent`][MpvEvent],
+    /// returns [`None`][None] for command replies and events yama doesn't care about.
+    fn into_event(self) -> Option<MpvEvent> {
+        match self.event.as_deref()? {
+            "property-change" => match (self.name.as_deref()?, self.data?) {
+                ("time-pos", Value::Number(n)) => Some(MpvEvent::Position(n.as_f64()?)),
+                ("duration", Value::Number(n)) => Some(MpvEvent::Duration(n.as_f64()?)),
+                ("pause", Value::Bool(paused)) => Some(MpvEvent::Pause(paused)),
+                _ => None,
+            },
+            "end-file" => Some(MpvEvent::EndFile {
+                eof: self.reason.as_deref() == Some("eof"),
+            }),
+            _ => None,
+        }
+    }
+}
+
+type Reader = Lines<BufReader<Box<dyn AsyncRead + Send + Unpin>>>;
+type Writer = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// A client connected to the JSON-IPC server of a running mpv instance.
+pub struct MpvIpc {
+    reader: Reader,
+    writer: Writer,
+}
+
+impl Debug for MpvIpc {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("Mpv IPC client")
+    }
+}
+
+impl MpvIpc {
+    /// Returns a new unique path for an mpv IPC server.
+    ///
+    /// A unix socket inside the temporary folder on Linux, and a named pipe on Windows.
+    pub fn socket_path() -> PathBuf {
+        let name = format!(
+            "yama-mpv-{}-{}",
+            std::process::id(),
+            SESSION_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+
+        if cfg!(target_os = "windows") {
+            PathBuf::from(format!(r"\\.\pipe\{name}"))
+        } else {
+            std::env::temp_dir().join(format!("{name}.sock"))
+        }
+    }
+
+    /// Connects to the IPC server located at _path_.
+    pub async fn connect(path: &Path) -> Result<Self> {
+        #[cfg(target_os = "windows")]
+        let stream = tokio::net::windows::named_pipe::ClientOptions::new().open(path)?;
+
+        #[cfg(not(target_os = "windows"))]
+        let stream = tokio::net::UnixStream::connect(path).await?;
+
+        let (reader, writer) = tokio::io::split(stream);
+        let reader: Box<dyn AsyncRead + Send + Unpin> = Box::new(reader);
+
+        Ok(Self {
+            reader: BufReader::new(reader).lines(),
+            writer: Box::new(writer),
+        })
+    }
+
+    /// Keeps trying to connect to the IPC server located at _path_,
+    /// mpv takes a bit to create it after being launched.
+    ///
+    /// Returns [`Error`][anyhow::Error] if the _process_ exits or the server doesn't show up in time.
+    pub async fn wait_for(path: &Path, process: &mut Running) -> Result<Self> {
+        let start = tokio::time::Instant::now();
+
+        loop {
+            match Self::connect(path).await {
+                Ok(ipc) => return Ok(ipc),
+                Err(e) => {
+                    if process.try_wait()?.is_some() {
+                        bail!("mpv exited before opening its IPC server.");
+                    }
+
+                    if start.elapsed() > CONNECT_TIMEOUT {
+                        bail!("Could not connect to mpv IPC server: {e}");
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }
+        }
+    }
+
+    /// Sends a command to mpv, the reply will be ignored.
+    pub async fn command(&mut self, command: Value) -> Result<()> {
+        let mut line = json!({ "command": command }).to_string();
+        line.push('\n');
+
+        self.writer.write_all(line.as_bytes()).await?;
+        Ok(self.writer.flush().await?)
+    }
+
+    /// Asks mpv to notify every change of the properties yama keeps track of.
+    pub async fn observe(&mut self) -> Result<()> {
+        for (id, property) in OBSERVED_PROPERTIES.iter().enumerate() {
+            self.command(json!(["observe_property", id + 1, property]))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the next [`MpvEvent`][MpvEvent].
+    ///
+    /// Returns [`None`][None] once mpv closes the connection.
+    pub async fn next_event(&mut self) -> Result<Option<MpvEvent>> {
+        while let Some(line) = self.reader.next_line().await? {
+            match serde_json::from_str::<RawMessage>(&line) {
+                Ok(msg) => {
+                    if let Some(event) = msg.into_event() {
+                        return Ok(Some(event));
+                    }
+                }
+                Err(e) => debug!("Unknown mpv message {line}: {e}"),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Follows the events of mpv until it closes the connection.
+    ///
+    /// Returns the final state of the [`Playback`][Playback].
+    pub async fn follow(mut self, mut playback: Playback) -> Result<Playback> {
+        self.observe().await?;
+
+        // mpv closes the connection when it quits, but a broken pipe
+        // halfway through still leaves us with the last known position.
+        loop {
+            match self.next_event().await {
+                Ok(Some(event)) => playback.apply(event),
+                Ok(None) => break,
+                Err(e) => {
+                    info!("Lost connection with mpv: {e}");
+                    break;
+                }
+            }
+        }
+
+        Ok(playback)
+    }
+}
+
+/// [mpv] media player, followed through its JSON-IPC server.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Mpv;
+
+impl Mpv {
+    /// Launches mpv for the video at _path_ starting at _start_ seconds,
+    /// and follows its [`Playback`][Playback] through the IPC server until it quits.
+    async fn run(path: PathBuf, start: f64) -> Result<Playback> {
+        let socket = MpvIpc::socket_path();
+
+        #[cfg(not(target_os = "windows"))]
+        let _ = std::fs::remove_file(&socket);
+
+        let mut process = Process::new("mpv")
+            .arg(format!("--input-ipc-server={}", socket.display()))
+            .arg(format!("--start={start}"))
+            .arg("--")
+            .arg(&path)
+            .spawn()?;
+
+        let playback = match MpvIpc::wait_for(&socket, &mut process).await {
+            Ok(ipc) => ipc.follow(Playback::new(start)).await,
+            Err(e) => Err(e),
+        };
+
+        let status = process.wait().await;
+
+        #[cfg(not(target_os = "windows"))]
+        let _ = std::fs::remove_file(&socket);
+
+        match (playback, status) {
+            // The playback was followed, so it's kept even if mpv quit with an error code.
+            (Ok(playback), Err(e)) => {
+                warn!("{e}");
+                Ok(playback)
+            }
+            (Ok(playback), Ok(())) => Ok(playback),
+            // If mpv failed its stderr tells why, so it goes first.
+            (Err(_), Err(e)) | (Err(e), Ok(())) => Err(e),
+        }
+    }
+}
+
+impl Player for Mpv {
+    fn play(&self, path: &Path, start: f64) -> BoxFuture<'static, Result<Playback>> {
+        Box::pin(Self::run(path.to_path_buf(), start))
+    }
+}
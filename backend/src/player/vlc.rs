@@ -0,0 +1,191 @@
+use super::{Playback, Player};
+use crate::Result;
+use crate::{Process, Running};
+
+use anyhow::bail;
+use core::fmt::Debug;
+use iced::futures::future::BoxFuture;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn};
+
+/// Time given to VLC to start its remote control interface before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often VLC is asked for its position.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+type Reader = BufReader<Box<dyn AsyncRead + Send + Unpin>>;
+type Writer = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// A client connected to the remote control (RC) interface of a running VLC instance.
+pub struct VlcRc {
+    reader: Reader,
+    writer: Writer,
+}
+
+impl Debug for VlcRc {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("VLC RC client")
+    }
+}
+
+impl VlcRc {
+    /// Returns a free loopback address for a VLC RC interface.
+    pub async fn free_address() -> Result<SocketAddr> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        Ok(listener.local_addr()?)
+    }
+
+    /// Connects to the RC interface listening at _address_.
+    pub async fn connect(address: SocketAddr) -> Result<Self> {
+        let (reader, writer) = TcpStream::connect(address).await?.into_split();
+        let reader: Box<dyn AsyncRead + Send + Unpin> = Box::new(reader);
+
+        Ok(Self {
+            reader: BufReader::new(reader),
+            writer: Box::new(writer),
+        })
+    }
+
+    /// Keeps trying to connect to the RC interface listening at _address_,
+    /// VLC takes a bit to start it after being launched.
+    ///
+    /// Returns [`Error`][anyhow::Error] if the _process_ exits or the interface doesn't show up in time.
+    pub async fn wait_for(address: SocketAddr, process: &mut Running) -> Result<Self> {
+        let start = tokio::time::Instant::now();
+
+        loop {
+            match Self::connect(address).await {
+                Ok(rc) => return Ok(rc),
+                Err(e) => {
+                    if process.try_wait()?.is_some() {
+                        bail!("VLC exited before opening its RC interface.");
+                    }
+
+                    if start.elapsed() > CONNECT_TIMEOUT {
+                        bail!("Could not connect to the VLC RC interface: {e}");
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }
+        }
+    }
+
+    /// Sends a command like `get_time` and returns the number it answers,
+    /// or [`None`][None] if it answers nothing, like when no video is loaded yet.
+    ///
+    /// Returns [`Error`][anyhow::Error] once VLC closes the connection.
+    pub async fn ask(&mut self, command: &str) -> Result<Option<f64>> {
+        self.writer
+            .write_all(format!("{command}\n").as_bytes())
+            .await?;
+        self.writer.flush().await?;
+
+        // The answers come after the prompt, and the greeting is skipped as it's not a number.
+        // A line without its end is the last prompt before VLC quits.
+        let mut line = String::new();
+        while self.reader.read_line(&mut line).await? > 0 && line.ends_with('\n') {
+            let answer = line.trim_start_matches(['>', ' ']).trim();
+
+            if answer.is_empty() {
+                return Ok(None);
+            }
+
+            if let Ok(number) = answer.parse() {
+                return Ok(Some(number));
+            }
+
+            line.clear();
+        }
+
+        bail!("VLC closed the connection.")
+    }
+
+    /// Asks VLC for the position and duration of the video every [`POLL_INTERVAL`][POLL_INTERVAL],
+    /// until it closes the connection.
+    ///
+    /// Returns the final state of the [`Playback`][Playback], the position is the last one
+    /// known once the video was loaded.
+    pub async fn follow(mut self, mut playback: Playback) -> Result<Playback> {
+        loop {
+            let answers = match self.ask("get_length").await {
+                Ok(length) => self.ask("get_time").await.map(|time| (length, time)),
+                Err(e) => Err(e),
+            };
+
+            match answers {
+                // Before the video is loaded VLC answers zero.
+                Ok((Some(length), Some(time))) if length > 0.0 => {
+                    playback.duration = length;
+                    playback.position = Some(time);
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    info!("Lost connection with VLC: {e}");
+                    break;
+                }
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        Ok(playback)
+    }
+}
+
+/// [VLC] media player, followed through its remote control interface.
+///
+/// If the interface can't be reached the position is unknown, see [`Playback`][Playback].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Vlc;
+
+impl Vlc {
+    /// Launches VLC for the video at _path_ starting at _start_ seconds,
+    /// and follows its [`Playback`][Playback] through the RC interface until it quits.
+    async fn run(path: PathBuf, start: f64) -> Result<Playback> {
+        let address = VlcRc::free_address().await?;
+
+        let mut process = Process::new("vlc")
+            .arg("--play-and-exit")
+            .arg(format!("--start-time={start}"))
+            .arg("--extraintf=rc")
+            .arg(format!("--rc-host={address}"));
+
+        if cfg!(target_os = "windows") {
+            process = process.arg("--rc-quiet");
+        }
+
+        let mut process = process.arg("--").arg(&path).spawn()?;
+
+        let playback = match VlcRc::wait_for(address, &mut process).await {
+            Ok(rc) => rc.follow(Playback::new(start)).await,
+            Err(e) => {
+                warn!("The position of the episode will be unknown: {e}");
+                Ok(Playback::unknown(start))
+            }
+        };
+
+        match process.wait().await {
+            Ok(()) => playback,
+            // The playback was followed, so it's kept even if VLC quit with an error code.
+            Err(e) => match playback {
+                Ok(playback) if playback.position.is_some() => {
+                    warn!("{e}");
+                    Ok(playback)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+impl Player for Vlc {
+    fn play(&self, path: &Path, start: f64) -> BoxFuture<'static, Result<Playback>> {
+        Box::pin(Self::run(path.to_path_buf(), start))
+    }
+}
@@ -2,3 +2,61 @@
 fn todo_test() {
     assert_eq!(1, 1)
 }
+
+#[test]
+fn title_folders_of_a_library() {
+    use backend::Backend;
+    use std::fs;
+
+    let library = std::env::temp_dir().join(format!("yama-title-folders-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&library);
+    fs::create_dir_all(&library).unwrap();
+
+    assert!(Backend::title_folders(&library).unwrap().is_empty());
+
+    fs::write(library.join("episode.mkv"), b"").unwrap();
+    assert!(Backend::title_folders(&library).unwrap().is_empty());
+
+    fs::create_dir_all(library.join("Cowboy Bebop")).unwrap();
+    fs::create_dir_all(library.join(".hidden")).unwrap();
+    assert_eq!(
+        Backend::title_folders(&library).unwrap(),
+        [library.join("Cowboy Bebop")]
+    );
+
+    fs::remove_dir_all(&library).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn video_files_skip_linked_folders() {
+    use backend::Title;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+
+    let title = std::env::temp_dir().join(format!("yama-video-files-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&title);
+    fs::create_dir_all(title.join("Season 1/Extras/Deeper")).unwrap();
+    fs::write(title.join("Season 1/01.mkv"), b"").unwrap();
+    fs::write(title.join("Season 1/Extras/NCOP.mkv"), b"").unwrap();
+    fs::write(title.join("Season 1/Extras/Deeper/too-deep.mkv"), b"").unwrap();
+    fs::write(title.join(".hidden.mkv"), b"").unwrap();
+
+    // A loop back to the title, and a linked episode.
+    symlink(&title, title.join("Season 1/loop")).unwrap();
+    symlink(title.join("Season 1/01.mkv"), title.join("linked.mkv")).unwrap();
+
+    let mut files = Title::get_video_files(&title).unwrap();
+    files.sort();
+
+    assert_eq!(
+        files,
+        [
+            title.join("Season 1/01.mkv"),
+            title.join("Season 1/Extras/NCOP.mkv"),
+            title.join("linked.mkv"),
+        ]
+    );
+
+    fs::remove_dir_all(&title).unwrap();
+}
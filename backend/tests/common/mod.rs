@@ -0,0 +1,10 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// Returns an empty folder for a test, unique to this run so tests running at the same time don't clash.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("yama-test-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.canonicalize().unwrap()
+}
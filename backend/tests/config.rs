@@ -0,0 +1,93 @@
+use backend::{Config, Library, TitleSort};
+
+use std::path::PathBuf;
+
+#[test]
+fn series_path_becomes_a_library() {
+    let mut cfg: Config = toml::from_str(
+        r#"
+        series_path = "/media/anime"
+        theme_path = "themes/iced.json"
+        min_time = 10.0
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(cfg.libraries(), vec![Library::new("anime", "/media/anime")]);
+
+    assert!(cfg.add_library(PathBuf::from("/mnt/usb/anime")));
+    assert!(!cfg.add_library(PathBuf::from("/media/anime")));
+    assert_eq!(cfg.series_path, None);
+    assert_eq!(
+        cfg.libraries(),
+        vec![
+            Library::new("anime", "/media/anime"),
+            Library::new("anime 2", "/mnt/usb/anime"),
+        ]
+    );
+}
+
+#[test]
+fn libraries_are_saved() {
+    let mut cfg = Config::default();
+    cfg.add_library(PathBuf::from("/media/ssd"));
+    cfg.add_library(PathBuf::from("/mnt/nas"));
+    cfg.toggle_library(1);
+
+    let cfg: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+    let libraries = cfg.libraries();
+
+    assert!(libraries[0].enabled);
+    assert!(!libraries[1].enabled);
+    assert_eq!(libraries[1].name, "nas");
+}
+
+#[test]
+fn title_sort_is_saved() {
+    let cfg = Config::default();
+    assert_eq!(cfg.title_sort, TitleSort::Folder);
+
+    let mut sort = cfg.title_sort;
+    for _ in 0..TitleSort::ALL.len() {
+        sort = sort.next();
+    }
+    assert_eq!(sort, TitleSort::Folder);
+
+    let cfg = Config {
+        title_sort: TitleSort::RecentlyWatched,
+        ..cfg
+    };
+    let text = toml::to_string(&cfg).unwrap();
+    assert!(text.contains(r#"title_sort = "recently_watched""#));
+
+    let cfg: Config = toml::from_str(&text).unwrap();
+    assert_eq!(cfg.title_sort, TitleSort::RecentlyWatched);
+}
+
+#[test]
+fn api_is_opt_in() {
+    let cfg = Config::default();
+    assert!(!cfg.api.enabled);
+    assert!(cfg.api.address.ip().is_loopback());
+    assert!(cfg.api.token.is_none());
+
+    let cfg: Config = toml::from_str(
+        r#"
+        theme_path = "themes/iced.json"
+        min_time = 10.0
+
+        [api]
+        enabled = true
+        address = "0.0.0.0:8080"
+        token = "so-pretty-so-yama"
+        "#,
+    )
+    .unwrap();
+    assert!(cfg.api.enabled);
+    assert_eq!(cfg.api.address.port(), 8080);
+    assert_eq!(cfg.api.token.as_deref(), Some("so-pretty-so-yama"));
+    assert!(cfg.api.allowed_origins.is_empty());
+
+    let text = toml::to_string(&cfg).unwrap();
+    assert!(text.contains("[api]"));
+}
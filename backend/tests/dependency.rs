@@ -0,0 +1,42 @@
+mod common;
+
+use backend::{find_program, PlayerConfig};
+use common::temp_dir;
+
+use std::ffi::OsString;
+use std::fs;
+
+#[test]
+fn programs_are_found_in_the_path() {
+    let dir = temp_dir("dependency");
+    let (first, second) = (dir.join("first"), dir.join("second"));
+    fs::create_dir_all(&first).unwrap();
+    fs::create_dir_all(&second).unwrap();
+    fs::write(second.join("ffprobe"), "").unwrap();
+    fs::write(first.join("ffprobe"), "").unwrap();
+    fs::write(second.join("mpv"), "").unwrap();
+
+    let paths: OsString = std::env::join_paths([&first, &second]).unwrap();
+
+    assert_eq!(find_program("ffprobe", &paths), Some(first.join("ffprobe")));
+    assert_eq!(find_program("mpv", &paths), Some(second.join("mpv")));
+    assert_eq!(find_program("ffmpeg", &paths), None);
+
+    // Programs with a folder are not searched.
+    let mpv = second.join("mpv");
+    assert_eq!(find_program(mpv.to_str().unwrap(), &paths), Some(mpv));
+    assert_eq!(find_program("./mpv", &paths), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn player_program() {
+    assert_eq!(PlayerConfig::Mpv.program().as_deref(), Some("mpv"));
+    assert_eq!(PlayerConfig::Vlc.program().as_deref(), Some("vlc"));
+
+    let custom = PlayerConfig::Command {
+        template: String::from("\"/opt/my player/play\" --start={start} {path}"),
+    };
+    assert_eq!(custom.program().as_deref(), Some("/opt/my player/play"));
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use backend::{Check, CheckStatus, Config, Dependency, Diagnostics};
+use common::temp_dir;
+
+use std::fs;
+
+#[test]
+fn missing_programs_fail_with_a_remedy() {
+    let ffprobe = Dependency {
+        program: String::from("ffprobe"),
+        used_for: "Read the duration of the episodes",
+        path: None,
+    };
+
+    let check = Check::program(&ffprobe);
+    assert_eq!(check.status, CheckStatus::Failed);
+    assert!(check.remedy.unwrap().contains("https://ffmpeg.org"));
+
+    let diagnostics = Diagnostics {
+        checks: vec![Check::program(&ffprobe)],
+    };
+    assert!(!diagnostics.passed());
+    assert!(diagnostics
+        .to_string()
+        .starts_with("[Failed] ffprobe: Not found in the PATH\n    -> Install FFmpeg"));
+}
+
+#[test]
+fn mpv_version_is_checked() {
+    let status = |version| Check::mpv_ipc(version).status;
+
+    assert_eq!(
+        status("mpv 0.35.1 Copyright © 2000-2023 mpv/MPlayer/mplayer2 projects"),
+        CheckStatus::Ok
+    );
+    assert_eq!(status("mpv v0.29.1-dirty Copyright"), CheckStatus::Ok);
+    assert_eq!(status("mpv 0.14.0 (C) 2000-2015"), CheckStatus::Failed);
+    assert_eq!(status("mpv git-2a3c3e4"), CheckStatus::Warning);
+}
+
+#[test]
+fn folders_and_config_are_checked() {
+    let dir = temp_dir("diagnostics");
+
+    // Missing folders are created.
+    let check = Check::folder("Logs folder", &dir.join("logs"), "its logs");
+    assert_eq!(check.status, CheckStatus::Ok);
+    assert!(dir.join("logs").is_dir());
+
+    let file = dir.join("file");
+    fs::write(&file, "").unwrap();
+    let check = Check::folder("Logs folder", &file, "its logs");
+    assert_eq!(check.status, CheckStatus::Failed);
+    assert!(check.remedy.is_some());
+
+    let config = dir.join("config.toml");
+    assert_eq!(Check::config_file(&config).status, CheckStatus::Ok);
+    fs::write(&config, "min_time = \"ten\"").unwrap();
+    assert_eq!(Check::config_file(&config).status, CheckStatus::Failed);
+
+    let mut cfg = Config::default();
+    assert_eq!(Check::libraries(&cfg)[0].status, CheckStatus::Failed);
+    cfg.add_library(dir.join("logs"));
+    cfg.add_library(dir.join("missing"));
+    let statuses: Vec<CheckStatus> = Check::libraries(&cfg)
+        .into_iter()
+        .map(|check| check.status)
+        .collect();
+    assert_eq!(statuses, [CheckStatus::Ok, CheckStatus::Failed]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn libraries_of_a_broken_config_are_not_checked() {
+    let dir = temp_dir("diagnostics-broken");
+    fs::write(dir.join("config.toml"), "min_time = \"ten\"").unwrap();
+
+    let mut cfg = Config::default();
+    cfg.add_library(dir.join("missing"));
+
+    let diagnostics = Diagnostics::in_config_dir(&cfg, &dir);
+    let config = diagnostics
+        .checks
+        .iter()
+        .find(|check| check.name == "Config file")
+        .unwrap();
+
+    assert_eq!(config.status, CheckStatus::Failed);
+    assert!(!diagnostics.passed());
+    assert!(diagnostics
+        .checks
+        .iter()
+        .all(|check| !check.name.starts_with("Library")));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use backend::{EpisodeId, MetadataStore, Title};
+use common::temp_dir;
+
+use std::fs;
+
+#[test]
+fn survives_renames() {
+    let dir = temp_dir("rename");
+    let old = dir.join("[Group] Show - 01.mkv");
+    let new = dir.join("Show S01E01.mkv");
+
+    fs::write(&old, b"episode one").unwrap();
+    let id = EpisodeId::new(&old).unwrap();
+    fs::rename(&old, &new).unwrap();
+
+    assert_eq!(EpisodeId::new(&new).unwrap(), id);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn depends_on_the_content() {
+    let dir = temp_dir("content");
+    let first = dir.join("01.mkv");
+    let second = dir.join("02.mkv");
+
+    // Same size and start, only the end is different.
+    let mut content = vec![0; 3 * 1024 * 1024];
+    fs::write(&first, &content).unwrap();
+    *content.last_mut().unwrap() = 1;
+    fs::write(&second, &content).unwrap();
+
+    assert_ne!(
+        EpisodeId::new(&first).unwrap(),
+        EpisodeId::new(&second).unwrap()
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn migrates_index_folders() {
+    let dir = temp_dir("migrate").join("Show");
+    let paths = [dir.join("Show - 01.mkv"), dir.join("Show - 02.mkv")];
+
+    fs::create_dir_all(dir.join(".metadata/episode_0")).unwrap();
+    fs::create_dir_all(dir.join(".metadata/episode_1")).unwrap();
+    fs::write(&paths[0], b"episode one").unwrap();
+    fs::write(&paths[1], b"episode two").unwrap();
+    // A new episode was added before, so the indexes are shifted.
+    fs::write(dir.join(".metadata/episode_0/Show - 02.md"), "two").unwrap();
+    fs::write(dir.join(".metadata/episode_1/Show - 01.md"), "one").unwrap();
+
+    Title::new(dir.clone(), &MetadataStore::Local)
+        .unwrap()
+        .migrate_metadata(&paths);
+
+    for (path, content) in paths.iter().zip(["one", "two"]) {
+        let id = EpisodeId::new(path).unwrap();
+        let metadata = dir.join(".metadata/episodes").join(id.as_str());
+        assert_eq!(
+            fs::read_to_string(metadata.join("metadata.md")).unwrap(),
+            content
+        );
+    }
+
+    assert!(!dir.join(".metadata/episode_0").exists());
+    assert!(!dir.join(".metadata/episode_1").exists());
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
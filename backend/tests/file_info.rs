@@ -0,0 +1,129 @@
+use backend::{EpisodeKind, FileInfo};
+
+#[test]
+fn release_group_version_and_resolution() {
+    let info = FileInfo::parse("[Group] Show - 07v2 [1080p]");
+
+    assert_eq!(info.group.as_deref(), Some("Group"));
+    assert_eq!(info.title.as_deref(), Some("Show"));
+    assert_eq!(info.episode, Some(7.0));
+    assert_eq!(info.version, Some(2));
+    assert_eq!(info.resolution.as_deref(), Some("1080p"));
+    assert_eq!(info.kind, EpisodeKind::Regular);
+}
+
+#[test]
+fn checksum_is_not_a_group() {
+    let info = FileInfo::parse("[SubsPlease] Sousou no Frieren - 12 (1080p) [A1B2C3D4]");
+
+    assert_eq!(info.group.as_deref(), Some("SubsPlease"));
+    assert_eq!(info.title.as_deref(), Some("Sousou no Frieren"));
+    assert_eq!(info.episode, Some(12.0));
+    assert_eq!(info.version, None);
+}
+
+#[test]
+fn dotted_season_episode() {
+    let info = FileInfo::parse("Show.Name.S02E05.1080p.WEB-DL");
+
+    assert_eq!(info.title.as_deref(), Some("Show Name"));
+    assert_eq!(info.season, Some(2));
+    assert_eq!(info.episode, Some(5.0));
+    assert_eq!(info.resolution.as_deref(), Some("1080p"));
+}
+
+#[test]
+fn numbers_in_titles() {
+    let info = FileInfo::parse("[Group] 86 - 05 [720p]");
+    assert_eq!(info.title.as_deref(), Some("86"));
+    assert_eq!(info.episode, Some(5.0));
+
+    let info = FileInfo::parse("Mob Psycho 100 - 03");
+    assert_eq!(info.title.as_deref(), Some("Mob Psycho 100"));
+    assert_eq!(info.episode, Some(3.0));
+
+    let info = FileInfo::parse("01 - The Beginning");
+    assert_eq!(info.title, None);
+    assert_eq!(info.episode, Some(1.0));
+}
+
+#[test]
+fn seasons() {
+    let info = FileInfo::parse("Show 2nd Season - 03");
+    assert_eq!(info.title.as_deref(), Some("Show"));
+    assert_eq!(info.season, Some(2));
+    assert_eq!(info.episode, Some(3.0));
+
+    let info = FileInfo::parse("Show Season 3 Episode 10");
+    assert_eq!(info.season, Some(3));
+    assert_eq!(info.episode, Some(10.0));
+}
+
+#[test]
+fn extras() {
+    let info = FileInfo::parse("[Group] Show - NCOP1 [1080p]");
+    assert_eq!(info.kind, EpisodeKind::Opening);
+    assert_eq!(info.episode, Some(1.0));
+
+    let info = FileInfo::parse("[Group] Show - NCED [1080p]");
+    assert_eq!(info.kind, EpisodeKind::Ending);
+    assert_eq!(info.episode, None);
+
+    let info = FileInfo::parse("[Group] Show OVA [DVD]");
+    assert_eq!(info.kind, EpisodeKind::Ova);
+
+    let info = FileInfo::parse("[Group] Show - SP02");
+    assert_eq!(info.kind, EpisodeKind::Special);
+    assert_eq!(info.episode, Some(2.0));
+}
+
+#[test]
+fn batches_and_decimals() {
+    let info = FileInfo::parse("[Group] Show - 01-12 [Batch]");
+    assert_eq!(info.episode, Some(1.0));
+    assert_eq!(info.episode_end, Some(12.0));
+
+    let info = FileInfo::parse("Show - 07.5 [720p]");
+    assert_eq!(info.episode, Some(7.5));
+}
+
+#[test]
+fn listing_order() {
+    let mut names = vec![
+        "[Group] Show - NCOP [1080p]",
+        "[Group] Show - 10 [1080p]",
+        "[Group] Show - 02 [1080p]",
+        "[Group] Show - 07v2 [1080p]",
+        "[Group] Show - 07 [1080p]",
+        "[Group] Show - OVA [1080p]",
+        "[Group] Show - 01 [1080p]",
+    ];
+
+    names.sort_by(|a, b| FileInfo::parse(a).cmp_order(&FileInfo::parse(b)));
+
+    assert_eq!(
+        names,
+        vec![
+            "[Group] Show - 01 [1080p]",
+            "[Group] Show - 02 [1080p]",
+            "[Group] Show - 07 [1080p]",
+            "[Group] Show - 07v2 [1080p]",
+            "[Group] Show - 10 [1080p]",
+            "[Group] Show - OVA [1080p]",
+            "[Group] Show - NCOP [1080p]",
+        ]
+    );
+}
+
+#[test]
+fn only_ascii_digits_are_numbers() {
+    // Four bytes long, but the second digit is a full-width one.
+    let info = FileInfo::parse("Show - 2０");
+    assert_eq!(info.episode, None);
+
+    let info = FileInfo::parse("Show - ٣");
+    assert_eq!(info.episode, None);
+
+    assert_eq!(FileInfo::parse("Show - 2009").episode, Some(2009.0));
+    assert_eq!(FileInfo::parse("Show 2009").episode, None);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use backend::{History, HistoryEntry, HistoryFormat};
+use common::temp_dir;
+
+use std::fs;
+
+fn entry(episode: &str, started_at: u64, completed: bool) -> HistoryEntry {
+    HistoryEntry {
+        title: String::from("Cowboy Bebop"),
+        episode: episode.to_string(),
+        started_at,
+        ended_at: started_at + 1_440,
+        start: 0.0,
+        end: Some(1_440.0),
+        completed,
+    }
+}
+
+#[test]
+fn history_is_appended() {
+    let dir = temp_dir("history-append");
+    let history = History::new(dir.join("history.jsonl"));
+
+    assert_eq!(history.entries().unwrap(), Vec::new());
+
+    let first = entry("Asteroid Blues", 1_693_603_800, true);
+    let second = entry("Stray Dog Strut", 1_693_690_200, false);
+    history.append(&first).unwrap();
+    history.append(&second).unwrap();
+
+    // A line cut by a crash doesn't lose the rest.
+    let path = dir.join("history.jsonl");
+    let mut content = fs::read_to_string(&path).unwrap();
+    content.push_str("{\"title\":\"Cowb\n");
+    fs::write(&path, content).unwrap();
+
+    assert_eq!(history.entries().unwrap(), vec![first.clone(), second]);
+    assert_eq!(first.date(), "2023-09-01 21:30");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn history_is_exported() {
+    let dir = temp_dir("history-export");
+    let history = History::new(dir.join("history.jsonl"));
+
+    history
+        .append(&entry("Honky Tonk Women, Part 1", 1_693_603_800, true))
+        .unwrap();
+
+    history
+        .export(&dir.join("history.csv"), HistoryFormat::Csv)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.join("history.csv")).unwrap(),
+        "title,episode,started_at,ended_at,start,end,completed\n\
+         Cowboy Bebop,\"Honky Tonk Women, Part 1\",1693603800,1693605240,0.0,1440.0,true\n"
+    );
+
+    history
+        .export(&dir.join("history.json"), HistoryFormat::Json)
+        .unwrap();
+    let exported: Vec<HistoryEntry> =
+        serde_json::from_str(&fs::read_to_string(dir.join("history.json")).unwrap()).unwrap();
+    assert_eq!(exported, history.entries().unwrap());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use backend::{MetadataStore, Title};
+use common::temp_dir;
+
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn local_store_is_inside_the_title() {
+    let path = PathBuf::from("/series/Show");
+
+    assert_eq!(
+        MetadataStore::Local.title_dir(&path),
+        PathBuf::from("/series/Show/.metadata")
+    );
+}
+
+#[test]
+fn central_store_imports_once() {
+    let dir = temp_dir("central");
+    let library = dir.join("series");
+    let title = library.join("Show");
+    let store = MetadataStore::Central {
+        root: dir.join("store"),
+        library: library.clone(),
+    };
+
+    fs::create_dir_all(title.join(".metadata/episodes/0123456789abcdef")).unwrap();
+    fs::write(title.join(".metadata/settings.json"), "{}").unwrap();
+    fs::write(
+        title.join(".metadata/episodes/0123456789abcdef/metadata.md"),
+        "progress",
+    )
+    .unwrap();
+
+    let loaded = Title::new(title.clone(), &store).unwrap();
+    assert_eq!(loaded.metadata_dir, dir.join("store/Show"));
+    assert_eq!(
+        fs::read_to_string(
+            loaded
+                .metadata_dir
+                .join("episodes/0123456789abcdef/metadata.md")
+        )
+        .unwrap(),
+        "progress"
+    );
+
+    // The series folder is left as it was, and it's not imported again.
+    fs::write(title.join(".metadata/settings.json"), "changed").unwrap();
+    assert!(!store.import(&title).unwrap());
+    assert_eq!(
+        fs::read_to_string(loaded.metadata_dir.join("settings.json")).unwrap(),
+        "{}"
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,131 @@
+#![cfg(unix)]
+
+use backend::{MpvEvent, MpvIpc, Playback, VideoMetadata};
+
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixListener;
+
+/// Starts a fake mpv IPC server that waits for the property observers
+/// and then sends each one of the _events_ lines.
+fn fake_mpv(name: &str, events: &'static [&'static str]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("yama-test-{name}-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        for _ in 0..3 {
+            let line = lines.next_line().await.unwrap().unwrap();
+            assert!(line.contains("observe_property"));
+            writer
+                .write_all(b"{\"request_id\":0,\"error\":\"success\"}\n")
+                .await
+                .unwrap();
+        }
+
+        for event in events {
+            writer.write_all(event.as_bytes()).await.unwrap();
+            writer.write_all(b"\n").await.unwrap();
+        }
+    });
+
+    path
+}
+
+#[tokio::test]
+async fn events_are_parsed() {
+    let path = fake_mpv(
+        "events",
+        &[
+            r#"{"event":"property-change","id":3,"name":"duration","data":1440.0}"#,
+            r#"{"event":"property-change","id":1,"name":"time-pos","data":null}"#,
+            r#"{"event":"playback-restart"}"#,
+            r#"{"event":"property-change","id":1,"name":"time-pos","data":12.5}"#,
+            r#"{"event":"property-change","id":2,"name":"pause","data":true}"#,
+            r#"{"event":"end-file","reason":"quit","playlist_entry_id":1}"#,
+        ],
+    );
+
+    let mut ipc = MpvIpc::connect(&path).await.unwrap();
+    ipc.observe().await.unwrap();
+
+    let mut events = Vec::new();
+    while let Some(event) = ipc.next_event().await.unwrap() {
+        events.push(event);
+    }
+
+    assert_eq!(
+        events,
+        vec![
+            MpvEvent::Duration(1440.0),
+            MpvEvent::Position(12.5),
+            MpvEvent::Pause(true),
+            MpvEvent::EndFile { eof: false },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn quitting_halfway_keeps_position() {
+    let path = fake_mpv(
+        "halfway",
+        &[
+            r#"{"event":"property-change","id":3,"name":"duration","data":1440.0}"#,
+            r#"{"event":"property-change","id":1,"name":"time-pos","data":600.0}"#,
+            r#"{"event":"end-file","reason":"quit"}"#,
+        ],
+    );
+
+    let ipc = MpvIpc::connect(&path).await.unwrap();
+    let playback = ipc.follow(Playback::new(300.0)).await.unwrap();
+
+    let mut metadata = VideoMetadata::default();
+    metadata.update(&playback, 10.0);
+
+    assert!(!metadata.watched);
+    assert_eq!(metadata.current, 600.0);
+    assert_eq!(metadata.remaining, 840.0);
+    assert_eq!(metadata.duration, 1440.0);
+}
+
+#[tokio::test]
+async fn reaching_the_end_marks_as_watched() {
+    let path = fake_mpv(
+        "eof",
+        &[
+            r#"{"event":"property-change","id":3,"name":"duration","data":1440.0}"#,
+            r#"{"event":"property-change","id":1,"name":"time-pos","data":1439.9}"#,
+            r#"{"event":"end-file","reason":"eof"}"#,
+        ],
+    );
+
+    let ipc = MpvIpc::connect(&path).await.unwrap();
+    let playback = ipc.follow(Playback::new(0.0)).await.unwrap();
+    assert!(playback.eof);
+
+    let mut metadata = VideoMetadata::default();
+    metadata.update(&playback, 10.0);
+
+    assert!(metadata.watched);
+    assert_eq!(metadata.current, 0.0);
+}
+
+#[test]
+fn unknown_duration_only_ends_at_the_end() {
+    let mut playback = Playback::new(0.0);
+    playback.position = Some(1.0);
+
+    let mut metadata = VideoMetadata::default();
+    metadata.update(&playback, 10.0);
+
+    assert!(!metadata.watched);
+    assert_eq!(metadata.current, 1.0);
+
+    playback.eof = true;
+    metadata.update(&playback, 10.0);
+    assert!(metadata.watched);
+}
@@ -0,0 +1,62 @@
+use backend::CommandTemplate;
+
+use std::ffi::OsString;
+use std::path::Path;
+
+#[test]
+fn template_placeholders_are_replaced() {
+    let template = CommandTemplate::new("vlc --play-and-exit --start-time={start} {path}");
+    let args = template
+        .args(Path::new("/anime/[Group] Show - 01.mkv"), 90.5)
+        .unwrap();
+
+    assert_eq!(
+        args,
+        vec![
+            OsString::from("vlc"),
+            OsString::from("--play-and-exit"),
+            OsString::from("--start-time=90.5"),
+            OsString::from("/anime/[Group] Show - 01.mkv"),
+        ]
+    );
+}
+
+#[test]
+fn template_quotes_keep_spaces() {
+    let template = CommandTemplate::new(r#""/opt/my player/run" --title 'yama {path}' {path}"#);
+    let args = template.args(Path::new("a $b `c`.mkv"), 0.0).unwrap();
+
+    assert_eq!(
+        args,
+        vec![
+            OsString::from("/opt/my player/run"),
+            OsString::from("--title"),
+            OsString::from("yama a $b `c`.mkv"),
+            OsString::from("a $b `c`.mkv"),
+        ]
+    );
+}
+
+#[test]
+fn invalid_templates_fail() {
+    assert!(CommandTemplate::new("")
+        .args(Path::new("a.mkv"), 0.0)
+        .is_err());
+    assert!(CommandTemplate::new("vlc \"{path}")
+        .args(Path::new("a.mkv"), 0.0)
+        .is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn commands_do_not_know_the_position() {
+    use backend::Player;
+
+    let playback = CommandTemplate::new("true {path}")
+        .play(Path::new("a.mkv"), 90.0)
+        .await
+        .unwrap();
+
+    assert_eq!(playback.start, 90.0);
+    assert_eq!(playback.position, None);
+}
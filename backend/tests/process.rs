@@ -0,0 +1,41 @@
+#![cfg(unix)]
+
+use backend::Process;
+
+#[test]
+fn arguments_are_not_interpreted() {
+    let name = "\"quoted\" $HOME `echo nope`.mkv";
+    let output = Process::new("printf").arg("%s").arg(name).run().unwrap();
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), name);
+}
+
+#[test]
+fn stderr_is_reported() {
+    let err = Process::new("sh")
+        .args(["-c", "echo 'No such file' >&2; exit 3"])
+        .run()
+        .unwrap_err();
+
+    assert!(err.to_string().contains("No such file"));
+}
+
+#[tokio::test]
+async fn stderr_is_reported_in_background() {
+    let err = Process::new("sh")
+        .args(["-c", "echo 'Invalid data found' >&2; exit 1"])
+        .spawn()
+        .unwrap()
+        .wait()
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("Invalid data found"));
+}
+
+#[test]
+fn missing_programs_fail() {
+    assert!(Process::new("yama-this-program-does-not-exist")
+        .run()
+        .is_err());
+}
@@ -0,0 +1,42 @@
+use backend::{next_episode, Progress};
+
+#[test]
+fn next_episode_continues_the_last_watched() {
+    // Never watched.
+    assert_eq!(next_episode([(false, None), (false, None)]), None);
+
+    // Left halfway.
+    assert_eq!(
+        next_episode([(true, Some(10)), (false, Some(20)), (false, None)]),
+        Some(1)
+    );
+
+    // Finished, the next one not watched yet, skipping the ones marked as watched.
+    assert_eq!(
+        next_episode([(true, Some(20)), (true, None), (false, None)]),
+        Some(2)
+    );
+
+    // Rewatched an old one, the most recent counts.
+    assert_eq!(
+        next_episode([
+            (true, Some(30)),
+            (false, None),
+            (true, Some(20)),
+            (false, None)
+        ]),
+        Some(1)
+    );
+
+    // Everything watched.
+    assert_eq!(next_episode([(true, Some(10)), (true, Some(20))]), None);
+}
+
+#[test]
+fn old_progress_files_are_read() {
+    let progress: Progress = serde_json::from_str(r#"{"episodes":12,"watched":3}"#).unwrap();
+
+    assert_eq!(progress.unwatched(), 9);
+    assert_eq!(progress.last_watched, None);
+    assert_eq!(progress.next, None);
+}
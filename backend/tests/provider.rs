@@ -0,0 +1,89 @@
+mod common;
+
+use backend::{Anilist, MetadataProvider, ProviderChain, Result, SeriesInfo, SeriesTitles};
+use common::temp_dir;
+
+use anyhow::bail;
+use iced::futures::future::BoxFuture;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Provider that only knows the series with its name.
+#[derive(Debug)]
+struct Fixed(&'static str);
+
+impl MetadataProvider for Fixed {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn series_info<'a>(
+        &'a self,
+        search: &'a str,
+        _ids: &'a BTreeMap<String, String>,
+        _dir: &'a Path,
+    ) -> BoxFuture<'a, Result<SeriesInfo>> {
+        Box::pin(async move {
+            if search != self.0 {
+                bail!("{search} not found");
+            }
+
+            Ok(SeriesInfo {
+                titles: SeriesTitles {
+                    romaji: Some(self.0.to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn chain_falls_back_in_order() {
+    let chain = ProviderChain::new(vec![Box::new(Fixed("first")), Box::new(Fixed("second"))]);
+    let (ids, dir) = (BTreeMap::new(), Path::new("."));
+
+    let info = chain.series_info("second", &ids, dir).await.unwrap();
+    assert_eq!(info.title(), Some("second"));
+    assert!(chain.series_info("third", &ids, dir).await.is_err());
+}
+
+#[tokio::test]
+async fn anilist_reads_older_downloads() {
+    let dir = temp_dir("anilist");
+    let json = r#"{"data": {"Media": {
+        "id": 21,
+        "title": {"romaji": "One Piece", "english": null, "native": "ONE PIECE"},
+        "description": "Gol D. Roger<br><br>was known as the <i>Pirate King</i>.",
+        "genres": ["Action", "Adventure"],
+        "bannerImage": "https://example.com/banner.jpg",
+        "studios": {"edges": [
+            {"isMain": false, "node": {"name": "Fuji TV"}},
+            {"isMain": true, "node": {"name": "Toei Animation"}}
+        ]}
+    }}}"#;
+    fs::write(dir.join("data.json"), json).unwrap();
+
+    let anilist = Anilist::default();
+    let info = anilist
+        .series_info("One Piece", &BTreeMap::new(), &dir)
+        .await
+        .unwrap();
+
+    assert_eq!(info.title(), Some("One Piece"));
+    assert_eq!(info.synopsis, "Gol D. Roger\nwas known as the Pirate King.");
+    assert_eq!(info.studio.as_deref(), Some("Toei Animation"));
+    assert_eq!(info.ids.get("anilist").map(String::as_str), Some("21"));
+    assert_eq!(info.images.thumbnail, None);
+
+    // Pinning the same media keeps using the download.
+    let pinned = BTreeMap::from([(String::from("anilist"), String::from("21"))]);
+    let pinned_info = anilist
+        .series_info("Wrong name", &pinned, &dir)
+        .await
+        .unwrap();
+    assert_eq!(pinned_info, info);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,20 @@
+use backend::Season;
+
+#[test]
+fn season_folders() {
+    assert_eq!(Season::from_folder("Season 1"), Some(1));
+    assert_eq!(Season::from_folder("S02"), Some(2));
+    assert_eq!(Season::from_folder("Show 3rd Season"), Some(3));
+    assert_eq!(Season::from_folder("Specials"), Some(0));
+    assert_eq!(Season::from_folder("Extras"), Some(0));
+    assert_eq!(Season::from_folder("OVA"), Some(0));
+    assert_eq!(Season::from_folder("Show"), None);
+}
+
+#[test]
+fn specials_go_last() {
+    let mut seasons = vec![0, 2, 1, 10];
+    seasons.sort_by(|a, b| Season::cmp_number(*a, *b));
+
+    assert_eq!(seasons, vec![1, 2, 10, 0]);
+}
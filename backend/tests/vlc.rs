@@ -0,0 +1,75 @@
+use backend::{Playback, VideoMetadata, VlcRc};
+
+use std::net::SocketAddr;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// Starts a fake VLC RC interface that greets like VLC does, answers each `get_length`
+/// and `get_time` with the _answers_ in order, and then quits.
+async fn fake_vlc(answers: &'static [(&'static str, &'static str)]) -> SocketAddr {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        writer
+            .write_all(b"VLC media player 3.0.18 Vetinari\r\nCommand Line Interface initialized. Type `help' for help.\r\n> ")
+            .await
+            .unwrap();
+
+        for (length, time) in answers {
+            for (command, answer) in [("get_length", length), ("get_time", time)] {
+                assert_eq!(lines.next_line().await.unwrap().unwrap(), command);
+                writer
+                    .write_all(format!("{answer}\r\n> ").as_bytes())
+                    .await
+                    .unwrap();
+            }
+        }
+    });
+
+    address
+}
+
+#[tokio::test]
+async fn answers_are_parsed() {
+    let address = fake_vlc(&[("", "12")]).await;
+    let mut rc = VlcRc::connect(address).await.unwrap();
+
+    assert_eq!(rc.ask("get_length").await.unwrap(), None);
+    assert_eq!(rc.ask("get_time").await.unwrap(), Some(12.0));
+    assert!(rc.ask("get_length").await.is_err());
+}
+
+#[tokio::test]
+async fn quitting_halfway_keeps_position() {
+    // Before the video is loaded VLC answers zero.
+    let address = fake_vlc(&[("0", "0"), ("1440", "600"), ("1440", "612")]).await;
+
+    let rc = VlcRc::connect(address).await.unwrap();
+    let playback = rc.follow(Playback::new(300.0)).await.unwrap();
+    assert_eq!(playback.position, Some(612.0));
+    assert_eq!(playback.duration, 1440.0);
+
+    let mut metadata = VideoMetadata::default();
+    metadata.update(&playback, 10.0);
+
+    assert!(!metadata.watched);
+    assert_eq!(metadata.current, 612.0);
+}
+
+#[test]
+fn unknown_position_keeps_the_progress() {
+    let mut metadata = VideoMetadata::default();
+    metadata.update(&Playback::new(300.0), 10.0);
+    assert_eq!(metadata.current, 300.0);
+
+    metadata.update(&Playback::unknown(300.0), 10.0);
+
+    assert!(!metadata.watched);
+    assert_eq!(metadata.current, 300.0);
+    assert!(metadata.last_watched.is_some());
+}
@@ -0,0 +1,50 @@
+use backend::{TitleSettings, WatchStatus};
+
+#[test]
+fn status_is_derived_from_the_progress() {
+    assert_eq!(WatchStatus::derive(None), WatchStatus::PlanToWatch);
+    assert_eq!(WatchStatus::derive(Some((0, 12))), WatchStatus::PlanToWatch);
+    assert_eq!(WatchStatus::derive(Some((3, 12))), WatchStatus::Watching);
+    assert_eq!(WatchStatus::derive(Some((12, 12))), WatchStatus::Completed);
+}
+
+#[test]
+fn status_cycles_back_to_derived() {
+    let mut status = None;
+
+    for expected in WatchStatus::ALL {
+        status = WatchStatus::next(status);
+        assert_eq!(status, Some(expected));
+    }
+
+    assert_eq!(WatchStatus::next(status), None);
+}
+
+#[test]
+fn status_is_parsed_and_saved() {
+    assert_eq!(WatchStatus::parse("on-hold"), Some(WatchStatus::OnHold));
+    assert_eq!(
+        WatchStatus::parse("Plan to Watch"),
+        Some(WatchStatus::PlanToWatch)
+    );
+    assert_eq!(
+        WatchStatus::parse("plan_to_watch"),
+        Some(WatchStatus::PlanToWatch)
+    );
+    assert_eq!(WatchStatus::parse("paused"), None);
+
+    let settings = TitleSettings {
+        status: Some(WatchStatus::OnHold),
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&settings).unwrap();
+    assert!(json.contains(r#""status":"on_hold""#));
+    assert_eq!(
+        serde_json::from_str::<TitleSettings>(&json).unwrap(),
+        settings
+    );
+
+    // Derived statuses are not saved.
+    let json = serde_json::to_string(&TitleSettings::default()).unwrap();
+    assert!(!json.contains("status"));
+}
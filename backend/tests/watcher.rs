@@ -0,0 +1,52 @@
+mod common;
+
+use backend::Watcher;
+use common::temp_dir;
+
+use std::fs;
+use std::time::Duration;
+
+#[tokio::test]
+async fn reports_new_files() {
+    let dir = temp_dir("watcher");
+    fs::create_dir_all(dir.join("Show/.metadata")).unwrap();
+
+    let mut watcher = Watcher::new(std::slice::from_ref(&dir))
+        .unwrap()
+        .with_debounce(Duration::from_millis(200));
+
+    fs::write(dir.join("Show/.metadata/settings.json"), b"{}").unwrap();
+    fs::write(dir.join("Show/01.mkv"), b"episode one").unwrap();
+
+    let paths = tokio::time::timeout(Duration::from_secs(10), watcher.next())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(paths.contains(&dir.join("Show/01.mkv")));
+    assert!(paths
+        .iter()
+        .all(|path| !path.starts_with(dir.join("Show/.metadata"))));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn watches_libraries_inside_hidden_folders() {
+    let dir = temp_dir("watcher-hidden");
+    let library = dir.join(".local/share/anime");
+    fs::create_dir_all(library.join("Show")).unwrap();
+
+    let mut watcher = Watcher::new(std::slice::from_ref(&library))
+        .unwrap()
+        .with_debounce(Duration::from_millis(200));
+
+    fs::write(library.join("Show/01.mkv"), b"episode one").unwrap();
+
+    let paths = tokio::time::timeout(Duration::from_secs(10), watcher.next())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(paths.contains(&library.join("Show/01.mkv")));
+    fs::remove_dir_all(dir).unwrap();
+}
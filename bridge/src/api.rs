@@ -0,0 +1,508 @@
+use crate::{EpisodeNumber, TitleIndex};
+
+use backend::{ApiConfig, Episode, Library, Meta, Title};
+
+use anyhow::bail;
+use core::fmt;
+use hyper::header::{self, HeaderValue};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// Requests waiting to be answered, the [`Session`][crate::session::Session] answers them one by one.
+const QUEUE_SIZE: usize = 16;
+
+/// Requests of the local HTTP API, answered by the [`Session`][crate::session::Session].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// `GET /api/libraries`
+    Libraries,
+    /// `GET /api/titles`
+    Titles,
+    /// `GET /api/titles/{id}`, with its episodes.
+    Title(TitleIndex),
+    /// `GET /api/status`
+    Status,
+    /// `GET /api/titles/{id}/thumbnail` or `GET /api/titles/{id}/episodes/{number}/thumbnail`
+    Thumbnail(TitleIndex, Option<EpisodeNumber>),
+    Command(ApiCommand),
+}
+
+/// Commands of the local HTTP API, run as a [`BackendMessage`][crate::BackendMessage].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiCommand {
+    /// `POST /api/titles/{id}/play` or `POST /api/titles/{id}/episodes/{number}/play`
+    ///
+    /// Args: (Title index, Episode number or the next one if it's none)
+    Play(TitleIndex, Option<EpisodeNumber>),
+    /// `PUT` or `DELETE /api/titles/{id}/episodes/{number}/watched`
+    ///
+    /// Args: (Title index, Episode number, Watched)
+    SetWatched(TitleIndex, EpisodeNumber, bool),
+    /// `POST /api/titles/{id}/refresh`
+    Refresh(TitleIndex),
+    /// `POST /api/rescan`
+    Rescan,
+}
+
+impl ApiCommand {
+    /// Returns the title the command runs on, none for a [`Rescan`][ApiCommand::Rescan].
+    pub fn title_number(&self) -> Option<TitleIndex> {
+        match *self {
+            ApiCommand::Play(title_number, _)
+            | ApiCommand::SetWatched(title_number, _, _)
+            | ApiCommand::Refresh(title_number) => Some(title_number),
+            ApiCommand::Rescan => None,
+        }
+    }
+}
+
+/// The content of a successful answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content {
+    Json(Value),
+    Image(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    BadRequest(String),
+    /// The token is missing or wrong.
+    Unauthorized,
+    /// The request comes from a web page not in the allowed origins.
+    Forbidden,
+    NotFound(String),
+    MethodNotAllowed,
+    /// The command could not be run, like playing while another episode plays.
+    Failed(String),
+    /// yama is closing or restarting.
+    Unavailable,
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ApiError::Failed(_) => StatusCode::CONFLICT,
+            ApiError::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::BadRequest(e) | ApiError::NotFound(e) | ApiError::Failed(e) => f.write_str(e),
+            ApiError::Unauthorized => f.write_str("Missing or wrong token"),
+            ApiError::Forbidden => f.write_str("Origin not allowed"),
+            ApiError::MethodNotAllowed => f.write_str("Method not allowed"),
+            ApiError::Unavailable => f.write_str("yama is not available, try again later"),
+        }
+    }
+}
+
+pub type Reply = Result<Content, ApiError>;
+
+/// A [`Request`][Request] along with where to send its [`Reply`][Reply].
+pub type Question = (Request, oneshot::Sender<Reply>);
+
+/// The local HTTP API server, it stops when it's dropped.
+#[derive(Debug)]
+pub struct Server {
+    /// Address it listens to, with the port chosen by the system if it was zero.
+    pub address: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl Server {
+    /// Starts listening at the address of the [`ApiConfig`][ApiConfig], returning the [`Server`][Server] along
+    /// with the [`Questions`][Question] received, which must be answered for the server to keep working.
+    ///
+    /// It's not started at an address other devices can reach without a token.
+    pub async fn bind(cfg: &ApiConfig) -> backend::Result<(Server, mpsc::Receiver<Question>)> {
+        let access = Arc::new(Access {
+            token: cfg.token.clone().filter(|token| !token.is_empty()),
+            allowed_origins: cfg.allowed_origins.clone(),
+        });
+
+        if access.token.is_none() && !cfg.address.ip().is_loopback() {
+            bail!(
+                "A token is needed to listen at {}, otherwise anyone in the network could control yama",
+                cfg.address
+            );
+        }
+
+        let builder = bind(cfg.address).await?;
+        let (sender, receiver) = mpsc::channel(QUEUE_SIZE);
+
+        let service = make_service_fn(move |_| {
+            let sender = sender.clone();
+            let access = access.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    respond(req, sender.clone(), access.clone())
+                }))
+            }
+        });
+
+        let server = builder.serve(service);
+        let address = server.local_addr();
+        info!("The API listens at http://{address}/api");
+
+        let task = tokio::spawn(async move {
+            if let Err(e) = server.await {
+                error!("The API stopped: {e}");
+            }
+        });
+
+        Ok((Server { address, task }, receiver))
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Binds the _address_, retrying for a moment if it's in use,
+/// as the [`Server`][Server] of a restarted [`Session`][crate::session::Session] may still be closing.
+async fn bind(
+    address: SocketAddr,
+) -> hyper::Result<hyper::server::Builder<hyper::server::conn::AddrIncoming>> {
+    let mut tries = 0;
+
+    loop {
+        match hyper::Server::try_bind(&address) {
+            Err(_) if tries < 10 => {
+                tries += 1;
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Who can use the API, from its [`ApiConfig`][ApiConfig].
+#[derive(Debug)]
+struct Access {
+    token: Option<String>,
+    allowed_origins: Vec<String>,
+}
+
+impl Access {
+    /// Returns the origin of the _req_ if it's from an allowed web page, none if it's not from a browser.
+    ///
+    /// Browsers send the requests of any page, even if they don't let it read the answer,
+    /// so the ones from other pages are refused before running their commands.
+    fn origin<'a>(
+        &self,
+        req: &'a hyper::Request<hyper::Body>,
+    ) -> Result<Option<&'a HeaderValue>, ApiError> {
+        match req.headers().get(header::ORIGIN) {
+            Some(origin)
+                if self
+                    .allowed_origins
+                    .iter()
+                    .any(|allowed| origin.as_bytes() == allowed.as_bytes()) =>
+            {
+                Ok(Some(origin))
+            }
+            Some(_) => Err(ApiError::Forbidden),
+            None => Ok(None),
+        }
+    }
+
+    /// Checks the `Authorization: Bearer <token>` header of the _req_, if there's a token.
+    fn authorize(&self, req: &hyper::Request<hyper::Body>) -> Result<(), ApiError> {
+        let Some(token) = &self.token else {
+            return Ok(());
+        };
+
+        let sent = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.as_bytes().strip_prefix(b"Bearer "))
+            .unwrap_or_default();
+
+        // Compared in constant time, to not tell how much of a guessed token is right.
+        let equal = sent.len() == token.len()
+            && sent
+                .iter()
+                .zip(token.as_bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0;
+
+        if equal {
+            Ok(())
+        } else {
+            Err(ApiError::Unauthorized)
+        }
+    }
+}
+
+async fn respond(
+    req: hyper::Request<hyper::Body>,
+    sender: mpsc::Sender<Question>,
+    access: Arc<Access>,
+) -> Result<hyper::Response<hyper::Body>, Infallible> {
+    let origin = match access.origin(&req) {
+        Ok(origin) => origin.cloned(),
+        Err(e) => return Ok(error_response(&e)),
+    };
+
+    let mut res = match req.method() {
+        // Browsers ask before sending the commands from an allowed dashboard.
+        &Method::OPTIONS => {
+            let mut res = response(StatusCode::NO_CONTENT, hyper::Body::empty(), None);
+            let headers = res.headers_mut();
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static("GET, POST, PUT, DELETE"),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static("authorization, content-type"),
+            );
+            res
+        }
+        method => {
+            let reply = match access.authorize(&req) {
+                Ok(()) => match route(method, req.uri().path()) {
+                    Ok(request) => ask(&sender, request).await,
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+
+            match reply {
+                Ok(Content::Json(value)) => json_response(StatusCode::OK, &value),
+                Ok(Content::Image(path)) => match tokio::fs::read(&path).await {
+                    Ok(image) => response(StatusCode::OK, image.into(), Some(image_type(&path))),
+                    Err(e) => {
+                        error!("Could not read {}: {e}", path.display());
+                        error_response(&ApiError::NotFound(String::from("No thumbnail found")))
+                    }
+                },
+                Err(e) => error_response(&e),
+            }
+        }
+    };
+
+    let headers = res.headers_mut();
+    if let Some(origin) = origin {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+    headers.insert(header::VARY, HeaderValue::from_static("origin"));
+
+    Ok(res)
+}
+
+/// Sends the _request_ to the [`Session`][crate::session::Session] and waits for its [`Reply`][Reply].
+async fn ask(sender: &mpsc::Sender<Question>, request: Request) -> Reply {
+    let (reply_sender, reply) = oneshot::channel();
+
+    if sender.send((request, reply_sender)).await.is_err() {
+        return Err(ApiError::Unavailable);
+    }
+
+    reply.await.unwrap_or(Err(ApiError::Unavailable))
+}
+
+/// Returns the [`Request`][Request] of the _path_, titles are found by their id
+/// and episodes by their number, starting at 1.
+pub fn route(method: &Method, path: &str) -> Result<Request, ApiError> {
+    let segments: Vec<&str> = path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    let id = |id: &str| {
+        id.parse::<TitleIndex>()
+            .map_err(|_| ApiError::BadRequest(format!("`{id}` is not a title id")))
+    };
+    let number = |number: &str| match number.parse::<EpisodeNumber>() {
+        Ok(number) if number > 0 => Ok(number - 1),
+        _ => Err(ApiError::BadRequest(format!(
+            "`{number}` is not an episode number, they start at 1"
+        ))),
+    };
+
+    let (expected, request) = match segments.as_slice() {
+        ["api", "libraries"] => (Method::GET, Request::Libraries),
+        ["api", "titles"] => (Method::GET, Request::Titles),
+        ["api", "status"] => (Method::GET, Request::Status),
+        ["api", "rescan"] => (Method::POST, Request::Command(ApiCommand::Rescan)),
+        ["api", "titles", t] => (Method::GET, Request::Title(id(t)?)),
+        ["api", "titles", t, "thumbnail"] => (Method::GET, Request::Thumbnail(id(t)?, None)),
+        ["api", "titles", t, "play"] => (
+            Method::POST,
+            Request::Command(ApiCommand::Play(id(t)?, None)),
+        ),
+        ["api", "titles", t, "refresh"] => {
+            (Method::POST, Request::Command(ApiCommand::Refresh(id(t)?)))
+        }
+        ["api", "titles", t, "episodes", e, "thumbnail"] => {
+            (Method::GET, Request::Thumbnail(id(t)?, Some(number(e)?)))
+        }
+        ["api", "titles", t, "episodes", e, "play"] => (
+            Method::POST,
+            Request::Command(ApiCommand::Play(id(t)?, Some(number(e)?))),
+        ),
+        ["api", "titles", t, "episodes", e, "watched"] => {
+            let watched = match *method {
+                Method::PUT => true,
+                Method::DELETE => false,
+                _ => return Err(ApiError::MethodNotAllowed),
+            };
+
+            return Ok(Request::Command(ApiCommand::SetWatched(
+                id(t)?,
+                number(e)?,
+                watched,
+            )));
+        }
+        _ => return Err(ApiError::NotFound(format!("Nothing at {path}"))),
+    };
+
+    if *method == expected {
+        Ok(request)
+    } else {
+        Err(ApiError::MethodNotAllowed)
+    }
+}
+
+fn response(
+    status: StatusCode,
+    body: hyper::Body,
+    content_type: Option<&'static str>,
+) -> hyper::Response<hyper::Body> {
+    let mut res = hyper::Response::new(body);
+    *res.status_mut() = status;
+
+    if let Some(content_type) = content_type {
+        res.headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+
+    res
+}
+
+fn json_response(status: StatusCode, value: &Value) -> hyper::Response<hyper::Body> {
+    response(status, value.to_string().into(), Some("application/json"))
+}
+
+fn error_response(e: &ApiError) -> hyper::Response<hyper::Body> {
+    let mut res = json_response(e.status(), &json!({ "error": e.to_string() }));
+
+    if *e == ApiError::Unauthorized {
+        res.headers_mut()
+            .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    }
+
+    res
+}
+
+fn image_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|ext| ext.to_str());
+
+    match extension.map(str::to_lowercase).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Returns the JSON of a _library_, and if its folder is available.
+pub fn library_json(library: &Library) -> Value {
+    json!({
+        "name": library.name,
+        "path": library.path,
+        "enabled": library.enabled,
+        "available": library.path.is_dir(),
+    })
+}
+
+/// Returns the JSON of a _title_ and its progress, without its episodes.
+pub fn title_json(title: &Title) -> Value {
+    let info = title.info.as_ref();
+    let progress = title.progress.as_ref();
+
+    json!({
+        "name": title.display_name(),
+        "folder": title.name,
+        "path": title.path,
+        "library": title.library,
+        "status": title.status(),
+        "episodes": progress.map(|progress| progress.episodes),
+        "watched": progress.map(|progress| progress.watched),
+        "last_watched": progress.and_then(|progress| progress.last_watched),
+        "next": progress.and_then(|progress| progress.next.as_ref()).map(|next| json!({
+            "number": next.number + 1,
+            "name": next.name,
+            "current": next.current,
+        })),
+        "genres": info.map(|info| info.genres.as_slice()).unwrap_or_default(),
+        "studio": info.and_then(|info| info.studio.as_ref()),
+        "year": info.and_then(|info| info.year),
+        "score": info.and_then(|info| info.score),
+        "offline": title.offline,
+    })
+}
+
+/// Returns the JSON of a title for the API, with its id, description and thumbnail.
+pub(crate) fn api_title_json(id: TitleIndex, title: &Title) -> Value {
+    let mut value = title_json(title);
+
+    value["id"] = json!(id);
+    value["description"] = json!(title.description());
+    value["thumbnail"] = json!(title
+        .thumbnail()
+        .filter(|path| path.is_file())
+        .map(|_| format!("/api/titles/{id}/thumbnail")));
+
+    value
+}
+
+/// Returns the JSON of an episode for the API, with its watch state and thumbnail.
+pub(crate) fn api_episode_json(id: TitleIndex, title: &Title, episode: &Episode) -> Value {
+    let number = episode.number as usize + 1;
+    let metadata = &episode.metadata;
+    let season = title
+        .seasons
+        .iter()
+        .find(|season| season.episodes.contains(&(episode.number as usize)))
+        .filter(|_| title.seasons.len() > 1);
+
+    json!({
+        "number": number,
+        "episode": episode.shown_number(),
+        "name": episode.name,
+        "season": season.map(|season| season.name.clone()),
+        "watched": metadata.watched,
+        "current": metadata.current,
+        "duration": metadata.duration,
+        "last_watched": metadata.last_watched,
+        "description": episode.description(),
+        "thumbnail": episode
+            .thumbnail_path
+            .is_file()
+            .then(|| format!("/api/titles/{id}/episodes/{number}/thumbnail")),
+    })
+}
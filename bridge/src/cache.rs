@@ -1,5 +1,8 @@
-use backend::{Backend, Episode, Meta, MetaType, Title};
-use std::{path::Path, sync::Arc};
+use backend::{
+    Backend, Episode, EpisodeId, Meta, MetaType, NextEpisode, Season, SeriesInfo, Title,
+    WatchStatus,
+};
+use std::{collections::BTreeMap, ops::Range, path::Path, sync::Arc};
 
 /// A cached copy of backend data to be shared with the frontend thread without the need of lockers.
 ///
@@ -22,6 +25,27 @@ impl Cache {
         }
     }
 
+    /// Creates a new instance of [`Cache`][Cache] like [`new`][Cache::new],
+    /// keeping the episodes of the titles that have them loaded.
+    pub fn with_loaded_episodes(backend: &Backend) -> Self {
+        Self {
+            titles_cache: backend.map(|title| match title.episodes {
+                Some(_) => TitleCache::with_episodes(title),
+                None => TitleCache::without_episodes(title),
+            }),
+            ..Self::new(backend)
+        }
+    }
+
+    /// Changes the name of the indexed title.
+    /// ## Panics
+    /// May panic if `number` is out of bounds.
+    pub fn set_title_name(&mut self, name: Arc<str>, number: usize) {
+        let mut titles_names = self.titles_names.to_vec();
+        titles_names[number] = name;
+        self.titles_names = Arc::from(titles_names);
+    }
+
     /// Returns a reference to the indexed [`TitleCache`][TitleCache].
     /// ## Panics
     /// May panic if `number` is out of bounds.
@@ -67,29 +91,156 @@ impl Cache {
 #[derive(Debug, Clone, Default)]
 pub struct TitleCache {
     pub size: usize,
+    /// Its library is not available.
+    pub offline: bool,
     cache: Arc<MetaCache>,
     pub episodes_names: Option<Arc<[Arc<str>]>>,
     episodes_cache: Option<Vec<EpisodeCache>>,
+    /// Only filled when the title has more than one season.
+    pub seasons: Option<Arc<[SeasonCache]>>,
+    /// Folder name and the names found by a provider, matched by the [title search][crate::search].
+    pub names: Arc<[Arc<str>]>,
+    pub genres: Arc<[Arc<str>]>,
+    pub studio: Option<Arc<str>>,
+    /// Episodes not watched yet, the last time they were loaded.
+    unwatched: Option<usize>,
+    /// Number of episodes, the last time they were loaded.
+    episodes: Option<usize>,
+    /// Unix time of the last time one of its episodes was watched, the last time they were loaded.
+    last_watched: Option<u64>,
+    /// Episode to continue watching, the last time they were loaded.
+    next: Option<Arc<NextEpisode>>,
+    /// Unix time of the last change of its folder.
+    pub added: Option<u64>,
+    pub score: Option<u8>,
+    pub year: Option<u16>,
+    /// Status set from the title list, see [`watch_status`][TitleCache::watch_status].
+    pub status: Option<WatchStatus>,
 }
 
 impl TitleCache {
     /// Creates a new instance of [`TitleCache`][TitleCache] without episodes loaded, requires a reference to [`Title`][Title].
     pub fn without_episodes(title: &Title) -> Self {
+        let info = title.info.as_ref();
+        let progress = title.progress.as_ref();
+        let names = info.map(|info| {
+            let titles = &info.titles;
+            [&titles.english, &titles.romaji, &titles.native]
+        });
+
         Self {
             size: title.count as usize,
+            offline: title.offline,
             cache: Arc::from(MetaCache::from(title as &dyn Meta)),
             episodes_names: None,
             episodes_cache: None,
+            seasons: None,
+            names: std::iter::once(title.name.clone())
+                .chain(
+                    names
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .map(|n| Arc::from(n.as_str())),
+                )
+                .collect(),
+            genres: info
+                .map(|info| info.genres.iter().map(|g| Arc::from(g.as_str())).collect())
+                .unwrap_or_default(),
+            studio: info.and_then(|info| info.studio.as_deref()).map(Arc::from),
+            unwatched: progress.map(|progress| progress.unwatched() as usize),
+            episodes: progress.map(|progress| progress.episodes as usize),
+            last_watched: progress.and_then(|progress| progress.last_watched),
+            next: progress.and_then(|progress| progress.next.clone().map(Arc::new)),
+            added: title.added,
+            score: info.and_then(|info| info.score),
+            year: info.and_then(|info| info.year),
+            status: title.settings.status,
         }
     }
 
     /// Creates a new instance of [`TitleCache`][TitleCache] with its episodes loaded, requires a reference to [`Title`][Title].
     pub fn with_episodes(title: &Title) -> Self {
         Self {
-            size: title.count as usize,
-            cache: Arc::from(MetaCache::from(title as &dyn Meta)),
             episodes_names: Some(title.cache()),
             episodes_cache: Some(title.map(EpisodeCache::new)),
+            seasons: (title.seasons.len() > 1)
+                .then(|| title.seasons.iter().map(SeasonCache::new).collect()),
+            ..Self::without_episodes(title)
+        }
+    }
+
+    /// Checks if the title has episodes not watched yet, the ones that were never loaded are all unwatched.
+    pub fn has_unwatched(&self) -> bool {
+        self.unwatched().is_none_or(|unwatched| unwatched > 0)
+    }
+
+    /// Returns the number of episodes not watched yet, if they were ever loaded.
+    pub fn unwatched(&self) -> Option<usize> {
+        match &self.episodes_cache {
+            Some(episodes) => Some(episodes.iter().filter(|episode| !episode.watched).count()),
+            None => self.unwatched,
+        }
+    }
+
+    /// Returns the number of episodes watched and the number of episodes, if they were ever loaded.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        let episodes = match &self.episodes_cache {
+            Some(episodes) => episodes.len(),
+            None => self.episodes?,
+        };
+
+        Some((episodes.saturating_sub(self.unwatched()?), episodes))
+    }
+
+    /// Returns the [`WatchStatus`][WatchStatus] set from the title list,
+    /// or the one [derived][WatchStatus::derive] from the progress of its episodes.
+    pub fn watch_status(&self) -> WatchStatus {
+        self.status
+            .unwrap_or_else(|| WatchStatus::derive(self.progress()))
+    }
+
+    /// Returns the Unix time of the last time one of its episodes was watched.
+    pub fn last_watched(&self) -> Option<u64> {
+        let episodes = self.episodes_cache.iter().flatten();
+        episodes
+            .filter_map(|episode| episode.last_watched)
+            .chain(self.last_watched)
+            .max()
+    }
+
+    /// Returns the number, name and seconds already watched of the episode to continue watching,
+    /// see [`next_episode`][backend::next_episode].
+    ///
+    /// If the episodes aren't loaded it's the saved one, only if it has its [id][TitleCache::next_episode_id].
+    pub fn next_episode(&self) -> Option<(usize, Arc<str>, f64)> {
+        let (Some(episodes), Some(names)) = (&self.episodes_cache, &self.episodes_names) else {
+            let next = self.next.as_ref().filter(|next| next.id.is_some())?;
+            return Some((
+                next.number as usize,
+                Arc::from(next.name.as_str()),
+                next.current as f64,
+            ));
+        };
+
+        let number = backend::next_episode(
+            episodes
+                .iter()
+                .map(|episode| (episode.watched, episode.last_watched)),
+        )?;
+
+        Some((number, names.get(number)?.clone(), episodes[number].current))
+    }
+
+    /// Returns the [`EpisodeId`][EpisodeId] of the episode to continue watching, saved the last time
+    /// the episodes were loaded, or [`None`][None] if they are loaded now.
+    ///
+    /// Its saved number may point to another episode if the files changed since then,
+    /// so it should be played by this id.
+    pub fn next_episode_id(&self) -> Option<&EpisodeId> {
+        match self.episodes_cache {
+            Some(_) => None,
+            None => self.next.as_ref()?.id.as_ref(),
         }
     }
 
@@ -117,6 +268,27 @@ impl TitleCache {
     }
 }
 
+/// A cached copy of a season data to be shared with the frontend thread without the need of lockers.
+///
+/// Its episodes are the indexed range of the [`TitleCache`][TitleCache] episodes.
+#[derive(Debug, Clone, Default)]
+pub struct SeasonCache {
+    pub name: Arc<str>,
+    pub episodes: Range<usize>,
+    pub cache: Arc<MetaCache>,
+}
+
+impl SeasonCache {
+    /// Creates a new instance of [`SeasonCache`][SeasonCache], requires a reference to [`Season`][Season].
+    pub fn new(season: &Season) -> Self {
+        Self {
+            name: season.name.clone(),
+            episodes: season.episodes.clone(),
+            cache: Arc::from(MetaCache::from(season as &dyn Meta)),
+        }
+    }
+}
+
 /// A cached copy of an episode data to be shared with the frontend thread without the need of lockers.
 ///
 /// It uses some [`Arc`][Arc] pointers to actually avoid making a deep-copy of some values.
@@ -124,7 +296,12 @@ impl TitleCache {
 #[derive(Debug, Clone, Default)]
 pub struct EpisodeCache {
     pub number: u16,
+    /// Number to show, see [`Episode::shown_number`][backend::Episode::shown_number].
+    pub shown_number: f32,
     pub watched: bool,
+    pub last_watched: Option<u64>,
+    /// Seconds already watched.
+    pub current: f64,
     cache: Arc<MetaCache>,
 }
 
@@ -133,12 +310,47 @@ impl EpisodeCache {
     pub fn new(episode: &Episode) -> Self {
         Self {
             number: episode.number,
+            shown_number: episode.shown_number(),
             watched: episode.metadata.watched,
+            last_watched: episode.metadata.last_watched,
+            current: episode.metadata.current,
             cache: Arc::from(MetaCache::from(episode as &dyn Meta)),
         }
     }
 }
 
+/// A cached copy of a candidate of the match picker to be shared with the frontend thread.
+#[derive(Debug, Clone, Default)]
+pub struct MatchCache {
+    pub title: Arc<str>,
+    /// Format, year and episodes, like _TV, 2019, 26 episodes_.
+    pub details: Arc<str>,
+    pub cover: Option<Arc<Path>>,
+    /// IDs to pin if it's chosen.
+    pub ids: BTreeMap<String, String>,
+}
+
+impl MatchCache {
+    /// Creates a new instance of [`MatchCache`][MatchCache], requires a reference to [`SeriesInfo`][SeriesInfo].
+    pub fn new(info: &SeriesInfo) -> Self {
+        let details: Vec<String> = [
+            info.format.clone(),
+            info.year.map(|year| year.to_string()),
+            info.episodes.map(|episodes| format!("{episodes} episodes")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Self {
+            title: Arc::from(info.title().unwrap_or("Unknown")),
+            details: Arc::from(details.join(", ")),
+            cover: info.images.thumbnail.as_deref().map(Arc::from),
+            ids: info.ids.clone(),
+        }
+    }
+}
+
 /// A cached copy of metadata to be shared with the frontend thread without the need of lockers.
 /// Used for displaying [`Title`][Title] and [`Episode`][Episode] thumbnails and description.
 ///
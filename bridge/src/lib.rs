@@ -1,11 +1,20 @@
+pub mod api;
 pub mod cache;
+pub mod next_up;
+pub mod search;
+pub mod session;
+pub mod sort;
+pub mod stats;
 pub mod subscription;
 
-use backend::Backend;
-use cache::{Cache, EpisodeCache, TitleCache};
+use backend::{Backend, Diagnostics, EpisodeId, HistoryEntry, HistoryFormat, WatchStatus};
+use cache::{Cache, EpisodeCache, MatchCache, TitleCache};
+use stats::Stats;
 
 use iced::futures::channel::mpsc;
 use iced::widget::pane_grid::{Direction, ResizeEvent};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -20,10 +29,19 @@ pub enum BackendMessage {
     LoadEpisodes(TitleIndex, Refresh),
     /// Args: (Title index, Episode number)
     WatchEpisode(TitleIndex, EpisodeNumber),
+    /// Plays an episode of the Continue Watching list, it's found by its id once the episodes are loaded.
+    /// Args: (Title index, Episode id)
+    ContinueWatching(TitleIndex, EpisodeId),
     /// Args: (Title index, Episode number)
     MarkEpisode(TitleIndex, EpisodeNumber),
     /// Args: (Title index, Episode number)
     MarkPreviousEpisodes(TitleIndex, EpisodeNumber),
+    /// Args: (Title index, Name to search or its folder name)
+    SearchMatches(TitleIndex, Option<Arc<str>>),
+    /// Args: (Title index, IDs of the chosen series)
+    PinMatch(TitleIndex, BTreeMap<String, String>),
+    /// Args: (Title index, Status to set or none to derive it)
+    SetStatus(TitleIndex, Option<WatchStatus>),
     Restart,
     CleanUp,
 }
@@ -33,8 +51,42 @@ pub enum BackendMessage {
 pub enum FrontendMessage {
     Recovery(mpsc::Sender<BackendMessage>, Arc<str>),
     Ready(mpsc::Sender<BackendMessage>, Cache),
+    /// Args: (Title name, Episode name)
+    PlaybackStarted(Arc<str>, Arc<str>),
+    /// Args: (Title index, Updated episode or the reason the playback failed)
+    PlaybackFinished(TitleIndex, Result<EpisodeCache, Arc<str>>),
+    /// Titles were added or removed, every index may have changed.
+    /// Args: (Added title names, Removed title names, New index of each previous title, Updated cache)
+    TitlesChanged(Vec<Arc<str>>, Vec<Arc<str>>, Vec<Option<usize>>, Cache),
+    /// Args: (Title index, Episode names, Title cache)
+    EpisodesAdded(TitleIndex, Vec<Arc<str>>, TitleCache),
+    /// Args: (Title index, Episode names, Title cache)
+    EpisodesRemoved(TitleIndex, Vec<Arc<str>>, TitleCache),
+    /// Args: (Title index, Title name, Title cache)
+    TitleChanged(TitleIndex, Arc<str>, TitleCache),
+    /// Args: (Title index, Searched name, Candidates)
+    MatchesFound(TitleIndex, Arc<str>, Arc<[MatchCache]>),
+    /// Args: (Name to search)
+    MatchSearchChanged(String),
+    SearchMatches,
+    /// Args: (Candidate index)
+    PinMatch(usize),
+    ShowHistory,
+    ShowStats,
+    /// Checks the external programs, folders and config.
+    ShowDiagnostics,
+    ExportHistory(HistoryFormat),
+    /// Args: (Query of the command palette)
+    PaletteChanged(String),
+    /// Args: (Items to move the focus of the command palette)
+    PaletteMove(isize),
+    /// Args: (Index of the command to run between the ones found by the palette)
+    PaletteSubmit(usize),
+    /// Rescans the libraries.
+    Restart,
     FontLoaded(Result<(), iced::font::Error>),
     UpdateConfig(ConfigChange),
+    Setup(SetupAction),
     PaneAction(PanelAction),
     Loading(Instant),
     MenuBar(Modals),
@@ -54,6 +106,15 @@ pub enum PanelAction {
     UpdateEpisode(TitleIndex, Vec<EpisodeCache>),
     MarkPreviousEpisodes,
     MarkEpisode,
+    PickMatch,
+    /// Sets the following [`WatchStatus`][WatchStatus] of the focused title.
+    NextStatus,
+    /// Opens the title search.
+    Search,
+    /// Args: (Search query)
+    SearchChanged(String),
+    SearchSubmit,
+    ClearSearch,
     FocusItem(Direction),
     JumpTo(usize),
     Plus(isize),
@@ -72,12 +133,59 @@ pub enum Modals {
     About,
     Config,
     Yama,
+    Matches(MatchPicker),
+    /// Args: (Every entry of the watch history, the oldest first)
+    History(Arc<[HistoryEntry]>),
+    Stats(Arc<Stats>),
+    Diagnostics(Arc<Diagnostics>),
+    Palette(CommandPalette),
     Error(Arc<str>),
 }
 
+/// State of the [command palette][Modals::Palette].
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    /// Index of the focused command between the ones found.
+    pub focused: usize,
+}
+
+/// State of the [match picker][Modals::Matches] of a title.
+#[derive(Debug, Clone)]
+pub struct MatchPicker {
+    pub title_number: TitleIndex,
+    /// Name to search, it can be edited.
+    pub search: String,
+    pub candidates: Arc<[MatchCache]>,
+}
+
+/// Actions of the first launch setup.
+#[derive(Debug, Clone)]
+pub enum SetupAction {
+    NextStep,
+    PreviousStep,
+    AddLibrary,
+    /// Args: (Library index)
+    RemoveLibrary(usize),
+    /// Args: (Theme file)
+    Theme(PathBuf),
+    BrowseTheme,
+    MinTime(f32),
+    CheckDependencies,
+    /// Saves the config and starts yama.
+    Finish,
+}
+
 #[derive(Debug, Clone)]
 pub enum ConfigChange {
-    SeriesPath,
+    AddLibrary,
+    /// Args: (Library index)
+    RemoveLibrary(usize),
+    /// Args: (Library index)
+    ToggleLibrary(usize),
     ThemePath,
     MinTime(f32),
+    CentralMetadata(bool),
+    /// Changes to the following [`TitleSort`][backend::TitleSort].
+    NextTitleSort,
 }
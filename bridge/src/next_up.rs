@@ -0,0 +1,69 @@
+use crate::cache::Cache;
+use crate::BackendMessage;
+
+use backend::EpisodeId;
+
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+/// Most episodes listed in the [Continue Watching][Cache::next_up] list.
+pub const NEXT_UP_SIZE: usize = 5;
+
+/// An episode of the [Continue Watching][Cache::next_up] list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextUpCache {
+    pub title_number: usize,
+    pub episode_number: usize,
+    /// Id of the episode if the episodes of its title aren't loaded, see [`message`][NextUpCache::message].
+    pub id: Option<EpisodeId>,
+    pub name: Arc<str>,
+    /// Seconds already watched, zero if it was never started.
+    pub current: f64,
+}
+
+impl NextUpCache {
+    /// Checks if the episode was left halfway.
+    pub fn in_progress(&self) -> bool {
+        self.current > 1.0
+    }
+
+    /// Returns the [`BackendMessage`][BackendMessage] to play the episode, by its id if its title
+    /// isn't loaded, as its saved number may be out of date.
+    pub fn message(&self) -> BackendMessage {
+        match &self.id {
+            Some(id) => BackendMessage::ContinueWatching(self.title_number, id.clone()),
+            None => BackendMessage::WatchEpisode(self.title_number, self.episode_number),
+        }
+    }
+}
+
+impl Cache {
+    /// Returns the episodes to continue watching of the most recently watched titles,
+    /// the last watched first.
+    pub fn next_up(&self) -> Vec<NextUpCache> {
+        let mut next_up: Vec<_> = (0..self.size)
+            .filter_map(|title_number| {
+                let title = self.get_title(title_number);
+                let last_watched = title.last_watched()?;
+                let (episode_number, name, current) = title.next_episode()?;
+
+                let next = NextUpCache {
+                    title_number,
+                    episode_number,
+                    id: title.next_episode_id().cloned(),
+                    name,
+                    current,
+                };
+
+                Some((last_watched, next))
+            })
+            .collect();
+
+        next_up.sort_by_key(|(last_watched, _)| Reverse(*last_watched));
+        next_up
+            .into_iter()
+            .take(NEXT_UP_SIZE)
+            .map(|(_, next)| next)
+            .collect()
+    }
+}
@@ -0,0 +1,174 @@
+use crate::cache::{Cache, TitleCache};
+
+use backend::{TitleSort, WatchStatus};
+
+/// Filters of the title search, parsed from a query like `steins genre:sci-fi is:unwatched`.
+///
+/// The words without a prefix are fuzzy-matched against the names and genres of the titles,
+/// the prefixed ones must be contained in a genre or the studio, or be the
+/// [`WatchStatus`][WatchStatus], like `status:on-hold`. Values with spaces can be quoted,
+/// like `studio:"kyoto animation"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TitleFilter {
+    pub words: Vec<String>,
+    pub genres: Vec<String>,
+    pub studio: Option<String>,
+    /// Only titles with episodes not watched yet.
+    pub unwatched: bool,
+    pub status: Option<WatchStatus>,
+}
+
+impl TitleFilter {
+    /// Creates a new [`TitleFilter`][TitleFilter] from a search _query_.
+    pub fn parse(query: &str) -> Self {
+        let mut filter = Self::default();
+
+        for token in tokenize(query) {
+            match token.split_once(':') {
+                Some(("genre", genre)) if !genre.is_empty() => {
+                    filter.genres.push(genre.to_string())
+                }
+                Some(("studio", studio)) if !studio.is_empty() => {
+                    filter.studio = Some(studio.to_string())
+                }
+                Some(("is", "unwatched")) => filter.unwatched = true,
+                Some(("status", status)) if WatchStatus::parse(status).is_some() => {
+                    filter.status = WatchStatus::parse(status)
+                }
+                _ => filter.words.push(token),
+            }
+        }
+
+        filter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Returns how well the title matches, the higher the better,
+    /// or [`None`][None] if it doesn't match.
+    pub fn score(&self, title: &TitleCache) -> Option<i64> {
+        if self.unwatched && !title.has_unwatched() {
+            return None;
+        }
+
+        if self
+            .status
+            .is_some_and(|status| status != title.watch_status())
+        {
+            return None;
+        }
+
+        let contains = |text: &str, value: &str| text.to_lowercase().contains(value);
+
+        for genre in &self.genres {
+            let genre = genre.to_lowercase();
+            title.genres.iter().find(|g| contains(g, &genre))?;
+        }
+
+        if let Some(studio) = &self.studio {
+            let studio = studio.to_lowercase();
+            title.studio.as_ref().filter(|s| contains(s, &studio))?;
+        }
+
+        self.words.iter().try_fold(0, |total, word| {
+            let best = title
+                .names
+                .iter()
+                .chain(title.genres.iter())
+                .filter_map(|text| fuzzy_score(word, text))
+                .max()?;
+
+            Some(total + best)
+        })
+    }
+}
+
+impl Cache {
+    /// Returns the indexes of the titles that match the _filter_, the best matches first.
+    ///
+    /// Titles that match equally are in the _sort_ order.
+    pub fn search(&self, filter: &TitleFilter, sort: TitleSort) -> Vec<usize> {
+        let mut found: Vec<(usize, i64)> = self
+            .sorted(sort)
+            .into_iter()
+            .filter_map(|idx| Some((idx, filter.score(self.get_title(idx))?)))
+            .collect();
+
+        found.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        found.into_iter().map(|(idx, _)| idx).collect()
+    }
+}
+
+/// Splits a query by its spaces, except the ones between quotes.
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// Matches the characters of the _pattern_, in order and ignoring case, against the _text_.
+///
+/// Returns a score that rewards consecutive characters and the start of words,
+/// or [`None`][None] if some character is missing.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let Some(first) = pattern.first() else {
+        return Some(0);
+    };
+
+    // Each place where the pattern can start is tried, so _his_ prefers _history_ to _show_.
+    (0..text.len())
+        .filter(|&idx| text[idx] == *first)
+        .filter_map(|start| score_from(&pattern, &text, start))
+        .max()
+}
+
+/// Scores the _pattern_ matched from _start_ onwards, taking the first match of each character.
+fn score_from(pattern: &[char], text: &[char], mut start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut last: Option<usize> = None;
+
+    for p in pattern {
+        let idx = start + text[start..].iter().position(|c| c == p)?;
+        let word_start = idx == 0 || !text[idx - 1].is_alphanumeric();
+
+        score += 1;
+        match last {
+            Some(last) if last + 1 == idx => score += 5,
+            Some(last) => score -= (idx - last - 1).min(3) as i64,
+            None if word_start => score += 3,
+            None => (),
+        }
+
+        if word_start {
+            score += 3;
+        }
+
+        last = Some(idx);
+        start = idx + 1;
+    }
+
+    Some(score)
+}
@@ -0,0 +1,573 @@
+mod answer;
+
+use super::*;
+
+use crate::api::{Question, Reply, Request, Server};
+
+use backend::{Config, LibraryChange, Playback, Watcher};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tokio::sync::{mpsc as api_mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// The [`Backend`][Backend] of a running frontend, along with the episode being played
+/// and the watcher of its libraries.
+///
+/// It answers each [`BackendMessage`][BackendMessage] with a [`FrontendMessage`][FrontendMessage],
+/// so any frontend can drive it, like the [subscription][crate::subscription] of the window.
+#[derive(Debug)]
+pub struct Session {
+    backend: Box<Backend>,
+    playing: Option<Playing>,
+    watcher: Option<Watcher>,
+    /// Messages waiting to be sent, a single change in the libraries may produce many.
+    pending: VecDeque<FrontendMessage>,
+    /// The local HTTP API, if it's enabled, with the [`Questions`][Question] it received.
+    api: Option<(Server, api_mpsc::Receiver<Question>)>,
+}
+
+impl Session {
+    /// Creates a new [`Session`][Session], returning it with a [`Cache`][Cache]
+    /// of the initial data loaded (just Titles and no Episodes).
+    ///
+    /// The local HTTP API is started too if it's enabled in the [`Config`][Config].
+    pub async fn start() -> backend::Result<(Self, Cache)> {
+        Self::with_backend(Backend::new().await?).await
+    }
+
+    /// Creates a new [`Session`][Session] like [`start`][Session::start], with the config,
+    /// history and libraries of the _config_dir_ folder instead of the ones of the user.
+    pub async fn in_config_dir(config_dir: impl Into<PathBuf>) -> backend::Result<(Self, Cache)> {
+        Self::with_backend(Backend::in_config_dir(config_dir).await?).await
+    }
+
+    async fn with_backend(backend: Backend) -> backend::Result<(Self, Cache)> {
+        let cfg: Config = backend.config()?;
+        let cache = Cache::new(&backend);
+        let watcher = match Watcher::new(&backend.library_paths()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("The libraries will not be watched for changes: {e}");
+                None
+            }
+        };
+
+        let api = if cfg.api.enabled {
+            match Server::bind(&cfg.api).await {
+                Ok(api) => Some(api),
+                Err(e) => {
+                    error!("The API could not be started: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let session = Self {
+            backend: Box::new(backend),
+            playing: None,
+            watcher,
+            pending: VecDeque::new(),
+            api,
+        };
+
+        Ok((session, cache))
+    }
+
+    /// Waits for the next [`BackendMessage`][BackendMessage] of the _receiver_, for the episode being played
+    /// to finish or for changes in the libraries, and returns the [`FrontendMessage`][FrontendMessage] to answer it.
+    ///
+    /// The requests of the local HTTP API are answered meanwhile, its commands also return
+    /// their [`FrontendMessage`][FrontendMessage] so the frontend shows their changes.
+    ///
+    /// [`BackendMessage::Restart`][BackendMessage::Restart] is answered with [`FrontendMessage::ToLoad`][FrontendMessage::ToLoad],
+    /// or an error while an episode is being played, and [`BackendMessage::CleanUp`][BackendMessage::CleanUp] with
+    /// [`FrontendMessage::Exit`][FrontendMessage::Exit], after those the [`Session`][Session] should be dropped.
+    pub async fn next(&mut self, receiver: &mut mpsc::Receiver<BackendMessage>) -> FrontendMessage {
+        if let Some(msg) = self.pending.pop_front() {
+            return msg;
+        }
+
+        let event = loop {
+            let questions = self.api.as_mut().map(|(_, questions)| questions);
+
+            match next_event(receiver, &mut self.playing, &mut self.watcher, questions).await {
+                Event::LibraryChanged(paths) => {
+                    info!("Changes found in the libraries.");
+
+                    for change in self.backend.apply_changes(&paths).await {
+                        if let (LibraryChange::TitlesChanged(_, _, moved), Some(playing)) =
+                            (&change, &mut self.playing)
+                        {
+                            // A removed title keeps its old index, where its episode won't be found.
+                            if let Some(&Some(title_number)) = moved.get(playing.title_number) {
+                                playing.title_number = title_number;
+                            }
+                        }
+
+                        self.pending
+                            .push_back(library_message(&self.backend, change));
+                    }
+
+                    if let Some(msg) = self.pending.pop_front() {
+                        return msg;
+                    }
+                }
+                Event::Api(request, reply) => {
+                    if let Some(msg) = self.answer(request, reply).await {
+                        return msg;
+                    }
+
+                    if let Some(msg) = self.pending.pop_front() {
+                        return msg;
+                    }
+                }
+                event => break event,
+            }
+        };
+
+        self.handle(event).await
+    }
+
+    async fn handle(&mut self, event: Event) -> FrontendMessage {
+        match event {
+            Event::LibraryChanged(_) | Event::Api(..) => unreachable!(),
+
+            Event::PlaybackFinished(title_number, path, started, result) => {
+                if let Some(ds_client) = &self.backend.ds_client {
+                    ds_client.idle_activity().await;
+                }
+
+                let result = match result {
+                    Ok(playback) => {
+                        match self
+                            .backend
+                            .finish_playback(title_number, &path, started, &playback)
+                        {
+                            Ok(Some(episode_number)) => self
+                                .backend
+                                .get_episode(title_number, episode_number)
+                                .map(|episode| EpisodeCache::new(episode))
+                                .ok_or_else(|| Arc::from("No episode found!")),
+                            Ok(None) => {
+                                error!("No episode found for {}", path.display());
+                                Err(Arc::from("No episode found!"))
+                            }
+                            Err(e) => {
+                                error!("{e}");
+                                Err(Arc::from("Could not save episode progress!"))
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("{e}");
+                        Err(load_error(&e))
+                    }
+                };
+
+                FrontendMessage::PlaybackFinished(title_number, result)
+            }
+
+            Event::Message(msg) => match msg {
+                BackendMessage::LoadEpisodes(title_number, refresh) => {
+                    match self.backend.titles.get_mut(title_number) {
+                        Some(title) => {
+                            info!("Loading episodes of: {}.", title.name);
+
+                            match title.load_episodes(refresh).await {
+                                Ok(_) => {
+                                    let title_cache = TitleCache::with_episodes(title);
+
+                                    FrontendMessage::PaneAction(PanelAction::EpisodesLoaded(
+                                        title_number,
+                                        title_cache,
+                                    ))
+                                }
+                                Err(e) => {
+                                    error!("{e}");
+                                    FrontendMessage::Error(Arc::from("Could not load title!"))
+                                }
+                            }
+                        }
+                        None => {
+                            error!("No title found at the index {}", title_number);
+                            FrontendMessage::Error(Arc::from("No title found!"))
+                        }
+                    }
+                }
+
+                BackendMessage::WatchEpisode(title_number, episode_number) => {
+                    self.watch(title_number, episode_number).await
+                }
+
+                BackendMessage::ContinueWatching(title_number, id) => {
+                    if let Err(e) =
+                        load_title(&mut self.backend, title_number, &mut self.pending).await
+                    {
+                        error!("{e}");
+                        return FrontendMessage::Error(Arc::from("Could not load title!"));
+                    }
+
+                    // Its saved number may be another episode now, so it's found by its id.
+                    match self
+                        .backend
+                        .titles
+                        .get(title_number)
+                        .and_then(|title| title.find_episode_id(&id))
+                    {
+                        Some(episode_number) => self.watch(title_number, episode_number).await,
+                        None => {
+                            error!("No episode found with the id {}", id);
+                            FrontendMessage::Error(Arc::from("No episode found!"))
+                        }
+                    }
+                }
+
+                BackendMessage::MarkEpisode(title_number, episode_number) => {
+                    match self.backend.get_episode(title_number, episode_number) {
+                        Some(episode) => {
+                            info!(
+                                "Mark {} as {}.",
+                                episode.name,
+                                if episode.metadata.watched {
+                                    "unwatched"
+                                } else {
+                                    "watched"
+                                }
+                            );
+
+                            match episode.as_watched() {
+                                Ok(_) => {
+                                    let episode_cache = EpisodeCache::new(episode);
+                                    self.backend.update_progress(title_number);
+
+                                    FrontendMessage::PaneAction(PanelAction::UpdateEpisode(
+                                        title_number,
+                                        vec![episode_cache],
+                                    ))
+                                }
+                                Err(e) => {
+                                    error!("{}", e);
+                                    FrontendMessage::Error(load_error(&e))
+                                }
+                            }
+                        }
+                        None => {
+                            error!("No episode found at the index {}", episode_number);
+                            FrontendMessage::Error(Arc::from("No episode found!"))
+                        }
+                    }
+                }
+
+                BackendMessage::MarkPreviousEpisodes(title_number, episode_number) => {
+                    match self.backend.titles.get_mut(title_number) {
+                        Some(title) => {
+                            info!(
+                            "Mark all previous episodes of {} from episode {} as watched/unwatched.",
+                            title.name,
+                            episode_number + 1
+                        );
+
+                            match title.as_watched(episode_number) {
+                                Ok(_) => {
+                                    // We can safely unwrap because 'as_watched' checked the bounds for us.
+                                    let episodes_cache: Vec<EpisodeCache> =
+                                        title.episodes.as_ref().unwrap()[..episode_number]
+                                            .iter()
+                                            .map(EpisodeCache::new)
+                                            .collect();
+
+                                    FrontendMessage::PaneAction(PanelAction::UpdateEpisode(
+                                        title_number,
+                                        episodes_cache,
+                                    ))
+                                }
+                                Err(e) => {
+                                    error!("{}", e);
+                                    FrontendMessage::Error(load_error(&e))
+                                }
+                            }
+                        }
+                        None => {
+                            error!("No title found at the index {}", title_number);
+                            FrontendMessage::Error(Arc::from("No title found!"))
+                        }
+                    }
+                }
+
+                BackendMessage::SearchMatches(title_number, search) => {
+                    match self
+                        .backend
+                        .search_matches(title_number, search.as_deref())
+                        .await
+                    {
+                        Ok((search, candidates)) => FrontendMessage::MatchesFound(
+                            title_number,
+                            search,
+                            candidates.iter().map(MatchCache::new).collect(),
+                        ),
+                        Err(e) => {
+                            error!("{e}");
+                            FrontendMessage::Error(Arc::from("Could not search!"))
+                        }
+                    }
+                }
+
+                BackendMessage::PinMatch(title_number, ids) => {
+                    match self.backend.pin_match(title_number, ids).await {
+                        Ok(()) => {
+                            let title = &self.backend.titles[title_number];
+                            let title_cache = match title.episodes {
+                                Some(_) => TitleCache::with_episodes(title),
+                                None => TitleCache::without_episodes(title),
+                            };
+
+                            FrontendMessage::TitleChanged(
+                                title_number,
+                                title.display_name(),
+                                title_cache,
+                            )
+                        }
+                        Err(e) => {
+                            error!("{e}");
+                            FrontendMessage::Error(Arc::from("Could not pin the match!"))
+                        }
+                    }
+                }
+
+                BackendMessage::SetStatus(title_number, status) => {
+                    match self.backend.set_status(title_number, status) {
+                        Ok(()) => {
+                            let title = &self.backend.titles[title_number];
+                            let title_cache = match title.episodes {
+                                Some(_) => TitleCache::with_episodes(title),
+                                None => TitleCache::without_episodes(title),
+                            };
+
+                            FrontendMessage::TitleChanged(
+                                title_number,
+                                title.display_name(),
+                                title_cache,
+                            )
+                        }
+                        Err(e) => {
+                            error!("{e}");
+                            FrontendMessage::Error(Arc::from("Could not save the status!"))
+                        }
+                    }
+                }
+
+                BackendMessage::Restart => match self.playing {
+                    // Its task would be dropped with the session, losing its progress and history entry.
+                    Some(_) => FrontendMessage::Error(Arc::from(
+                        "Close the episode being played before loading the libraries again!",
+                    )),
+                    None => FrontendMessage::ToLoad,
+                },
+
+                BackendMessage::CleanUp => {
+                    if let Some(ds_client) = self.backend.ds_client.take() {
+                        match ds_client.cleanup().await {
+                            Ok(_) => info!("Discord client closed successfully"),
+                            Err(e) => error!("Discord client failed to close:\n{e}"),
+                        }
+                    }
+
+                    FrontendMessage::Exit
+                }
+            },
+        }
+    }
+
+    /// Plays the indexed episode in the background, loading the episodes of its title if they aren't yet.
+    async fn watch(
+        &mut self,
+        title_number: TitleIndex,
+        episode_number: EpisodeNumber,
+    ) -> FrontendMessage {
+        if self.playing.is_some() {
+            FrontendMessage::Error(Arc::from("Already playing an episode!"))
+        } else if let Err(e) = load_title(&mut self.backend, title_number, &mut self.pending).await
+        {
+            error!("{e}");
+            FrontendMessage::Error(Arc::from("Could not load title!"))
+        } else if let (Some(title_name), Some((_, remaining_time))) = (
+            self.backend.get_title_name(title_number),
+            self.backend.get_episode_data(title_number, episode_number),
+        ) {
+            let player = self.backend.player(title_number);
+
+            match (
+                player,
+                self.backend.get_episode(title_number, episode_number),
+            ) {
+                (Ok(player), Some(episode)) => {
+                    info!("Loading episode: {}.", episode.name);
+
+                    let episode_name = episode.name.clone();
+                    self.playing = Some(Playing {
+                        title_number,
+                        path: episode.path.clone(),
+                        started: SystemTime::now(),
+                        task: tokio::spawn(episode.play(player.as_ref())),
+                    });
+
+                    if let Some(ds_client) = &self.backend.ds_client {
+                        ds_client
+                            .watch_activity(
+                                title_name.clone(),
+                                episode_name.clone(),
+                                remaining_time,
+                            )
+                            .await;
+                    }
+
+                    FrontendMessage::PlaybackStarted(title_name, episode_name)
+                }
+                (Err(e), _) => {
+                    error!("{e}");
+                    FrontendMessage::Error(Arc::from("Could not load player!"))
+                }
+                (_, None) => {
+                    error!("No episode found at the index {}", episode_number);
+                    FrontendMessage::Error(Arc::from("No episode found!"))
+                }
+            }
+        } else {
+            error!(
+                "No episode found at the index {} of the title {}",
+                episode_number, title_number
+            );
+            FrontendMessage::Error(Arc::from("No episode found!"))
+        }
+    }
+}
+
+/// An [`Episode`][backend::Episode] being played in the background.
+#[derive(Debug)]
+struct Playing {
+    title_number: TitleIndex,
+    /// The episodes may be renumbered while it plays, so it's found again by its path.
+    path: PathBuf,
+    /// When it started, for the [`History`][backend::History].
+    started: SystemTime,
+    task: JoinHandle<backend::Result<Playback>>,
+}
+
+/// Events that wake up the [`Session`][Session].
+enum Event {
+    Message(BackendMessage),
+    /// Args: (Title index, Episode path, When it started, Final state of the playback)
+    PlaybackFinished(TitleIndex, PathBuf, SystemTime, backend::Result<Playback>),
+    /// Args: (Changed paths)
+    LibraryChanged(Vec<PathBuf>),
+    /// Args: (Request of the API, Where to send its reply)
+    Api(Request, oneshot::Sender<Reply>),
+}
+
+/// Waits for the next [`BackendMessage`][BackendMessage], for the [`Playing`][Playing] episode
+/// to finish, for changes in the libraries or for a request of the API, whatever comes first.
+async fn next_event(
+    receiver: &mut mpsc::Receiver<BackendMessage>,
+    playing: &mut Option<Playing>,
+    watcher: &mut Option<Watcher>,
+    questions: Option<&mut api_mpsc::Receiver<Question>>,
+) -> Event {
+    use iced::futures::future;
+    use iced::futures::StreamExt;
+
+    let finished = async {
+        match playing.as_mut() {
+            Some(p) => (&mut p.task).await,
+            None => future::pending().await,
+        }
+    };
+
+    let changed = async {
+        match watcher.as_mut() {
+            Some(w) => w.next().await,
+            None => future::pending().await,
+        }
+    };
+
+    let asked = async {
+        match questions {
+            Some(questions) => questions.recv().await,
+            None => future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        msg = receiver.select_next_some() => Event::Message(msg),
+        Some(paths) = changed => Event::LibraryChanged(paths),
+        Some((request, reply)) = asked => Event::Api(request, reply),
+        result = finished => {
+            let p = playing.take().unwrap();
+            Event::PlaybackFinished(
+                p.title_number,
+                p.path,
+                p.started,
+                result.unwrap_or_else(|e| Err(e.into())),
+            )
+        }
+    }
+}
+
+/// Returns the [`FrontendMessage`][FrontendMessage] for a [`LibraryChange`][LibraryChange].
+fn library_message(backend: &Backend, change: LibraryChange) -> FrontendMessage {
+    match change {
+        LibraryChange::TitlesChanged(added, removed, moved) => FrontendMessage::TitlesChanged(
+            added,
+            removed,
+            moved,
+            Cache::with_loaded_episodes(backend),
+        ),
+        LibraryChange::EpisodesAdded(title_number, names) => FrontendMessage::EpisodesAdded(
+            title_number,
+            names,
+            TitleCache::with_episodes(&backend.titles[title_number]),
+        ),
+        LibraryChange::EpisodesRemoved(title_number, names) => FrontendMessage::EpisodesRemoved(
+            title_number,
+            names,
+            TitleCache::with_episodes(&backend.titles[title_number]),
+        ),
+    }
+}
+
+/// Loads the episodes of a title played without opening it, like from the Continue Watching list,
+/// and queues its updated [`TitleCache`][TitleCache] for the frontend.
+async fn load_title(
+    backend: &mut Backend,
+    title_number: TitleIndex,
+    pending: &mut VecDeque<FrontendMessage>,
+) -> backend::Result<()> {
+    let Some(title) = backend.titles.get_mut(title_number) else {
+        return Ok(());
+    };
+
+    if title.episodes.is_some() {
+        return Ok(());
+    }
+
+    info!("Loading episodes of: {}.", title.name);
+    title.load_episodes(false).await?;
+
+    pending.push_back(FrontendMessage::TitleChanged(
+        title_number,
+        title.display_name(),
+        TitleCache::with_episodes(title),
+    ));
+
+    Ok(())
+}
+
+/// Error shown when an episode can't be loaded or played, usually because of a missing program.
+fn load_error(e: &impl std::fmt::Display) -> Arc<str> {
+    Arc::from(format!(
+        "Could not load episode: {e}\nThe Diagnostics can tell if a program like ffmpeg or mpv is missing."
+    ))
+}
@@ -0,0 +1,213 @@
+use super::*;
+
+use crate::api::{
+    api_episode_json, api_title_json, library_json, ApiCommand, ApiError, Content, Reply, Request,
+};
+
+use backend::{next_episode, Meta};
+use serde_json::{json, Value};
+
+impl Session {
+    /// Answers a [`Request`][Request] of the local HTTP API through its _reply_ channel.
+    ///
+    /// Commands are run like any [`BackendMessage`][BackendMessage], their [`FrontendMessage`][FrontendMessage]
+    /// is returned so the frontend shows what changed.
+    pub(super) async fn answer(
+        &mut self,
+        request: Request,
+        reply: oneshot::Sender<Reply>,
+    ) -> Option<FrontendMessage> {
+        let (answer, msg) = match request {
+            Request::Command(command) => match self.command_message(command).await {
+                Ok(Some(msg)) => {
+                    let msg = match self.handle(Event::Message(msg)).await {
+                        // The title should not be opened in the frontend, only updated.
+                        FrontendMessage::PaneAction(PanelAction::EpisodesLoaded(
+                            title_number,
+                            title_cache,
+                        )) => match self.backend.get_title_name(title_number) {
+                            Some(title_name) => {
+                                FrontendMessage::TitleChanged(title_number, title_name, title_cache)
+                            }
+                            None => FrontendMessage::Error(Arc::from("No title found!")),
+                        },
+                        msg => msg,
+                    };
+
+                    let answer = match &msg {
+                        FrontendMessage::Error(e) => Err(ApiError::Failed(e.to_string())),
+                        FrontendMessage::ToLoad => {
+                            Ok(Content::Json(json!({"result": "rescanning"})))
+                        }
+                        _ => self.title_answer(command.title_number()),
+                    };
+
+                    (answer, Some(msg))
+                }
+                Ok(None) => (self.title_answer(command.title_number()), None),
+                Err(e) => (Err(e), None),
+            },
+            Request::Title(title_number) => match self.load(title_number).await {
+                Ok(()) => (self.title_answer(Some(title_number)), None),
+                Err(e) => (Err(e), None),
+            },
+            request => (self.read(request), None),
+        };
+
+        if reply.send(answer).is_err() {
+            warn!("The API request was dropped before its answer.");
+        }
+
+        msg
+    }
+
+    /// Answers the requests that only read the titles as they are.
+    fn read(&self, request: Request) -> Reply {
+        let backend = &self.backend;
+
+        match request {
+            Request::Libraries => Ok(Content::Json(
+                backend.libraries().map(library_json).collect(),
+            )),
+            Request::Titles => Ok(Content::Json(
+                backend
+                    .titles
+                    .iter()
+                    .enumerate()
+                    .map(|(title_number, title)| api_title_json(title_number, title))
+                    .collect(),
+            )),
+            Request::Status => {
+                let playing = self.playing.as_ref().and_then(|playing| {
+                    let title = backend.titles.get(playing.title_number)?;
+                    let episode = title
+                        .episodes
+                        .iter()
+                        .flatten()
+                        .find(|episode| episode.path == playing.path)?;
+
+                    Some(json!({
+                        "title_id": playing.title_number,
+                        "title": title.display_name(),
+                        "episode": episode.number as usize + 1,
+                        "name": episode.name,
+                    }))
+                });
+
+                Ok(Content::Json(json!({
+                    "playing": playing,
+                    "titles": backend.titles.len(),
+                })))
+            }
+            Request::Thumbnail(title_number, episode_number) => {
+                let title = backend.titles.get(title_number).ok_or_else(no_title)?;
+                let path = match episode_number {
+                    Some(episode_number) => title
+                        .episodes
+                        .as_ref()
+                        .and_then(|episodes| episodes.get(episode_number))
+                        .and_then(|episode| episode.thumbnail()),
+                    None => title.thumbnail(),
+                };
+
+                match path {
+                    Some(path) if path.is_file() => Ok(Content::Image(path.to_path_buf())),
+                    _ => Err(ApiError::NotFound(String::from("No thumbnail found"))),
+                }
+            }
+            Request::Title(_) | Request::Command(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the JSON of a title with its episodes, or the result of a rescan if there's none.
+    fn title_answer(&self, title_number: Option<TitleIndex>) -> Reply {
+        let Some(title_number) = title_number else {
+            return Ok(Content::Json(json!({"result": "rescanning"})));
+        };
+        let title = self.backend.titles.get(title_number).ok_or_else(no_title)?;
+
+        let mut value = api_title_json(title_number, title);
+        value["episodes"] = title
+            .episodes
+            .iter()
+            .flatten()
+            .map(|episode| api_episode_json(title_number, title, episode))
+            .collect::<Vec<Value>>()
+            .into();
+
+        Ok(Content::Json(value))
+    }
+
+    /// Loads the episodes of a title if they aren't yet, the frontend gets its updated cache.
+    async fn load(&mut self, title_number: TitleIndex) -> Result<(), ApiError> {
+        if title_number >= self.backend.titles.len() {
+            return Err(no_title());
+        }
+
+        load_title(&mut self.backend, title_number, &mut self.pending)
+            .await
+            .map_err(|e| {
+                error!("{e}");
+                ApiError::Failed(String::from("Could not load title!"))
+            })
+    }
+
+    /// Returns the [`BackendMessage`][BackendMessage] of a command, or none if there's nothing to do.
+    async fn command_message(
+        &mut self,
+        command: ApiCommand,
+    ) -> Result<Option<BackendMessage>, ApiError> {
+        if let Some(title_number) = command.title_number() {
+            self.load(title_number).await?;
+        }
+
+        let episodes = |backend: &Backend, title_number: TitleIndex| {
+            backend.titles[title_number]
+                .episodes
+                .as_deref()
+                .unwrap_or_default()
+                .len()
+        };
+
+        let msg = match command {
+            ApiCommand::Play(title_number, Some(episode_number)) => {
+                if episode_number >= episodes(&self.backend, title_number) {
+                    return Err(no_episode());
+                }
+
+                BackendMessage::WatchEpisode(title_number, episode_number)
+            }
+            ApiCommand::Play(title_number, None) => {
+                let episode_number = next_episode(
+                    self.backend.titles[title_number]
+                        .episodes
+                        .iter()
+                        .flatten()
+                        .map(|e| (e.metadata.watched, e.metadata.last_watched)),
+                )
+                .ok_or_else(|| ApiError::Failed(String::from("Every episode is watched!")))?;
+
+                BackendMessage::WatchEpisode(title_number, episode_number)
+            }
+            ApiCommand::SetWatched(title_number, episode_number, watched) => {
+                match self.backend.get_episode(title_number, episode_number) {
+                    Some(episode) if episode.metadata.watched == watched => return Ok(None),
+                    Some(_) => BackendMessage::MarkEpisode(title_number, episode_number),
+                    None => return Err(no_episode()),
+                }
+            }
+            ApiCommand::Refresh(title_number) => BackendMessage::LoadEpisodes(title_number, true),
+            ApiCommand::Rescan => BackendMessage::Restart,
+        };
+
+        Ok(Some(msg))
+    }
+}
+
+fn no_title() -> ApiError {
+    ApiError::NotFound(String::from("No title found"))
+}
+
+fn no_episode() -> ApiError {
+    ApiError::NotFound(String::from("No episode found"))
+}
@@ -0,0 +1,33 @@
+use crate::cache::Cache;
+
+use backend::TitleSort;
+use std::cmp::Reverse;
+
+impl Cache {
+    /// Returns the indexes of the titles in the _sort_ order.
+    ///
+    /// It only uses the cached data, the titles that can't be compared keep their folder order at the end.
+    pub fn sorted(&self, sort: TitleSort) -> Vec<usize> {
+        let mut titles: Vec<usize> = (0..self.size).collect();
+        let title = |idx: &usize| self.get_title(*idx);
+
+        match sort {
+            TitleSort::Folder => (),
+            TitleSort::Title => {
+                titles.sort_by_cached_key(|&idx| self.titles_names[idx].to_lowercase())
+            }
+            TitleSort::RecentlyWatched => {
+                titles.sort_by_key(|idx| Reverse(title(idx).last_watched()))
+            }
+            TitleSort::RecentlyAdded => titles.sort_by_key(|idx| Reverse(title(idx).added)),
+            TitleSort::Unwatched => titles.sort_by_key(|idx| match title(idx).unwatched() {
+                Some(0) | None => (true, 0),
+                Some(unwatched) => (false, unwatched),
+            }),
+            TitleSort::Score => titles.sort_by_key(|idx| Reverse(title(idx).score)),
+            TitleSort::Year => titles.sort_by_key(|idx| Reverse(title(idx).year)),
+        }
+
+        titles
+    }
+}
@@ -0,0 +1,138 @@
+use crate::cache::Cache;
+
+use backend::HistoryEntry;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Weeks shown in the activity calendar.
+pub const CALENDAR_WEEKS: usize = 53;
+/// Weeks counted in [`Stats::weekly`][Stats::weekly].
+pub const WEEKLY_WEEKS: usize = 8;
+/// Most genres and studios listed.
+const TOP_SIZE: usize = 5;
+const DAY: u64 = 86_400;
+
+/// Viewing statistics, only from the local [`History`][backend::History] and [`Cache`][Cache].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// Seconds watched.
+    pub watched: f64,
+    /// Episodes watched until the end.
+    pub episodes: usize,
+    /// Episodes watched until the end each of the last [`WEEKLY_WEEKS`][WEEKLY_WEEKS], the current week last.
+    pub weekly: Vec<usize>,
+    /// Seconds watched each day of the last [`CALENDAR_WEEKS`][CALENDAR_WEEKS], from a Monday to today.
+    pub days: Vec<f64>,
+    /// Genres of the episodes watched until the end, the most watched first.
+    pub genres: Vec<(Arc<str>, usize)>,
+    /// Studios of the episodes watched until the end, the most watched first.
+    pub studios: Vec<(Arc<str>, usize)>,
+    /// Args: (Title name, Episodes watched, Episodes) of the titles started, the most complete first.
+    pub titles: Vec<(Arc<str>, usize, usize)>,
+}
+
+impl Stats {
+    /// Creates new [`Stats`][Stats] from the entries of the [`History`][backend::History],
+    /// with _now_ as the Unix time of today.
+    ///
+    /// Only the numbers that don't need the titles are filled, see [`Cache::stats`][Cache::stats].
+    pub fn new(entries: &[HistoryEntry], now: u64) -> Self {
+        let today = now / DAY;
+        // The 1st of January of 1970 was a Thursday.
+        let weekday = (today + 3) % 7;
+        let first_day = (today - weekday).saturating_sub(7 * (CALENDAR_WEEKS as u64 - 1));
+        let first_week = (today - weekday).saturating_sub(7 * (WEEKLY_WEEKS as u64 - 1));
+
+        let mut stats = Self {
+            weekly: vec![0; WEEKLY_WEEKS],
+            days: vec![0.0; (today - first_day + 1) as usize],
+            ..Default::default()
+        };
+
+        for entry in entries {
+            // Playbacks of players that can't report where they ended don't add any time.
+            let watched = entry.end.map_or(0.0, |end| (end - entry.start).max(0.0));
+            let day = entry.started_at / DAY;
+
+            stats.watched += watched;
+
+            if (first_day..=today).contains(&day) {
+                stats.days[(day - first_day) as usize] += watched;
+            }
+
+            if entry.completed {
+                stats.episodes += 1;
+
+                if (first_week..=today).contains(&day) {
+                    stats.weekly[((day - first_week) / 7) as usize] += 1;
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Average of episodes watched until the end each week, of the last [`WEEKLY_WEEKS`][WEEKLY_WEEKS].
+    pub fn per_week(&self) -> f64 {
+        self.weekly.iter().sum::<usize>() as f64 / WEEKLY_WEEKS as f64
+    }
+}
+
+impl Cache {
+    /// Returns the [`Stats`][Stats] of the entries of the [`History`][backend::History],
+    /// with the genres, studios and progress of the titles.
+    pub fn stats(&self, entries: &[HistoryEntry], now: u64) -> Stats {
+        let mut stats = Stats::new(entries, now);
+
+        let titles: BTreeMap<&str, usize> = self
+            .titles_names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.as_ref(), idx))
+            .collect();
+
+        let mut genres: BTreeMap<Arc<str>, usize> = BTreeMap::new();
+        let mut studios: BTreeMap<Arc<str>, usize> = BTreeMap::new();
+
+        for entry in entries.iter().filter(|entry| entry.completed) {
+            let Some(&idx) = titles.get(entry.title.as_str()) else {
+                continue;
+            };
+            let title = self.get_title(idx);
+
+            for genre in title.genres.iter() {
+                *genres.entry(genre.clone()).or_default() += 1;
+            }
+
+            if let Some(studio) = &title.studio {
+                *studios.entry(studio.clone()).or_default() += 1;
+            }
+        }
+
+        stats.genres = top(genres);
+        stats.studios = top(studios);
+
+        stats.titles = (0..self.size)
+            .filter_map(|idx| {
+                let (watched, episodes) = self.get_title(idx).progress()?;
+                (watched > 0).then(|| (self.titles_names[idx].clone(), watched, episodes))
+            })
+            .collect();
+        stats.titles.sort_by(|(_, a, a_total), (_, b, b_total)| {
+            let a = *a as f64 / (*a_total).max(1) as f64;
+            let b = *b as f64 / (*b_total).max(1) as f64;
+            b.total_cmp(&a)
+        });
+
+        stats
+    }
+}
+
+/// Returns the [`TOP_SIZE`][TOP_SIZE] most counted, the most counted first.
+fn top(counts: BTreeMap<Arc<str>, usize>) -> Vec<(Arc<str>, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by_key(|(_, count)| Reverse(*count));
+    counts.truncate(TOP_SIZE);
+    counts
+}
@@ -0,0 +1,232 @@
+use backend::ApiConfig;
+use bridge::api::{route, ApiCommand, ApiError, Content, Request, Server};
+
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Client, Method, StatusCode};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+
+const TOKEN: &str = "so-pretty-so-yama";
+const DASHBOARD: &str = "http://dashboard.local:8123";
+
+/// Config of the API on a free loopback port.
+fn config(token: Option<&str>) -> ApiConfig {
+    ApiConfig {
+        enabled: true,
+        address: ([127, 0, 0, 1], 0).into(),
+        token: token.map(String::from),
+        allowed_origins: vec![String::from(DASHBOARD)],
+    }
+}
+
+/// Starts the API, answering like a [`Session`][bridge::session::Session] with
+/// a single title: the requests received are echoed back as JSON.
+async fn serve(cfg: &ApiConfig) -> (Server, SocketAddr) {
+    let (server, mut questions) = Server::bind(cfg).await.unwrap();
+    let address = server.address;
+
+    tokio::spawn(async move {
+        while let Some((request, reply)) = questions.recv().await {
+            let answer = match request {
+                Request::Titles => Ok(Content::Json(json!([{ "id": 0, "name": "Cowboy Bebop" }]))),
+                Request::Title(title_number) | Request::Thumbnail(title_number, _)
+                    if title_number > 0 =>
+                {
+                    Err(ApiError::NotFound(String::from("No title found")))
+                }
+                request => Ok(Content::Json(json!(format!("{request:?}")))),
+            };
+
+            let _ = reply.send(answer);
+        }
+    });
+
+    (server, address)
+}
+
+async fn send(
+    address: SocketAddr,
+    method: Method,
+    path: &str,
+    headers: &[(header::HeaderName, &str)],
+) -> hyper::Response<Body> {
+    let mut req = hyper::Request::builder()
+        .method(method)
+        .uri(format!("http://{address}{path}"));
+
+    for (name, value) in headers {
+        req = req.header(name, *value);
+    }
+
+    Client::new()
+        .request(req.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn call(address: SocketAddr, method: Method, path: &str) -> (StatusCode, Value) {
+    let res = send(address, method, path, &[]).await;
+    let status = res.status();
+    let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[test]
+fn routes_the_paths() {
+    assert_eq!(route(&Method::GET, "/api/titles"), Ok(Request::Titles));
+    assert_eq!(route(&Method::GET, "/api/titles/3/"), Ok(Request::Title(3)));
+    assert_eq!(
+        route(&Method::GET, "/api/titles/3/episodes/1/thumbnail"),
+        Ok(Request::Thumbnail(3, Some(0)))
+    );
+    assert_eq!(
+        route(&Method::POST, "/api/titles/3/play"),
+        Ok(Request::Command(ApiCommand::Play(3, None)))
+    );
+    assert_eq!(
+        route(&Method::DELETE, "/api/titles/3/episodes/12/watched"),
+        Ok(Request::Command(ApiCommand::SetWatched(3, 11, false)))
+    );
+
+    assert_eq!(
+        route(&Method::POST, "/api/titles"),
+        Err(ApiError::MethodNotAllowed)
+    );
+    assert!(matches!(
+        route(&Method::GET, "/api/titles/3/episodes/0/thumbnail"),
+        Err(ApiError::BadRequest(_))
+    ));
+    assert!(matches!(
+        route(&Method::GET, "/api/movies"),
+        Err(ApiError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn answers_on_a_loopback_port() {
+    let (_server, address) = serve(&config(None)).await;
+
+    let (status, titles) = call(address, Method::GET, "/api/titles").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(titles[0]["name"], "Cowboy Bebop");
+
+    // Episodes are numbered from 1 in the API, like in the command line.
+    let (status, request) = call(address, Method::PUT, "/api/titles/0/episodes/3/watched").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(request, "Command(SetWatched(0, 2, true))");
+
+    let (status, request) = call(address, Method::POST, "/api/rescan").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(request, "Command(Rescan)");
+
+    let (status, error) = call(address, Method::GET, "/api/titles/7").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["error"], "No title found");
+
+    let (status, _) = call(address, Method::GET, "/api/titles/seven").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = call(address, Method::DELETE, "/api/titles/0").await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn unavailable_once_not_answered() {
+    let (server, questions) = Server::bind(&config(None)).await.unwrap();
+    drop(questions);
+
+    let (status, _) = call(server.address, Method::GET, "/api/status").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn needs_the_token() {
+    let (_server, address) = serve(&config(Some(TOKEN))).await;
+
+    let res = send(address, Method::GET, "/api/titles", &[]).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(res.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+    let wrong = send(
+        address,
+        Method::POST,
+        "/api/rescan",
+        &[(header::AUTHORIZATION, "Bearer so-pretty")],
+    )
+    .await;
+    assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+    let bearer = format!("Bearer {TOKEN}");
+    let res = send(
+        address,
+        Method::GET,
+        "/api/titles",
+        &[(header::AUTHORIZATION, &bearer)],
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn only_allowed_pages_can_call_it() {
+    let (_server, address) = serve(&config(None)).await;
+
+    // Any page open in a browser could send this, even if it can't read the answer.
+    let res = send(
+        address,
+        Method::POST,
+        "/api/rescan",
+        &[(header::ORIGIN, "http://evil.example")],
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    assert!(!res
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    let res = send(
+        address,
+        Method::OPTIONS,
+        "/api/rescan",
+        &[(header::ORIGIN, DASHBOARD)],
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        res.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some(&HeaderValue::from_static(DASHBOARD))
+    );
+
+    let res = send(
+        address,
+        Method::GET,
+        "/api/titles",
+        &[(header::ORIGIN, DASHBOARD)],
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some(&HeaderValue::from_static(DASHBOARD))
+    );
+
+    // Requests without an origin, like from curl or an app, don't get the header.
+    let res = send(address, Method::GET, "/api/titles", &[]).await;
+    assert!(!res
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+}
+
+#[tokio::test]
+async fn needs_a_token_for_the_network() {
+    let mut cfg = config(None);
+    cfg.address = ([0, 0, 0, 0], 0).into();
+    assert!(Server::bind(&cfg).await.is_err());
+
+    cfg.token = Some(String::new());
+    assert!(Server::bind(&cfg).await.is_err());
+
+    cfg.token = Some(String::from(TOKEN));
+    assert!(Server::bind(&cfg).await.is_ok());
+}
@@ -0,0 +1,78 @@
+use backend::WatchStatus;
+use bridge::cache::TitleCache;
+use bridge::search::{fuzzy_score, TitleFilter};
+
+use std::sync::Arc;
+
+fn title(names: &[&str], genres: &[&str], studio: &str) -> TitleCache {
+    let mut title = TitleCache::default();
+    title.names = names.iter().map(|&name| Arc::from(name)).collect();
+    title.genres = genres.iter().map(|&genre| Arc::from(genre)).collect();
+    title.studio = Some(Arc::from(studio));
+    title
+}
+
+#[test]
+fn parses_filters() {
+    let filter = TitleFilter::parse(r#"steins genre:sci-fi studio:"white fox" is:unwatched"#);
+
+    assert_eq!(filter.words, ["steins"]);
+    assert_eq!(filter.genres, ["sci-fi"]);
+    assert_eq!(filter.studio.as_deref(), Some("white fox"));
+    assert!(filter.unwatched);
+    assert!(TitleFilter::parse("  ").is_empty());
+}
+
+#[test]
+fn fuzzy_prefers_consecutive_and_word_starts() {
+    assert!(fuzzy_score("fma", "Fullmetal Alchemist").is_some());
+    assert!(fuzzy_score("xyz", "Fullmetal Alchemist").is_none());
+    assert!(fuzzy_score("gate", "Steins;Gate") > fuzzy_score("gate", "Gintama: The Very Final"));
+    // The best place to start is found, not only the first one.
+    assert!(
+        fuzzy_score("his", "Show the watch history") > fuzzy_score("his", "Show the keybindings")
+    );
+    assert!(
+        fuzzy_score("hist", "Export the watch history")
+            > fuzzy_score("hsty", "Export the watch history")
+    );
+}
+
+#[test]
+fn matches_names_genres_and_studio() {
+    let steins = title(
+        &["Steins Gate", "STEINS;GATE"],
+        &["Sci-Fi", "Thriller"],
+        "White Fox",
+    );
+
+    assert!(TitleFilter::parse("steins").score(&steins).is_some());
+    assert!(TitleFilter::parse("thriller").score(&steins).is_some());
+    assert!(TitleFilter::parse("steins genre:sci")
+        .score(&steins)
+        .is_some());
+    assert!(TitleFilter::parse("genre:romance").score(&steins).is_none());
+    assert!(TitleFilter::parse("studio:bones").score(&steins).is_none());
+    assert!(TitleFilter::parse("is:unwatched").score(&steins).is_some());
+}
+
+#[test]
+fn filters_by_status() {
+    let mut steins = title(&["Steins Gate"], &["Sci-Fi"], "White Fox");
+
+    assert_eq!(
+        TitleFilter::parse("status:on-hold").status,
+        Some(WatchStatus::OnHold)
+    );
+    assert!(TitleFilter::parse(r#"status:"plan to watch""#)
+        .score(&steins)
+        .is_some());
+    assert!(TitleFilter::parse("status:dropped")
+        .score(&steins)
+        .is_none());
+
+    steins.status = Some(WatchStatus::Dropped);
+    assert!(TitleFilter::parse("status:dropped")
+        .score(&steins)
+        .is_some());
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use backend::{EpisodeId, History, NextEpisode, Progress, VideoMetadata};
+use bridge::session::Session;
+use bridge::{BackendMessage, FrontendMessage};
+use common::temp_dir;
+
+use iced::futures::channel::mpsc;
+use iced::futures::SinkExt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Creates a config folder with a library of one title and one episode, its metadata is already
+/// there so neither ffprobe nor the network are needed. The player only waits for a moment.
+fn library(root: &Path) -> PathBuf {
+    let config = root.join("config");
+    let title = root.join("library").join("Cowboy Bebop");
+    let episode = title.join("Asteroid Blues.mkv");
+
+    fs::create_dir_all(&config).unwrap();
+    fs::create_dir_all(&title).unwrap();
+    fs::write(&episode, b"not really a video").unwrap();
+
+    let id = EpisodeId::new(&episode).unwrap();
+    let metadata = title.join(".metadata");
+    let episode_metadata = metadata.join("episodes").join(id.as_str());
+    fs::create_dir_all(&episode_metadata).unwrap();
+    fs::write(episode_metadata.join("thumbnail.jpg"), b"").unwrap();
+    VideoMetadata::default_file(1_440.0, &episode_metadata.join("metadata.md")).unwrap();
+    info(&title, "Cowboy Bebop");
+
+    fs::write(
+        config.join("config.toml"),
+        format!(
+            r#"
+            theme_path = "themes/iced.json"
+            min_time = 10.0
+
+            [[libraries]]
+            name = "Anime"
+            path = "{}"
+            enabled = true
+
+            [player]
+            kind = "command"
+            template = "sh -c 'sleep 1' {{path}}"
+            "#,
+            root.join("library").display()
+        ),
+    )
+    .unwrap();
+
+    config
+}
+
+/// Writes the info of the _title_ folder as if a provider had found it.
+fn info(title: &Path, name: &str) {
+    let metadata = title.join(".metadata");
+    fs::create_dir_all(&metadata).unwrap();
+    fs::write(
+        metadata.join("data.json"),
+        format!(
+            r#"{{"data":{{"Media":{{"id":1,"title":{{"romaji":"{name}"}},"studios":{{"edges":[]}}}}}}}}"#
+        ),
+    )
+    .unwrap();
+}
+
+/// Returns the next [`FrontendMessage`][FrontendMessage] other than the updates of the title.
+async fn next(
+    session: &mut Session,
+    receiver: &mut mpsc::Receiver<BackendMessage>,
+) -> FrontendMessage {
+    loop {
+        match session.next(receiver).await {
+            FrontendMessage::TitleChanged(..) => (),
+            msg => return msg,
+        }
+    }
+}
+
+#[tokio::test]
+async fn restart_is_refused_while_playing() {
+    let root = temp_dir("session-restart");
+    let config = library(&root);
+
+    let (mut session, cache) = Session::in_config_dir(&config).await.unwrap();
+    assert_eq!(cache.titles_names.len(), 1);
+
+    let (mut sender, mut receiver) = mpsc::channel(1);
+
+    sender
+        .send(BackendMessage::WatchEpisode(0, 0))
+        .await
+        .unwrap();
+    let msg = next(&mut session, &mut receiver).await;
+    assert!(
+        matches!(msg, FrontendMessage::PlaybackStarted(..)),
+        "{msg:?}"
+    );
+
+    // Restarting now would drop the playback, so it's refused.
+    sender.send(BackendMessage::Restart).await.unwrap();
+    let msg = next(&mut session, &mut receiver).await;
+    assert!(matches!(msg, FrontendMessage::Error(_)), "{msg:?}");
+
+    let msg = next(&mut session, &mut receiver).await;
+    assert!(
+        matches!(msg, FrontendMessage::PlaybackFinished(0, Ok(_))),
+        "{msg:?}"
+    );
+    let history = History::new(config.join("history.jsonl"));
+    assert_eq!(history.entries().unwrap().len(), 1);
+
+    sender.send(BackendMessage::Restart).await.unwrap();
+    let msg = next(&mut session, &mut receiver).await;
+    assert!(matches!(msg, FrontendMessage::ToLoad), "{msg:?}");
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[tokio::test]
+async fn missing_episodes_are_errors() {
+    let root = temp_dir("session-missing");
+    let (mut session, _) = Session::in_config_dir(library(&root)).await.unwrap();
+
+    let (mut sender, mut receiver) = mpsc::channel(1);
+
+    for (title_number, episode_number) in [(0, 1), (1, 0)] {
+        sender
+            .send(BackendMessage::WatchEpisode(title_number, episode_number))
+            .await
+            .unwrap();
+        let msg = next(&mut session, &mut receiver).await;
+        assert!(matches!(msg, FrontendMessage::Error(_)), "{msg:?}");
+    }
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[tokio::test]
+async fn continue_watching_finds_the_episode_by_its_id() {
+    let root = temp_dir("session-continue");
+    let config = library(&root);
+
+    // The episode was the sixth one when it was saved, other files were removed since then.
+    let title = root.join("library").join("Cowboy Bebop");
+    let progress = Progress {
+        episodes: 6,
+        watched: 5,
+        last_watched: Some(1_693_603_800),
+        next: Some(NextEpisode {
+            number: 5,
+            id: Some(EpisodeId::new(&title.join("Asteroid Blues.mkv")).unwrap()),
+            name: String::from("Asteroid Blues"),
+            current: 0,
+        }),
+    };
+    progress
+        .save(&title.join(".metadata").join("progress.json"))
+        .unwrap();
+
+    let (mut session, cache) = Session::in_config_dir(&config).await.unwrap();
+    let next_up = cache.next_up();
+    assert_eq!(next_up.len(), 1);
+
+    let (mut sender, mut receiver) = mpsc::channel(1);
+
+    sender.send(next_up[0].message()).await.unwrap();
+    let msg = next(&mut session, &mut receiver).await;
+    assert!(
+        matches!(msg, FrontendMessage::PlaybackStarted(..)),
+        "{msg:?}"
+    );
+
+    let msg = next(&mut session, &mut receiver).await;
+    assert!(
+        matches!(msg, FrontendMessage::PlaybackFinished(0, Ok(_))),
+        "{msg:?}"
+    );
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+/// Waits for the titles of the library to change, returns the names added and removed,
+/// the new index of each previous title and the names of the titles after the change.
+async fn titles_changed(
+    session: &mut Session,
+    receiver: &mut mpsc::Receiver<BackendMessage>,
+) -> (
+    Vec<Arc<str>>,
+    Vec<Arc<str>>,
+    Vec<Option<usize>>,
+    Vec<Arc<str>>,
+) {
+    let next = tokio::time::timeout(Duration::from_secs(30), session.next(receiver));
+
+    match next.await.unwrap() {
+        FrontendMessage::TitlesChanged(added, removed, moved, cache) => {
+            (added, removed, moved, cache.titles_names.to_vec())
+        }
+        msg => panic!("{msg:?}"),
+    }
+}
+
+#[tokio::test]
+async fn titles_are_sorted_again_when_added_or_removed() {
+    let root = temp_dir("session-titles");
+    let (mut session, cache) = Session::in_config_dir(library(&root)).await.unwrap();
+    assert_eq!(cache.titles_names.len(), 1);
+
+    let (_sender, mut receiver) = mpsc::channel(1);
+
+    // It goes before the other title, which moves down.
+    let akira = root.join("library").join("Akira");
+    info(&akira, "Akira");
+    let (added, removed, moved, names) = titles_changed(&mut session, &mut receiver).await;
+    assert_eq!(added, [Arc::from("Akira")]);
+    assert!(removed.is_empty());
+    assert_eq!(moved, [Some(1)]);
+    assert_eq!(names, [Arc::from("Akira"), Arc::from("Cowboy Bebop")]);
+
+    fs::remove_dir_all(root.join("library").join("Cowboy Bebop")).unwrap();
+    let (added, removed, moved, names) = titles_changed(&mut session, &mut receiver).await;
+    assert!(added.is_empty());
+    assert_eq!(removed, [Arc::from("Cowboy Bebop")]);
+    assert_eq!(moved, [Some(0), None]);
+    assert_eq!(names, [Arc::from("Akira")]);
+
+    let _ = fs::remove_dir_all(&root);
+}
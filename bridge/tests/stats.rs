@@ -0,0 +1,55 @@
+use backend::HistoryEntry;
+use bridge::stats::{Stats, CALENDAR_WEEKS, WEEKLY_WEEKS};
+
+const DAY: u64 = 86_400;
+/// Friday, 2023-09-01 21:30 UTC.
+const NOW: u64 = 1_693_603_800;
+
+fn entry(started_at: u64, start: f64, end: f64, completed: bool) -> HistoryEntry {
+    HistoryEntry {
+        title: String::from("Cowboy Bebop"),
+        episode: String::from("Asteroid Blues"),
+        started_at,
+        ended_at: started_at + (end - start) as u64,
+        start,
+        end: Some(end),
+        completed,
+    }
+}
+
+#[test]
+fn stats_of_the_history() {
+    let entries = [
+        entry(NOW - 3_600, 0.0, 1_440.0, true),
+        entry(NOW - 3_600, 600.0, 1_440.0, true),
+        entry(NOW - DAY, 0.0, 720.0, false),
+        entry(NOW - 7 * DAY, 0.0, 1_440.0, true),
+        entry(NOW - 365 * DAY, 0.0, 1_440.0, true),
+    ];
+
+    let stats = Stats::new(&entries, NOW);
+
+    assert_eq!(stats.watched, 1_440.0 * 3.0 + 840.0 + 720.0);
+    assert_eq!(stats.episodes, 4);
+
+    assert_eq!(stats.weekly.len(), WEEKLY_WEEKS);
+    assert_eq!(stats.weekly[WEEKLY_WEEKS - 1], 2);
+    assert_eq!(stats.weekly[WEEKLY_WEEKS - 2], 1);
+    assert_eq!(stats.per_week(), 3.0 / WEEKLY_WEEKS as f64);
+
+    // From a Monday to today, a Friday.
+    assert_eq!(stats.days.len(), 7 * (CALENDAR_WEEKS - 1) + 5);
+    assert_eq!(stats.days[stats.days.len() - 1], 1_440.0 + 840.0);
+    assert_eq!(stats.days[stats.days.len() - 2], 720.0);
+    assert_eq!(stats.days[stats.days.len() - 8], 1_440.0);
+    assert_eq!(stats.days.iter().sum::<f64>(), stats.watched);
+}
+
+#[test]
+fn stats_without_history() {
+    let stats = Stats::new(&[], NOW);
+
+    assert_eq!(stats.watched, 0.0);
+    assert_eq!(stats.per_week(), 0.0);
+    assert!(stats.days.iter().all(|&day| day == 0.0));
+}
@@ -1,10 +1,10 @@
 use crate::widgets::theme::{self, widget::Element};
 
 use anyhow::{bail, Ok};
-use backend::Config;
+use backend::{Config, TitleSort};
 use bridge::{ConfigChange, FrontendMessage};
 
-use iced::widget::{button, column, row, text, tooltip, vertical_space};
+use iced::widget::{button, column, row, text, tooltip, vertical_space, Column};
 use iced::{alignment, Length};
 use iced_aw::NumberInput;
 use tracing::{info, warn};
@@ -21,10 +21,38 @@ impl GUIConfig {
                 .unwrap_unchecked()
         });
 
-        let series_path = match &cfg.series_path {
-            Some(path) => path.display().to_string(),
-            None => "No path found".to_string(),
-        };
+        let libraries = Column::with_children(
+            cfg.libraries()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, library)| {
+                    row![
+                        text(format!("{}: ", library.name))
+                            .width(Length::Fixed(100.0))
+                            .horizontal_alignment(alignment::Horizontal::Right),
+                        text(library.path.display().to_string()).width(Length::Fill),
+                        button(text(if library.enabled {
+                            "Enabled"
+                        } else {
+                            "Disabled"
+                        }))
+                        .on_press(FrontendMessage::UpdateConfig(ConfigChange::ToggleLibrary(
+                            idx
+                        )))
+                        .style(theme::Button::Input),
+                        button(text("Remove"))
+                            .on_press(FrontendMessage::UpdateConfig(ConfigChange::RemoveLibrary(
+                                idx
+                            )))
+                            .style(theme::Button::Input),
+                    ]
+                    .align_items(alignment::Alignment::Center)
+                    .spacing(15)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(10);
 
         column![
             text("Configs")
@@ -32,18 +60,16 @@ impl GUIConfig {
                 .vertical_alignment(alignment::Vertical::Top)
                 .horizontal_alignment(alignment::Horizontal::Center)
                 .width(Length::Fill),
+            libraries,
             tooltip(
-                row![
-                    text("Series Path: ")
-                        .width(Length::Fixed(100.0))
-                        .horizontal_alignment(alignment::Horizontal::Right),
-                    button(text(series_path))
-                        .on_press(FrontendMessage::UpdateConfig(ConfigChange::SeriesPath))
-                        .style(theme::Button::Input)
-                        .width(Length::Fill),
-                ]
-                .align_items(alignment::Alignment::Center)
-                .spacing(15),
+                button(
+                    text("Add library")
+                        .horizontal_alignment(alignment::Horizontal::Center)
+                        .width(Length::Fill)
+                )
+                .on_press(FrontendMessage::UpdateConfig(ConfigChange::AddLibrary))
+                .style(theme::Button::Input)
+                .width(Length::Fill),
                 "Folder to scan for Titles",
                 tooltip::Position::Top,
             )
@@ -79,6 +105,44 @@ impl GUIConfig {
                 tooltip::Position::Top,
             )
             .style(theme::Container::Tooltip),
+            tooltip(
+                row![
+                    text("Metadata: ")
+                        .width(Length::Fixed(100.0))
+                        .horizontal_alignment(alignment::Horizontal::Right),
+                    button(text(if cfg.central_metadata {
+                        "Config folder"
+                    } else {
+                        "Series folders"
+                    }))
+                    .on_press(FrontendMessage::UpdateConfig(
+                        ConfigChange::CentralMetadata(!cfg.central_metadata)
+                    ))
+                    .style(theme::Button::Input)
+                    .width(Length::Fill),
+                ]
+                .align_items(alignment::Alignment::Center)
+                .spacing(15),
+                "Where the metadata of each title is stored",
+                tooltip::Position::Top,
+            )
+            .style(theme::Container::Tooltip),
+            tooltip(
+                row![
+                    text("Sort titles: ")
+                        .width(Length::Fixed(100.0))
+                        .horizontal_alignment(alignment::Horizontal::Right),
+                    button(text(cfg.title_sort.to_string()))
+                        .on_press(FrontendMessage::UpdateConfig(ConfigChange::NextTitleSort))
+                        .style(theme::Button::Input)
+                        .width(Length::Fill),
+                ]
+                .align_items(alignment::Alignment::Center)
+                .spacing(15),
+                "Order of the title list",
+                tooltip::Position::Top,
+            )
+            .style(theme::Container::Tooltip),
             vertical_space(Length::Fill),
             button(
                 text("  Ok?  ")
@@ -95,15 +159,16 @@ impl GUIConfig {
         .into()
     }
 
-    pub fn change_series_path(cfg: &mut Config) -> crate::Result<()> {
+    pub fn add_library(cfg: &mut Config) -> crate::Result<()> {
         let path = std::env::current_dir()?;
         let res = rfd::FileDialog::new().set_directory(path).pick_folder();
         info!("The user choose: {:#?}", res);
 
         if let Some(path) = res {
             if std::fs::metadata(&path).is_ok() {
-                std::env::set_current_dir(path.clone())?;
-                cfg.series_path = Some(path);
+                if !cfg.add_library(path) {
+                    bail!("The library was already added")
+                }
 
                 if let Err(error) = confy::store("yama", "config", cfg) {
                     bail!("Could not save config because: {:#?}", error)
@@ -116,6 +181,22 @@ impl GUIConfig {
         bail!("Invalid path")
     }
 
+    pub fn remove_library(cfg: &mut Config, index: usize) {
+        cfg.remove_library(index);
+
+        if let Err(error) = confy::store("yama", "config", cfg) {
+            warn!("Could not save config because: {:#?}", error)
+        }
+    }
+
+    pub fn toggle_library(cfg: &mut Config, index: usize) {
+        cfg.toggle_library(index);
+
+        if let Err(error) = confy::store("yama", "config", cfg) {
+            warn!("Could not save config because: {:#?}", error)
+        }
+    }
+
     pub fn change_theme_path(cfg: &mut Config) {
         let path = confy::get_configuration_file_path("yama", "config")
             .unwrap()
@@ -139,6 +220,22 @@ impl GUIConfig {
         warn!("Invalid path");
     }
 
+    pub fn change_central_metadata(cfg: &mut Config, central: bool) {
+        cfg.central_metadata = central;
+
+        if let Err(error) = confy::store("yama", "config", cfg) {
+            warn!("Could not save config because: {:#?}", error)
+        }
+    }
+
+    pub fn change_title_sort(cfg: &mut Config, sort: TitleSort) {
+        cfg.title_sort = sort;
+
+        if let Err(error) = confy::store("yama", "config", cfg) {
+            warn!("Could not save config because: {:#?}", error)
+        }
+    }
+
     pub fn change_min_time(cfg: &mut Config, new_time: f32) {
         cfg.min_time = new_time;
 
@@ -149,7 +246,7 @@ impl GUIConfig {
 }
 
 /// Utils
-fn capitalize(s: &str) -> String {
+pub(crate) fn capitalize(s: &str) -> String {
     let mut c = s.chars();
     match c.next() {
         None => String::new(),
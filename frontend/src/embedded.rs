@@ -11,6 +11,9 @@ pub static YAMA_ICON: &[u8] = include_bytes!("../../res/yama_logo.ico");
 pub static YAMA_PNG: &[u8] = include_bytes!("../../res/yama.png");
 pub static YAMA_ERR: &[u8] = include_bytes!("../../res/yama_error.png");
 
+// Themes
+pub static DEFAULT_THEME: &[u8] = include_bytes!("../../res/iced.json");
+
 // Fonts
 pub static REGULAR_FONT_BYTES: &[u8] = include_bytes!("../../res/fonts/KumbhSans-Regular.ttf");
 pub static BOLD_FONT_BYTES: &[u8] = include_bytes!("../../res/fonts/KumbhSans-SemiBold.ttf");
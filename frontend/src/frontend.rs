@@ -1,42 +1,51 @@
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::config::GUIConfig;
+use crate::setup::Setup;
 use crate::widgets::*;
-use crate::{keybindings, Result};
+use crate::{keybindings, palette, Result};
 
-use backend::Config;
+use backend::{Config, Diagnostics, History, HistoryFormat};
+use bridge::{stats::Stats, MatchPicker};
 use bridge::{BackendMessage, ConfigChange, FrontendMessage as Message, Modals, PanelAction};
 
 use iced::futures::channel::mpsc::Sender;
 use iced::widget::{
     button, canvas, column, container, horizontal_space, pane_grid::Direction, row, text,
+    text_input,
 };
-use iced::{alignment, executor, font, keyboard, mouse, window, Font};
-use iced::{event, subscription, Event};
+use iced::{event, keyboard, subscription, Event};
+use iced::{executor, font, window, Font};
 use iced::{Application, Command, Length, Settings, Subscription};
 use tracing::{error, info};
 
 #[derive(Debug)]
 pub enum State {
+    /// First launch, the backend doesn't start until it's finished.
+    Setup(Box<Setup>),
     Normal,
     Loading,
-    Watching,
     ShowingMenu(Modals),
 }
 
 #[derive(Debug)]
 pub struct Frontend {
     cfg: Config,
+    theme: theme::Theme,
     state: State,
     pane: Option<Panels>,
     loading: LoadingCircle,
     sender: Option<Sender<BackendMessage>>,
+    /// Title and episode names of the episode being played in the background.
+    now_playing: Option<(Arc<str>, Arc<str>)>,
 }
 
 impl Frontend {
-    pub fn execute(cfg: Config) -> Result<()> {
+    /// Runs [yama], starting with the [`Setup`] if it's the _first_run_.
+    pub fn execute(cfg: Config, first_run: bool) -> Result<()> {
         Ok(Frontend::run(Settings {
-            flags: cfg,
+            flags: (cfg, first_run),
             antialiasing: true,
             window: window::Settings {
                 size: (1600, 900),
@@ -57,16 +66,27 @@ impl Application for Frontend {
     type Message = Message;
     type Theme = theme::Theme;
     type Executor = executor::Default;
-    type Flags = Config;
+    type Flags = (Config, bool);
+
+    fn new((cfg, first_run): Self::Flags) -> (Self, Command<Message>) {
+        let theme = theme::Theme::from_path(&cfg.theme_path).unwrap_or_else(|err| {
+            error!("Could not load the theme: {err}");
+            theme::Theme::default()
+        });
+        let state = match first_run {
+            true => State::Setup(Box::new(Setup::new(Config::default()))),
+            false => State::Loading,
+        };
 
-    fn new(cfg: Self::Flags) -> (Self, Command<Message>) {
         (
             Self {
                 cfg,
+                theme,
                 pane: None,
-                state: State::Loading,
+                state,
                 loading: LoadingCircle::new(),
                 sender: None,
+                now_playing: None,
             },
             Command::batch(vec![
                 font::load(crate::embedded::REGULAR_FONT_BYTES).map(Message::FontLoaded),
@@ -80,19 +100,91 @@ impl Application for Frontend {
         String::from("yama")
     }
 
+    fn theme(&self) -> theme::Theme {
+        match &self.state {
+            State::Setup(setup) => setup.theme(),
+            _ => self.theme,
+        }
+    }
+
     fn update(&mut self, message: Message) -> Command<Message> {
+        // The playback runs in the background, so it can start or finish in any state.
+        match message {
+            Message::PlaybackStarted(title_name, episode_name) => {
+                self.now_playing = Some((title_name, episode_name));
+                return Command::none();
+            }
+            Message::PlaybackFinished(title_number, result) => {
+                self.now_playing = None;
+
+                return match result {
+                    Ok(episode_cache) => Command::perform(
+                        async move { PanelAction::UpdateEpisode(title_number, vec![episode_cache]) },
+                        Message::PaneAction,
+                    ),
+                    Err(err) => Command::perform(async { err }, Message::Error),
+                };
+            }
+            // So do the changes in the libraries.
+            Message::TitlesChanged(added, removed, moved, cache) => {
+                info!(
+                    "Titles changed, added: [{}], removed: [{}]",
+                    added.join(", "),
+                    removed.join(", ")
+                );
+
+                if let Some(pane) = &mut self.pane {
+                    pane.change_titles(cache, &moved);
+                }
+                return Command::none();
+            }
+            Message::TitleChanged(title_number, name, title_cache) => {
+                if let Some(pane) = &mut self.pane {
+                    pane.change_title(title_number, name, title_cache);
+                }
+                return Command::none();
+            }
+            Message::EpisodesAdded(title_number, names, title_cache)
+            | Message::EpisodesRemoved(title_number, names, title_cache) => {
+                info!("Episodes changed: {}", names.join(", "));
+
+                if let Some(pane) = &mut self.pane {
+                    pane.update_title(title_number, title_cache);
+                }
+                return Command::none();
+            }
+            _ => (),
+        }
+
         match self.state {
+            State::Setup(ref mut setup) => {
+                if let Message::Setup(action) = message {
+                    if let Some(cfg) = setup.update(action) {
+                        info!("First launch setup finished");
+                        self.theme = setup.theme();
+                        self.cfg = cfg;
+                        self.state = State::Loading;
+                    }
+                }
+            }
             State::Loading => match message {
                 Message::Loading(instant) => self.loading.update(instant),
                 Message::Ready(sender, cache) => {
                     self.sender = Some(sender.clone());
-                    self.pane = Some(Panels::new(cache, sender));
+                    self.pane = Some(Panels::new(cache, sender, self.cfg.title_sort));
                     self.state = State::Normal;
 
-                    return Command::perform(
+                    let focus = Command::perform(
                         async { PanelAction::FocusItem(Direction::Left) },
                         Message::PaneAction,
                     );
+
+                    return match keybindings::keymap_error() {
+                        Some(err) => {
+                            Command::batch([focus, Command::perform(async { err }, Message::Error)])
+                        }
+                        None => focus,
+                    };
                 }
                 Message::PaneAction(message) => {
                     if let Some(pane) = &mut self.pane {
@@ -108,6 +200,13 @@ impl Application for Frontend {
                     self.sender = Some(sender);
                     return Command::perform(async { err }, Message::Error);
                 }
+                Message::MatchesFound(title_number, search, candidates) => {
+                    self.state = State::ShowingMenu(Modals::Matches(MatchPicker {
+                        title_number,
+                        search: search.to_string(),
+                        candidates,
+                    }));
+                }
                 _ => (),
             },
 
@@ -119,12 +218,23 @@ impl Application for Frontend {
                 }
 
                 match message {
-                    Message::ToLoad => self.state = State::Loading,
-                    Message::MenuBar(menu) => self.state = State::ShowingMenu(menu),
+                    Message::ToLoad => {
+                        // The backend only restarts when no episode is being played.
+                        self.now_playing = None;
+                        self.state = State::Loading;
+                    }
+                    Message::MenuBar(menu) => {
+                        let palette = matches!(menu, Modals::Palette(_));
+                        self.state = State::ShowingMenu(menu);
+
+                        if palette {
+                            return text_input::focus(menus::PALETTE_ID.clone());
+                        }
+                    }
                     Message::HideMenubar => self.state = State::Normal,
                     Message::UpdateConfig(change) => match change {
-                        ConfigChange::SeriesPath => {
-                            let res = GUIConfig::change_series_path(&mut self.cfg);
+                        ConfigChange::AddLibrary => {
+                            let res = GUIConfig::add_library(&mut self.cfg);
 
                             match res {
                                 Ok(()) => {
@@ -140,11 +250,170 @@ impl Application for Frontend {
                                 }
                             }
                         }
-                        ConfigChange::ThemePath => GUIConfig::change_theme_path(&mut self.cfg),
+                        ConfigChange::RemoveLibrary(index) => {
+                            GUIConfig::remove_library(&mut self.cfg, index);
+
+                            if let Some(sender) = &mut self.sender {
+                                let _ = sender.try_send(BackendMessage::Restart);
+                            }
+                        }
+                        ConfigChange::ToggleLibrary(index) => {
+                            GUIConfig::toggle_library(&mut self.cfg, index);
+
+                            if let Some(sender) = &mut self.sender {
+                                let _ = sender.try_send(BackendMessage::Restart);
+                            }
+                        }
+                        ConfigChange::ThemePath => {
+                            GUIConfig::change_theme_path(&mut self.cfg);
+
+                            match theme::Theme::from_path(&self.cfg.theme_path) {
+                                Ok(theme) => self.theme = theme,
+                                Err(err) => error!("Could not load the theme: {err}"),
+                            }
+                        }
                         ConfigChange::MinTime(new_time) => {
                             GUIConfig::change_min_time(&mut self.cfg, new_time)
                         }
+                        ConfigChange::NextTitleSort => {
+                            let sort = self.cfg.title_sort.next();
+                            GUIConfig::change_title_sort(&mut self.cfg, sort);
+
+                            if let Some(pane) = &mut self.pane {
+                                pane.set_sort(self.cfg.title_sort);
+                            }
+                        }
+                        ConfigChange::CentralMetadata(central) => {
+                            GUIConfig::change_central_metadata(&mut self.cfg, central);
+
+                            if let Some(sender) = &mut self.sender {
+                                let _ = sender.try_send(BackendMessage::Restart);
+                            }
+                        }
+                    },
+                    Message::MatchSearchChanged(search) => {
+                        if let State::ShowingMenu(Modals::Matches(picker)) = &mut self.state {
+                            picker.search = search;
+                        }
+                    }
+                    Message::SearchMatches => {
+                        if let State::ShowingMenu(Modals::Matches(picker)) = &self.state {
+                            let msg = BackendMessage::SearchMatches(
+                                picker.title_number,
+                                Some(Arc::from(picker.search.trim())),
+                            );
+
+                            if let Some(sender) = &mut self.sender {
+                                let _ = sender.try_send(msg);
+                                self.state = State::Loading;
+                            }
+                        }
+                    }
+                    Message::PinMatch(index) => {
+                        if let State::ShowingMenu(Modals::Matches(picker)) = &self.state {
+                            let msg = BackendMessage::PinMatch(
+                                picker.title_number,
+                                picker.candidates[index].ids.clone(),
+                            );
+
+                            if let Some(sender) = &mut self.sender {
+                                let _ = sender.try_send(msg);
+                            }
+                            self.state = State::Normal;
+                        }
+                    }
+                    Message::PaletteChanged(query) => {
+                        if let State::ShowingMenu(Modals::Palette(palette)) = &mut self.state {
+                            palette.query = query;
+                            palette.focused = 0;
+                        }
+                    }
+                    Message::PaletteMove(plus) => {
+                        if let State::ShowingMenu(Modals::Palette(palette)) = &mut self.state {
+                            let found = palette::search(&palette.query).len();
+                            palette.focused = palette
+                                .focused
+                                .saturating_add_signed(plus)
+                                .min(found.saturating_sub(1));
+                        }
+                    }
+                    Message::PaletteSubmit(index) => {
+                        if let State::ShowingMenu(Modals::Palette(palette)) = &self.state {
+                            let command = palette::search(&palette.query).into_iter().nth(index);
+                            self.state = State::Normal;
+
+                            if let Some(command) = command {
+                                info!("Running command: {}", command.name);
+                                let message = command.message;
+                                return Command::perform(async { message }, |message| message);
+                            }
+                        }
+                    }
+                    Message::Restart => {
+                        if let Some(sender) = &mut self.sender {
+                            let _ = sender.try_send(BackendMessage::Restart);
+                        }
+                    }
+                    Message::ShowHistory => match History::default().entries() {
+                        Ok(entries) => {
+                            self.state = State::ShowingMenu(Modals::History(Arc::from(entries)))
+                        }
+                        Err(err) => {
+                            error!("Could not read the watch history: {err}");
+                            return Command::perform(
+                                async { Arc::from("Could not read the watch history!") },
+                                Message::Error,
+                            );
+                        }
+                    },
+                    Message::ShowStats => match History::default().entries() {
+                        Ok(entries) => {
+                            let now = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .map_or(0, |time| time.as_secs());
+                            let stats = match &self.pane {
+                                Some(pane) => pane.cache().stats(&entries, now),
+                                None => Stats::new(&entries, now),
+                            };
+
+                            self.state = State::ShowingMenu(Modals::Stats(Arc::new(stats)))
+                        }
+                        Err(err) => {
+                            error!("Could not read the watch history: {err}");
+                            return Command::perform(
+                                async { Arc::from("Could not read the watch history!") },
+                                Message::Error,
+                            );
+                        }
                     },
+                    Message::ShowDiagnostics => {
+                        let cfg = self.cfg.clone();
+
+                        // The programs are launched to get their versions, so it runs in a blocking thread.
+                        return Command::perform(
+                            async move {
+                                tokio::task::spawn_blocking(move || Diagnostics::run(&cfg)).await
+                            },
+                            |diagnostics| match diagnostics {
+                                Ok(diagnostics) => {
+                                    Message::MenuBar(Modals::Diagnostics(Arc::new(diagnostics)))
+                                }
+                                Err(err) => {
+                                    error!("Could not run the diagnostics: {err}");
+                                    Message::Error(Arc::from("Could not run the diagnostics!"))
+                                }
+                            },
+                        );
+                    }
+                    Message::ExportHistory(format) => {
+                        if let Err(err) = export_history(format) {
+                            error!("Could not export the watch history: {err}");
+                            return Command::perform(
+                                async { Arc::from("Could not export the watch history!") },
+                                Message::Error,
+                            );
+                        }
+                    }
                     Message::CleanUp => {
                         if let Some(sender) = &mut self.sender {
                             let _ = sender.try_send(BackendMessage::CleanUp);
@@ -172,6 +441,9 @@ impl Application for Frontend {
 
     fn subscription(&self) -> Subscription<Message> {
         match self.state {
+            //The backend starts once the setup is finished
+            State::Setup(_) => Subscription::none(),
+
             //Loading subscription, disables input
             State::Loading => Subscription::batch(vec![
                 bridge::subscription::start(),
@@ -186,49 +458,71 @@ impl Application for Frontend {
                         return None;
                     }
 
-                    match event {
-                        Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
-                            keybindings::handle_mousewheel(delta)
-                        }
-
-                        Event::Mouse(mouse::Event::ButtonPressed(button)) => {
-                            keybindings::handle_mousebutton(button)
-                        }
-
-                        Event::Keyboard(keyboard::Event::KeyPressed {
-                            key_code,
-                            modifiers,
-                        }) if modifiers.shift() && key_code == keyboard::KeyCode::W => {
-                            Some(Message::PaneAction(PanelAction::MarkPreviousEpisodes))
-                        }
-
-                        Event::Keyboard(keyboard::Event::KeyPressed {
-                            key_code,
-                            modifiers: _,
-                        }) => keybindings::handle_hotkey(key_code),
+                    keybindings::handle_event(event)
+                }),
+            ]),
 
+            //Command palette, moves the focus between the commands
+            State::ShowingMenu(Modals::Palette(_)) => Subscription::batch(vec![
+                bridge::subscription::start(),
+                subscription::events_with(|event, _| match event {
+                    Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => match key_code
+                    {
+                        keyboard::KeyCode::Up => Some(Message::PaletteMove(-1)),
+                        keyboard::KeyCode::Down => Some(Message::PaletteMove(1)),
+                        keyboard::KeyCode::PageUp => Some(Message::PaletteMove(-5)),
+                        keyboard::KeyCode::PageDown => Some(Message::PaletteMove(5)),
+                        keyboard::KeyCode::Escape => Some(Message::HideMenubar),
                         _ => None,
-                    }
+                    },
+                    _ => None,
                 }),
             ]),
 
-            State::Watching | State::ShowingMenu(_) => bridge::subscription::start(),
+            State::ShowingMenu(_) => bridge::subscription::start(),
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let pane_view = if let Some(pane) = &self.pane {
             pane.view()
         } else {
             horizontal_space(Length::Shrink).into()
         };
 
+        let now_playing_bar: Element<Message> = match &self.now_playing {
+            Some((title_name, episode_name)) => container(
+                row![
+                    text("Now playing:").style(theme::Text::Focused),
+                    text(title_name.clone()),
+                    text("-"),
+                    text(episode_name.clone()),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+            )
+            .width(Length::Fill)
+            .padding(10)
+            .style(theme::Container::Focused)
+            .into(),
+            None => horizontal_space(Length::Shrink).into(),
+        };
+
         let content = container(
             column![
                 row![
                     button("Config")
                         .on_press(Message::MenuBar(Modals::Config))
                         .style(theme::Button::Menu),
+                    button("History")
+                        .on_press(Message::ShowHistory)
+                        .style(theme::Button::Menu),
+                    button("Stats")
+                        .on_press(Message::ShowStats)
+                        .style(theme::Button::Menu),
+                    button("Diagnostics")
+                        .on_press(Message::ShowDiagnostics)
+                        .style(theme::Button::Menu),
                     button("About")
                         .on_press(Message::MenuBar(Modals::About))
                         .style(theme::Button::Menu),
@@ -240,6 +534,7 @@ impl Application for Frontend {
                 .align_items(iced::Alignment::Center)
                 .width(Length::Fill),
                 pane_view,
+                now_playing_bar,
             ]
             .spacing(10),
         )
@@ -248,6 +543,12 @@ impl Application for Frontend {
         .padding(35);
 
         match &self.state {
+            State::Setup(setup) => container(setup.view())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+                .into(),
             State::Loading => {
                 let modal = container(
                     canvas(&self.loading)
@@ -261,28 +562,17 @@ impl Application for Frontend {
                 Modal::new(content, modal).into()
             }
 
-            State::Watching => {
-                let modal = container(
-                    text("Watching episode...")
-                        .size(42)
-                        .vertical_alignment(alignment::Vertical::Center)
-                        .horizontal_alignment(alignment::Horizontal::Center)
-                        .width(Length::Fill)
-                        .height(Length::Fill),
-                )
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .padding(15);
-
-                Modal::new(content, modal).into()
-            }
-
             State::ShowingMenu(menu) => {
                 let modal = match menu {
                     Modals::Help => menus::help(),
                     Modals::About => menus::about(),
                     Modals::Config => menus::config(&self.cfg),
                     Modals::Yama => menus::yama(),
+                    Modals::Matches(picker) => menus::matches(picker),
+                    Modals::History(entries) => menus::history(entries),
+                    Modals::Stats(stats) => menus::stats(stats),
+                    Modals::Diagnostics(diagnostics) => menus::diagnostics(diagnostics),
+                    Modals::Palette(palette) => menus::palette(palette),
                     Modals::Error(err) => menus::error(err.clone()),
                 };
 
@@ -294,3 +584,18 @@ impl Application for Frontend {
         }
     }
 }
+
+/// Asks where to save the watch [`History`][History] and exports it in the given _format_.
+fn export_history(format: HistoryFormat) -> crate::Result<()> {
+    let extension = format.extension();
+    let res = rfd::FileDialog::new()
+        .set_file_name(format!("yama-history.{extension}"))
+        .add_filter(extension, &[extension])
+        .save_file();
+    info!("The user choose: {:#?}", res);
+
+    match res {
+        Some(path) => History::default().export(&path, format),
+        None => Ok(()),
+    }
+}
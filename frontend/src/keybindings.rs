@@ -1,53 +1,76 @@
-use bridge::{FrontendMessage as Message, PanelAction};
+mod keymap;
 
-use iced::widget::pane_grid::Direction;
-use iced::{keyboard, mouse};
+pub use keymap::{Action, Chord, Input, Keymap, KeymapFile};
 
-pub fn handle_hotkey(key_code: keyboard::KeyCode) -> Option<Message> {
-    use keyboard::KeyCode;
+use bridge::FrontendMessage as Message;
 
-    match key_code {
-        // Bridge Messages
-        KeyCode::Up | KeyCode::K => {
-            Some(Message::PaneAction(PanelAction::FocusItem(Direction::Up)))
-        }
-        KeyCode::Down | KeyCode::J => {
-            Some(Message::PaneAction(PanelAction::FocusItem(Direction::Down)))
+use iced::{keyboard, mouse, Event};
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use tracing::error;
+
+/// The [`Keymap`] loaded from the _keymap.toml_ file in the config folder,
+/// along with the error found while loading it, if any, in which case the default one is used.
+static KEYMAP: Lazy<(Keymap, Option<Arc<str>>)> = Lazy::new(|| {
+    let path = confy::get_configuration_file_path("yama", "config")
+        .ok()
+        .and_then(|path| path.parent().map(|parent| parent.join("keymap.toml")))
+        .unwrap_or_else(|| PathBuf::from("keymap.toml"));
+
+    match Keymap::load(&path) {
+        Ok(keymap) => (keymap, None),
+        Err(e) => {
+            let e = format!(
+                "Invalid keymap at {}, using the default one: {e}",
+                path.display()
+            );
+            error!("{e}");
+            (Keymap::default(), Some(e.into()))
         }
-        KeyCode::Right | KeyCode::L => Some(Message::PaneAction(PanelAction::Enter)),
-        KeyCode::Enter => Some(Message::PaneAction(PanelAction::Enter)),
-        KeyCode::Left | KeyCode::H => Some(Message::PaneAction(PanelAction::Back)),
-        KeyCode::R => Some(Message::PaneAction(PanelAction::Refresh)),
-        KeyCode::W | KeyCode::Space => Some(Message::PaneAction(PanelAction::MarkEpisode)),
-        KeyCode::PageDown => Some(Message::PaneAction(PanelAction::Plus(5))),
-        KeyCode::PageUp => Some(Message::PaneAction(PanelAction::Plus(-5))),
-        KeyCode::Home => Some(Message::PaneAction(PanelAction::Start)),
-        KeyCode::End => Some(Message::PaneAction(PanelAction::End)),
-
-        // Messages
-        KeyCode::Q => Some(Message::CleanUp),
-        _ => None,
     }
+});
+
+/// Returns the [`Keymap`] in use.
+pub fn keymap() -> &'static Keymap {
+    &KEYMAP.0
 }
 
-pub fn handle_mousewheel(delta: mouse::ScrollDelta) -> Option<Message> {
-    if let mouse::ScrollDelta::Lines { x: _, y } = delta {
-        if y > 0.0 {
-            Some(Message::PaneAction(PanelAction::FocusItem(Direction::Up)))
-        } else {
-            Some(Message::PaneAction(PanelAction::FocusItem(Direction::Down)))
-        }
-    } else {
-        None
-    }
+/// Returns the error found while loading the keymap file, if any.
+pub fn keymap_error() -> Option<Arc<str>> {
+    KEYMAP.1.clone()
 }
 
-pub fn handle_mousebutton(button: mouse::Button) -> Option<Message> {
-    match button {
-        mouse::Button::Right | mouse::Button::Other(8) => {
-            Some(Message::PaneAction(PanelAction::Back))
+/// Modifiers held, tracked to match them with the mouse inputs.
+static MODIFIERS: AtomicU32 = AtomicU32::new(0);
+
+/// Returns the [`Message`] of the [`Action`] bound to the input of the _event_, if any.
+pub fn handle_event(event: Event) -> Option<Message> {
+    let modifiers = || keyboard::Modifiers::from_bits_truncate(MODIFIERS.load(Ordering::Relaxed));
+
+    let input = match event {
+        Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+            MODIFIERS.store(modifiers.bits(), Ordering::Relaxed);
+            return None;
         }
-        mouse::Button::Other(9) => Some(Message::PaneAction(PanelAction::Enter)),
-        _ => None,
-    }
+        Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code,
+            modifiers,
+        }) => {
+            MODIFIERS.store(modifiers.bits(), Ordering::Relaxed);
+            return handle(Chord::new(modifiers, Input::Key(key_code)));
+        }
+        Event::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Lines { x: _, y },
+        }) => Input::Wheel(y > 0.0),
+        Event::Mouse(mouse::Event::ButtonPressed(button)) => Input::Mouse(button),
+        _ => return None,
+    };
+
+    handle(Chord::new(modifiers(), input))
+}
+
+fn handle(chord: Chord) -> Option<Message> {
+    keymap().action(chord).map(|action| action.message())
 }
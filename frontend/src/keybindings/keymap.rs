@@ -0,0 +1,405 @@
+use bridge::{CommandPalette, ConfigChange, FrontendMessage as Message, Modals, PanelAction};
+
+use anyhow::{anyhow, bail};
+use iced::keyboard::{KeyCode, Modifiers};
+use iced::mouse;
+use iced::widget::pane_grid::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Named actions that can be bound in the [`Keymap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Enter,
+    Back,
+    PickMatch,
+    NextStatus,
+    CommandPalette,
+    Quit,
+    Search,
+    ClearSearch,
+    NextSort,
+    Refresh,
+    MarkEpisode,
+    MarkPreviousEpisodes,
+}
+
+/// Sections of the help, with the [`Actions`][Action] listed in each one.
+const SECTIONS: [(&str, &[Action]); 3] = [
+    (
+        "General",
+        &[
+            Action::Up,
+            Action::Down,
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+            Action::Enter,
+            Action::Back,
+            Action::PickMatch,
+            Action::NextStatus,
+            Action::CommandPalette,
+            Action::Quit,
+        ],
+    ),
+    (
+        "Titles",
+        &[Action::Search, Action::ClearSearch, Action::NextSort],
+    ),
+    (
+        "Episodes",
+        &[
+            Action::Refresh,
+            Action::MarkEpisode,
+            Action::MarkPreviousEpisodes,
+        ],
+    ),
+];
+
+impl Action {
+    /// Every [`Action`] in the order of the help.
+    pub fn all() -> impl Iterator<Item = Action> {
+        SECTIONS
+            .into_iter()
+            .flat_map(|(_, actions)| actions.iter().copied())
+    }
+
+    /// Name used in the keymap file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Enter => "enter",
+            Action::Back => "back",
+            Action::PickMatch => "pick_match",
+            Action::NextStatus => "next_status",
+            Action::CommandPalette => "command_palette",
+            Action::Quit => "quit",
+            Action::Search => "search",
+            Action::ClearSearch => "clear_search",
+            Action::NextSort => "next_sort",
+            Action::Refresh => "refresh",
+            Action::MarkEpisode => "mark_episode",
+            Action::MarkPreviousEpisodes => "mark_previous_episodes",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Up => "Move one item up",
+            Action::Down => "Move one item down",
+            Action::PageUp => "Move 5 items up",
+            Action::PageDown => "Move 5 items down",
+            Action::First => "Go to the first item",
+            Action::Last => "Go to the last item",
+            Action::Enter => {
+                "Enter to Title/Watch Episode, or play the ones to continue at the top"
+            }
+            Action::Back => "Go back to Titles",
+            Action::PickMatch => "Pick the AniList match of the Title",
+            Action::NextStatus => {
+                "Change the status of the Title: Watching, Completed, On Hold, Dropped, Plan to Watch or automatic"
+            }
+            Action::CommandPalette => "Open the command palette, to search and run any action",
+            Action::Quit => "Exit yama",
+            Action::Search => "Search titles by name or genre",
+            Action::ClearSearch => "Clear the search",
+            Action::NextSort => "Change the order of the Titles",
+            Action::Refresh => "Refresh Title episodes list",
+            Action::MarkEpisode => "Mark selected episode as watched/unwatched",
+            Action::MarkPreviousEpisodes => {
+                "Mark previous episodes to the selected as watched/unwatched"
+            }
+        }
+    }
+
+    /// Returns the [`Message`] sent by this [`Action`].
+    pub fn message(&self) -> Message {
+        let action = match self {
+            Action::Up => PanelAction::FocusItem(Direction::Up),
+            Action::Down => PanelAction::FocusItem(Direction::Down),
+            Action::PageUp => PanelAction::Plus(-5),
+            Action::PageDown => PanelAction::Plus(5),
+            Action::First => PanelAction::Start,
+            Action::Last => PanelAction::End,
+            Action::Enter => PanelAction::Enter,
+            Action::Back => PanelAction::Back,
+            Action::PickMatch => PanelAction::PickMatch,
+            Action::NextStatus => PanelAction::NextStatus,
+            Action::Search => PanelAction::Search,
+            Action::ClearSearch => PanelAction::ClearSearch,
+            Action::Refresh => PanelAction::Refresh,
+            Action::MarkEpisode => PanelAction::MarkEpisode,
+            Action::MarkPreviousEpisodes => PanelAction::MarkPreviousEpisodes,
+            Action::CommandPalette => {
+                return Message::MenuBar(Modals::Palette(CommandPalette::default()))
+            }
+            Action::Quit => return Message::CleanUp,
+            Action::NextSort => return Message::UpdateConfig(ConfigChange::NextTitleSort),
+        };
+
+        Message::PaneAction(action)
+    }
+
+    fn parse(name: &str) -> Option<Action> {
+        Action::all().find(|action| action.name() == name)
+    }
+}
+
+/// An input that can be bound to an [`Action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Key(KeyCode),
+    Mouse(mouse::Button),
+    /// Args: (Scrolled up)
+    Wheel(bool),
+}
+
+/// An [`Input`] with the modifiers held, like _shift+w_.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub modifiers: Modifiers,
+    pub input: Input,
+}
+
+/// Keys that can be bound, named like their [`KeyCode`].
+const KEYS: [KeyCode; 72] = {
+    use KeyCode::*;
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Key0, Key1,
+        Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10,
+        F11, F12, Escape, Insert, Home, Delete, End, PageDown, PageUp, Left, Up, Right, Down,
+        Backspace, Enter, Space, Tab, Apostrophe, Backslash, Comma, Equals, Grave, Minus, Period,
+        Semicolon, Slash,
+    ]
+};
+
+/// Mouse inputs that can be bound, by their name.
+const MOUSE: [(&str, Input); 7] = [
+    ("mouse_left", Input::Mouse(mouse::Button::Left)),
+    ("mouse_right", Input::Mouse(mouse::Button::Right)),
+    ("mouse_middle", Input::Mouse(mouse::Button::Middle)),
+    ("mouse_back", Input::Mouse(mouse::Button::Other(8))),
+    ("mouse_forward", Input::Mouse(mouse::Button::Other(9))),
+    ("wheel_up", Input::Wheel(true)),
+    ("wheel_down", Input::Wheel(false)),
+];
+
+impl Chord {
+    pub fn new(modifiers: Modifiers, input: Input) -> Self {
+        Self { modifiers, input }
+    }
+
+    /// Parses a chord like _k_, _page_down_, _shift+w_, _ctrl+alt+p_ or _mouse_back_.
+    pub fn parse(chord: &str) -> crate::Result<Chord> {
+        let mut modifiers = Modifiers::empty();
+        let mut parts: Vec<&str> = chord.split('+').map(str::trim).collect();
+        let input = parts.pop().unwrap_or_default().to_lowercase();
+
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "shift" => Modifiers::SHIFT,
+                "ctrl" | "control" => Modifiers::CTRL,
+                "alt" => Modifiers::ALT,
+                "logo" | "super" | "cmd" => Modifiers::LOGO,
+                _ => bail!("Unknown modifier `{modifier}` in `{chord}`"),
+            };
+        }
+
+        if let Some((_, input)) = MOUSE.iter().find(|(name, _)| *name == input) {
+            return Ok(Chord::new(modifiers, *input));
+        }
+
+        let name = match input.as_str() {
+            "esc" => String::from("escape"),
+            digit if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) => {
+                format!("key{digit}")
+            }
+            name => name.replace('_', ""),
+        };
+
+        KEYS.into_iter()
+            .find(|key| format!("{key:?}").to_lowercase() == name)
+            .map(|key| Chord::new(modifiers, Input::Key(key)))
+            .ok_or_else(|| anyhow!("Unknown key `{input}` in `{chord}`"))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (Modifiers::CTRL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::LOGO, "Super"),
+            (Modifiers::SHIFT, "Shift"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name} + ")?;
+            }
+        }
+
+        match self.input {
+            Input::Key(KeyCode::Up) => f.write_str("UpArrow"),
+            Input::Key(KeyCode::Down) => f.write_str("DownArrow"),
+            Input::Key(KeyCode::Left) => f.write_str("LeftArrow"),
+            Input::Key(KeyCode::Right) => f.write_str("RightArrow"),
+            Input::Key(KeyCode::Slash) => f.write_str("/"),
+            Input::Key(key) => write!(f, "{}", format!("{key:?}").trim_start_matches("Key")),
+            Input::Mouse(mouse::Button::Left) => f.write_str("Left MB"),
+            Input::Mouse(mouse::Button::Right) => f.write_str("Right MB"),
+            Input::Mouse(mouse::Button::Middle) => f.write_str("Middle MB"),
+            Input::Mouse(mouse::Button::Other(button)) => write!(f, "{}th MB", button - 4),
+            Input::Wheel(true) => f.write_str("Wheel Up"),
+            Input::Wheel(false) => f.write_str("Wheel Down"),
+        }
+    }
+}
+
+/// The keymap file, with the chords bound to each [`Action`] by its name.
+///
+/// The actions missing in the file keep their default chords.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeymapFile {
+    pub keys: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for KeymapFile {
+    fn default() -> Self {
+        let keys = DEFAULT_KEYMAP
+            .iter()
+            .map(|(action, chords)| {
+                let chords = chords.iter().map(|chord| chord.to_string()).collect();
+                (action.name().to_string(), chords)
+            })
+            .collect();
+
+        Self { keys }
+    }
+}
+
+const DEFAULT_KEYMAP: [(Action, &[&str]); 18] = [
+    (Action::Up, &["k", "up", "wheel_up"]),
+    (Action::Down, &["j", "down", "wheel_down"]),
+    (Action::PageUp, &["page_up"]),
+    (Action::PageDown, &["page_down"]),
+    (Action::First, &["home"]),
+    (Action::Last, &["end"]),
+    (Action::Enter, &["l", "enter", "right", "mouse_forward"]),
+    (Action::Back, &["h", "left", "mouse_right", "mouse_back"]),
+    (Action::PickMatch, &["m"]),
+    (Action::NextStatus, &["t"]),
+    (Action::CommandPalette, &["ctrl+p"]),
+    (Action::Quit, &["q"]),
+    (Action::Search, &["slash"]),
+    (Action::ClearSearch, &["escape"]),
+    (Action::NextSort, &["s"]),
+    (Action::Refresh, &["r"]),
+    (Action::MarkEpisode, &["w", "space"]),
+    (Action::MarkPreviousEpisodes, &["shift+w"]),
+];
+
+/// The [`Chords`][Chord] bound to each [`Action`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Chord, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeymapFile::default()).expect("The default keymap is valid")
+    }
+}
+
+impl Keymap {
+    /// Creates a new [`Keymap`] from a [`KeymapFile`], over the default one.
+    ///
+    /// Returns an error if there's an unknown action or chord, or a chord bound to two actions.
+    pub fn new(file: &KeymapFile) -> crate::Result<Keymap> {
+        let mut keys: BTreeMap<Action, &[String]> = BTreeMap::new();
+        let defaults = KeymapFile::default();
+
+        for (name, chords) in defaults.keys.iter().chain(file.keys.iter()) {
+            let action = Action::parse(name).ok_or_else(|| anyhow!("Unknown action `{name}`"))?;
+            keys.insert(action, chords);
+        }
+
+        let mut bindings: Vec<(Chord, Action)> = Vec::new();
+
+        for action in Action::all() {
+            for chord in keys.get(&action).copied().unwrap_or_default() {
+                let chord = Chord::parse(chord)?;
+
+                if let Some((_, other)) = bindings.iter().find(|(c, _)| *c == chord) {
+                    bail!(
+                        "`{chord}` is bound to both `{}` and `{}`",
+                        other.name(),
+                        action.name()
+                    );
+                }
+
+                bindings.push((chord, action));
+            }
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// Loads the [`Keymap`] from the keymap file at _path_,
+    /// the file is created with the default keymap if it doesn't exist.
+    pub fn load(path: &Path) -> crate::Result<Keymap> {
+        let file: KeymapFile = confy::load_path(path)?;
+        Self::new(&file)
+    }
+
+    /// Returns the [`Action`] bound to the _chord_.
+    pub fn action(&self, chord: Chord) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(c, _)| *c == chord)
+            .map(|(_, action)| *action)
+    }
+
+    /// Returns the [`Chords`][Chord] bound to the _action_.
+    pub fn chords(&self, action: Action) -> impl Iterator<Item = Chord> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, a)| *a == action)
+            .map(|(chord, _)| *chord)
+    }
+
+    /// Returns the help with every bound [`Action`], by sections.
+    pub fn help(&self) -> String {
+        let mut help = String::new();
+
+        for (section, actions) in SECTIONS {
+            help.push_str(&format!("\n-- {section}:\n"));
+
+            for &action in actions {
+                let chords: Vec<String> = self.chords(action).map(|c| c.to_string()).collect();
+
+                if !chords.is_empty() {
+                    help.push_str(&format!(
+                        "{} -> {}\n",
+                        chords.join(" / "),
+                        action.description()
+                    ));
+                }
+            }
+        }
+
+        help
+    }
+}
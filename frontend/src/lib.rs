@@ -2,6 +2,8 @@ mod config;
 pub mod embedded;
 mod frontend;
 pub mod keybindings;
+pub mod palette;
+mod setup;
 mod widgets;
 
 pub type Result<T> = anyhow::Result<T>;
@@ -0,0 +1,82 @@
+use crate::keybindings::{self, Action};
+
+use backend::HistoryFormat;
+use bridge::search::fuzzy_score;
+use bridge::{ConfigChange, FrontendMessage as Message, Modals};
+
+/// A named action of the command palette.
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub name: &'static str,
+    /// Keys bound to the command in the keymap, like _Shift + W_, empty if it has none.
+    pub keys: String,
+    pub message: Message,
+}
+
+impl Command {
+    fn new(name: &'static str, message: Message) -> Self {
+        Self {
+            name,
+            keys: String::new(),
+            message,
+        }
+    }
+}
+
+/// Returns every [`Command`] of the palette, the ones of the keymap first.
+pub fn commands() -> Vec<Command> {
+    let keymap = keybindings::keymap();
+
+    let bound = Action::all()
+        .filter(|action| *action != Action::CommandPalette)
+        .map(|action| {
+            let keys: Vec<String> = keymap.chords(action).map(|c| c.to_string()).collect();
+
+            Command {
+                name: action.description(),
+                keys: keys.join(" / "),
+                message: action.message(),
+            }
+        });
+
+    let unbound = [
+        Command::new("Open the config", Message::MenuBar(Modals::Config)),
+        Command::new(
+            "Add a library",
+            Message::UpdateConfig(ConfigChange::AddLibrary),
+        ),
+        Command::new(
+            "Change the theme",
+            Message::UpdateConfig(ConfigChange::ThemePath),
+        ),
+        Command::new("Rescan the libraries", Message::Restart),
+        Command::new("Show the watch history", Message::ShowHistory),
+        Command::new(
+            "Export the watch history as JSON",
+            Message::ExportHistory(HistoryFormat::Json),
+        ),
+        Command::new(
+            "Export the watch history as CSV",
+            Message::ExportHistory(HistoryFormat::Csv),
+        ),
+        Command::new("Show the stats", Message::ShowStats),
+        Command::new("Run the diagnostics", Message::ShowDiagnostics),
+        Command::new("Show the keybindings", Message::MenuBar(Modals::Help)),
+        Command::new("About yama", Message::MenuBar(Modals::About)),
+    ];
+
+    bound.chain(unbound).collect()
+}
+
+/// Returns the [`Commands`][Command] that match the _query_, ignoring its spaces, the best matches first.
+pub fn search(query: &str) -> Vec<Command> {
+    let query: String = query.split_whitespace().collect();
+    let mut found: Vec<(i64, Command)> = commands()
+        .into_iter()
+        .filter_map(|command| fuzzy_score(&query, command.name).map(|score| (score, command)))
+        .collect();
+
+    // Stable, so the ties keep the order of the registry.
+    found.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    found.into_iter().map(|(_, command)| command).collect()
+}
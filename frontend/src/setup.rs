@@ -0,0 +1,372 @@
+use crate::config::capitalize;
+use crate::widgets::theme::{self, widget::Element, Theme};
+
+use backend::{Backend, Config, Dependency};
+use bridge::{FrontendMessage, SetupAction};
+
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text, Column};
+use iced::{alignment, Length};
+use iced_aw::NumberInput;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+/// Steps of the [`Setup`], in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupStep {
+    Libraries,
+    Theme,
+    MinTime,
+    Dependencies,
+}
+
+impl SetupStep {
+    const ALL: [SetupStep; 4] = [
+        SetupStep::Libraries,
+        SetupStep::Theme,
+        SetupStep::MinTime,
+        SetupStep::Dependencies,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&step| step == self).unwrap_or(0)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SetupStep::Libraries => "Libraries",
+            SetupStep::Theme => "Theme",
+            SetupStep::MinTime => "Watched episodes",
+            SetupStep::Dependencies => "Dependencies",
+        }
+    }
+}
+
+/// First launch setup, the [`Config`] is only saved once it's finished.
+#[derive(Debug)]
+pub struct Setup {
+    step: SetupStep,
+    cfg: Config,
+    /// Theme files of the config folder.
+    themes: Vec<PathBuf>,
+    /// The chosen theme, to preview it.
+    theme: Theme,
+    dependencies: Vec<Dependency>,
+    error: Option<String>,
+}
+
+impl Setup {
+    pub fn new(cfg: Config) -> Self {
+        let mut themes: Vec<PathBuf> = cfg
+            .theme_path
+            .parent()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        themes.sort();
+
+        Self {
+            step: SetupStep::Libraries,
+            theme: Theme::from_path(&cfg.theme_path).unwrap_or_default(),
+            dependencies: Dependency::all(&cfg.player),
+            cfg,
+            themes,
+            error: None,
+        }
+    }
+
+    /// The chosen [`Theme`].
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Updates the [`Setup`], once it's finished the [`Config`] is saved and returned.
+    pub fn update(&mut self, action: SetupAction) -> Option<Config> {
+        self.error = None;
+
+        match action {
+            SetupAction::NextStep => {
+                if let Some(&step) = SetupStep::ALL.get(self.step.index() + 1) {
+                    self.step = step;
+                }
+            }
+            SetupAction::PreviousStep => {
+                if let Some(idx) = self.step.index().checked_sub(1) {
+                    self.step = SetupStep::ALL[idx];
+                }
+            }
+            SetupAction::AddLibrary => {
+                let res = rfd::FileDialog::new().pick_folder();
+                info!("The user choose: {:#?}", res);
+
+                if let Some(path) = res {
+                    // Without titles yama would start with nothing to show.
+                    if !has_titles(&path) {
+                        self.error = Some(format!(
+                            "No series found in {}, each series should be a folder inside it",
+                            path.display()
+                        ));
+                    } else if !self.cfg.add_library(path) {
+                        self.error = Some(String::from("The library was already added"));
+                    }
+                }
+            }
+            SetupAction::RemoveLibrary(index) => self.cfg.remove_library(index),
+            SetupAction::Theme(path) => self.set_theme(path),
+            SetupAction::BrowseTheme => {
+                let mut dialog = rfd::FileDialog::new().add_filter("json", &["json"]);
+                if let Some(dir) = self.cfg.theme_path.parent() {
+                    dialog = dialog.set_directory(dir);
+                }
+                let res = dialog.pick_file();
+                info!("The user choose: {:#?}", res);
+
+                if let Some(path) = res {
+                    self.set_theme(path);
+                }
+            }
+            SetupAction::MinTime(min_time) => self.cfg.min_time = min_time,
+            SetupAction::CheckDependencies => {
+                self.dependencies = Dependency::all(&self.cfg.player);
+            }
+            // The folders may have changed since they were added.
+            SetupAction::Finish if !self.has_titles() => {
+                self.step = SetupStep::Libraries;
+                self.error = Some(String::from(
+                    "No series found in the libraries, add a folder with series inside",
+                ));
+            }
+            SetupAction::Finish => match confy::store("yama", "config", &self.cfg) {
+                Ok(()) => return Some(std::mem::take(&mut self.cfg)),
+                Err(error) => {
+                    warn!("Could not save config because: {:#?}", error);
+                    self.error = Some(format!("Could not save the config: {error}"));
+                }
+            },
+        }
+
+        None
+    }
+
+    /// Checks that at least one of the libraries has titles.
+    fn has_titles(&self) -> bool {
+        self.cfg
+            .libraries()
+            .iter()
+            .any(|library| library.enabled && has_titles(&library.path))
+    }
+
+    fn set_theme(&mut self, path: PathBuf) {
+        match Theme::from_path(&path) {
+            Ok(theme) => {
+                self.theme = theme;
+                self.cfg.theme_path = path;
+            }
+            Err(error) => {
+                warn!("Invalid theme {}: {error}", path.display());
+                self.error = Some(String::from("The file is not a valid theme"));
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, FrontendMessage> {
+        let body = match self.step {
+            SetupStep::Libraries => self.libraries(),
+            SetupStep::Theme => self.themes(),
+            SetupStep::MinTime => self.min_time(),
+            SetupStep::Dependencies => self.dependencies(),
+        };
+
+        let back = button(text("Back"))
+            .on_press_maybe(
+                (self.step != SetupStep::Libraries)
+                    .then_some(FrontendMessage::Setup(SetupAction::PreviousStep)),
+            )
+            .style(theme::Button::Menu);
+
+        let next = match self.step {
+            SetupStep::Dependencies => button(text("Finish"))
+                .on_press(FrontendMessage::Setup(SetupAction::Finish))
+                .style(theme::Button::Menu),
+            // Without a library there's nothing to show.
+            _ => button(text("Next"))
+                .on_press_maybe(
+                    (!self.cfg.libraries().is_empty())
+                        .then_some(FrontendMessage::Setup(SetupAction::NextStep)),
+                )
+                .style(theme::Button::Menu),
+        };
+
+        let error = text(self.error.as_deref().unwrap_or_default()).style(theme::Text::Focused);
+
+        container(
+            column![
+                text("Welcome to yama!")
+                    .style(theme::Text::Focused)
+                    .size(28)
+                    .horizontal_alignment(alignment::Horizontal::Center)
+                    .width(Length::Fill),
+                text(format!(
+                    "Step {} of {}: {}",
+                    self.step.index() + 1,
+                    SetupStep::ALL.len(),
+                    self.step.name()
+                ))
+                .horizontal_alignment(alignment::Horizontal::Center)
+                .width(Length::Fill),
+                container(body).height(Length::Fill),
+                error,
+                row![back, horizontal_space(Length::Fill), next],
+            ]
+            .spacing(25)
+            .padding(15),
+        )
+        .width(Length::Fixed(700.0))
+        .height(Length::Fixed(550.0))
+        .style(theme::Container::Box)
+        .padding(15)
+        .into()
+    }
+
+    fn libraries(&self) -> Element<'_, FrontendMessage> {
+        let libraries = Column::with_children(
+            self.cfg
+                .libraries()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, library)| {
+                    row![
+                        text(library.path.display().to_string()).width(Length::Fill),
+                        button(text("Remove"))
+                            .on_press(FrontendMessage::Setup(SetupAction::RemoveLibrary(idx)))
+                            .style(theme::Button::Input),
+                    ]
+                    .align_items(alignment::Alignment::Center)
+                    .spacing(15)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(10);
+
+        column![
+            text("Choose the folders with your series, each folder inside them is a title."),
+            scrollable(libraries).height(Length::Fill),
+            button(
+                text("Add library")
+                    .horizontal_alignment(alignment::Horizontal::Center)
+                    .width(Length::Fill)
+            )
+            .on_press(FrontendMessage::Setup(SetupAction::AddLibrary))
+            .style(theme::Button::Input)
+            .width(Length::Fill),
+        ]
+        .spacing(15)
+        .into()
+    }
+
+    fn themes(&self) -> Element<'_, FrontendMessage> {
+        let themes = Column::with_children(
+            self.themes
+                .iter()
+                .map(|path| {
+                    let name = path
+                        .file_stem()
+                        .map(|name| capitalize(&name.to_string_lossy()))
+                        .unwrap_or_default();
+
+                    button(text(name))
+                        .on_press(FrontendMessage::Setup(SetupAction::Theme(path.clone())))
+                        .style(match *path == self.cfg.theme_path {
+                            true => theme::Button::Focused,
+                            false => theme::Button::Menu,
+                        })
+                        .width(Length::Fill)
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        column![
+            text("Pick a theme, it can be changed later in the config."),
+            scrollable(themes).height(Length::Fill),
+            button(
+                text("Other theme file...")
+                    .horizontal_alignment(alignment::Horizontal::Center)
+                    .width(Length::Fill)
+            )
+            .on_press(FrontendMessage::Setup(SetupAction::BrowseTheme))
+            .style(theme::Button::Input)
+            .width(Length::Fill),
+        ]
+        .spacing(15)
+        .into()
+    }
+
+    fn min_time(&self) -> Element<'_, FrontendMessage> {
+        column![
+            text(
+                "An episode is marked as watched when you close it with less than \
+                these seconds remaining, so the ending credits can be skipped."
+            ),
+            row![
+                text("Min Time: ")
+                    .width(Length::Fixed(100.0))
+                    .horizontal_alignment(alignment::Horizontal::Right),
+                NumberInput::new(self.cfg.min_time, f32::MAX, |min_time| {
+                    FrontendMessage::Setup(SetupAction::MinTime(min_time))
+                })
+            ]
+            .align_items(alignment::Alignment::Center)
+            .spacing(15),
+        ]
+        .spacing(15)
+        .into()
+    }
+
+    fn dependencies(&self) -> Element<'_, FrontendMessage> {
+        let dependencies = Column::with_children(
+            self.dependencies
+                .iter()
+                .map(|dependency| {
+                    let found = match &dependency.path {
+                        Some(path) => text(format!("Found at {}", path.display())),
+                        None => text("Not found, install it and check again")
+                            .style(theme::Text::Focused),
+                    };
+
+                    column![
+                        text(format!("{}: {}", dependency.program, dependency.used_for)),
+                        found,
+                    ]
+                    .spacing(5)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(15);
+
+        column![
+            text("yama uses these programs, they have to be installed and in the PATH."),
+            scrollable(dependencies).height(Length::Fill),
+            button(
+                text("Check again")
+                    .horizontal_alignment(alignment::Horizontal::Center)
+                    .width(Length::Fill)
+            )
+            .on_press(FrontendMessage::Setup(SetupAction::CheckDependencies))
+            .style(theme::Button::Input)
+            .width(Length::Fill),
+        ]
+        .spacing(15)
+        .into()
+    }
+}
+
+fn has_titles(path: &PathBuf) -> bool {
+    Backend::title_folders(path).is_ok_and(|titles| !titles.is_empty())
+}
@@ -1,3 +1,4 @@
+mod calendar;
 mod loading;
 pub mod menus;
 mod modal;
@@ -5,8 +6,15 @@ mod mouse_area;
 mod panels;
 pub mod theme;
 
+pub use calendar::ActivityCalendar;
 pub use loading::LoadingCircle;
 pub use modal::Modal;
 pub use mouse_area::mouse_area;
 pub use panels::Panels;
 pub use theme::widget::Element;
+
+/// Formats seconds like _MM:SS_.
+pub fn format_time(seconds: f64) -> String {
+    let seconds = seconds as u64;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
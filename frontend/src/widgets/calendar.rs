@@ -0,0 +1,62 @@
+use crate::widgets::theme::Theme;
+
+use iced::mouse::Cursor;
+use iced::widget::canvas::{self, Path};
+use iced::{Color, Point, Rectangle, Renderer, Size};
+
+/// Space between the days of the [`ActivityCalendar`].
+const GAP: f32 = 2.0;
+
+/// A GitHub-like calendar, with a column for each week and a square for each day,
+/// colored by how much was watched that day.
+#[derive(Debug)]
+pub struct ActivityCalendar<'a> {
+    /// Seconds watched each day, from a Monday to today.
+    days: &'a [f64],
+}
+
+impl<'a> ActivityCalendar<'a> {
+    pub fn new(days: &'a [f64]) -> Self {
+        Self { days }
+    }
+}
+
+impl<Message> canvas::Program<Message, Renderer<Theme>> for ActivityCalendar<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer<Theme>,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+
+        let weeks = self.days.len().div_ceil(7).max(1);
+        let side = ((frame.width() + GAP) / weeks as f32).min((frame.height() + GAP) / 7.0) - GAP;
+        let most = self.days.iter().copied().fold(0.0, f64::max);
+
+        for (idx, &watched) in self.days.iter().enumerate() {
+            let (week, weekday) = (idx / 7, idx % 7);
+            let square = Path::rectangle(
+                Point::new(week as f32 * (side + GAP), weekday as f32 * (side + GAP)),
+                Size::new(side, side),
+            );
+
+            let color = match watched > 0.0 {
+                // Four levels, like GitHub.
+                true => Color {
+                    a: 0.25 * (1.0 + (3.0 * watched / most).floor() as f32).min(4.0),
+                    ..theme.focus
+                },
+                false => theme.unfocus,
+            };
+
+            frame.fill(&square, color);
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
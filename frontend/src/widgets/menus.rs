@@ -1,13 +1,17 @@
 use crate::{
     config::GUIConfig,
-    widgets::{theme, Element},
+    keybindings, palette,
+    widgets::{format_time, theme, ActivityCalendar, Element},
 };
 
-use backend::Config;
-use bridge::{FrontendMessage as Message, Modals};
+use backend::{CheckStatus, Config, Diagnostics, HistoryEntry, HistoryFormat};
+use bridge::{stats::Stats, CommandPalette, FrontendMessage as Message, MatchPicker, Modals};
 
-use iced::widget::{button, column as col, container, image, scrollable, text};
+use iced::widget::{
+    button, canvas, column as col, container, image, row, scrollable, text, text_input, Column,
+};
 use iced::{alignment, Length};
+use once_cell::sync::Lazy;
 use std::sync::Arc;
 
 pub fn about<'a>() -> Element<'a, Message> {
@@ -46,7 +50,7 @@ pub fn about<'a>() -> Element<'a, Message> {
 pub fn config<'a>(cfg: &Config) -> Element<'a, Message> {
     container(GUIConfig::view(cfg))
         .width(Length::Fixed(600.0))
-        .height(Length::Fixed(400.0))
+        .height(Length::Fixed(450.0))
         .style(theme::Container::Box)
         .padding(15)
         .into()
@@ -94,6 +98,314 @@ pub fn error<'a>(err: Arc<str>) -> Element<'a, Message> {
     .into()
 }
 
+pub fn matches(picker: &MatchPicker) -> Element<'_, Message> {
+    let candidates: Element<Message> = match picker.candidates.is_empty() {
+        true => text("No matches found...").into(),
+        false => Column::with_children(
+            picker
+                .candidates
+                .iter()
+                .enumerate()
+                .map(|(idx, candidate)| {
+                    let cover = match &candidate.cover {
+                        Some(path) => image::Handle::from_path(path.as_ref()),
+                        None => image::Handle::from_memory(crate::embedded::NO_TUMBNAIL),
+                    };
+
+                    button(
+                        row![
+                            image::Image::new(cover)
+                                .width(Length::Fixed(60.0))
+                                .height(Length::Fixed(85.0)),
+                            col![
+                                text(candidate.title.clone()),
+                                text(candidate.details.clone()).style(theme::Text::Watched),
+                            ]
+                            .spacing(5),
+                        ]
+                        .align_items(alignment::Alignment::Center)
+                        .spacing(15),
+                    )
+                    .on_press(Message::PinMatch(idx))
+                    .style(theme::Button::Menu)
+                    .width(Length::Fill)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(10)
+        .into(),
+    };
+
+    container(
+        col![
+            text("Choose the match:")
+                .style(theme::Text::Focused)
+                .vertical_alignment(alignment::Vertical::Top)
+                .horizontal_alignment(alignment::Horizontal::Left)
+                .width(Length::Fill),
+            row![
+                text_input("Name to search", &picker.search)
+                    .on_input(Message::MatchSearchChanged)
+                    .on_submit(Message::SearchMatches)
+                    .padding(5),
+                button("Search")
+                    .on_press(Message::SearchMatches)
+                    .style(theme::Button::Input),
+            ]
+            .align_items(alignment::Alignment::Center)
+            .spacing(15),
+            scrollable(candidates).height(Length::Fill),
+        ]
+        .spacing(15)
+        .padding(15),
+    )
+    .width(Length::Fixed(615.0))
+    .height(Length::Fixed(600.0))
+    .style(theme::Container::Box)
+    .padding(15)
+    .into()
+}
+
+pub fn diagnostics(diagnostics: &Diagnostics) -> Element<'_, Message> {
+    let checks = Column::with_children(
+        diagnostics
+            .checks
+            .iter()
+            .map(|check| {
+                let status = text(format!("[{}]", check.status))
+                    .style(match check.status {
+                        CheckStatus::Ok => theme::Text::Watched,
+                        CheckStatus::Warning | CheckStatus::Failed => theme::Text::Focused,
+                    })
+                    .width(Length::Fixed(80.0));
+
+                let mut details = col![
+                    text(&check.name),
+                    text(&check.details).style(theme::Text::Watched),
+                ]
+                .spacing(5);
+
+                if let Some(remedy) = &check.remedy {
+                    details = details.push(text(remedy));
+                }
+
+                row![status, details].spacing(10).into()
+            })
+            .collect(),
+    )
+    .spacing(15);
+
+    container(
+        col![
+            text(match diagnostics.passed() {
+                true => "Diagnostics: everything is ready",
+                false => "Diagnostics: something needs your attention",
+            })
+            .style(theme::Text::Focused)
+            .vertical_alignment(alignment::Vertical::Top)
+            .horizontal_alignment(alignment::Horizontal::Left)
+            .width(Length::Fill),
+            scrollable(checks).height(Length::Fill),
+            button(
+                text("Check again")
+                    .horizontal_alignment(alignment::Horizontal::Center)
+                    .width(Length::Fill)
+            )
+            .on_press(Message::ShowDiagnostics)
+            .style(theme::Button::Input)
+            .width(Length::Fill),
+        ]
+        .spacing(15)
+        .padding(15),
+    )
+    .width(Length::Fixed(700.0))
+    .height(Length::Fixed(600.0))
+    .style(theme::Container::Box)
+    .padding(15)
+    .into()
+}
+
+/// Text input of the command palette, focused when it's opened.
+pub static PALETTE_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+
+pub fn palette(state: &CommandPalette) -> Element<'_, Message> {
+    let found = palette::search(&state.query);
+
+    let commands: Element<Message> = match found.is_empty() {
+        true => text("No commands found...").into(),
+        false => Column::with_children(
+            found
+                .into_iter()
+                .enumerate()
+                .map(|(idx, command)| {
+                    button(
+                        row![
+                            text(command.name).width(Length::Fill),
+                            text(command.keys).style(theme::Text::Watched),
+                        ]
+                        .spacing(15),
+                    )
+                    .on_press(Message::PaletteSubmit(idx))
+                    .style(match idx == state.focused {
+                        true => theme::Button::Focused,
+                        false => theme::Button::Menu,
+                    })
+                    .width(Length::Fill)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5)
+        .into(),
+    };
+
+    container(
+        col![
+            text_input("Type a command", &state.query)
+                .id(PALETTE_ID.clone())
+                .on_input(Message::PaletteChanged)
+                .on_submit(Message::PaletteSubmit(state.focused))
+                .padding(5),
+            scrollable(commands).height(Length::Fill),
+        ]
+        .spacing(15)
+        .padding(15),
+    )
+    .width(Length::Fixed(615.0))
+    .height(Length::Fixed(450.0))
+    .style(theme::Container::Box)
+    .padding(15)
+    .into()
+}
+
+/// Most recent entries listed in the history.
+const HISTORY_SIZE: usize = 200;
+
+pub fn history(entries: &[HistoryEntry]) -> Element<'_, Message> {
+    let list: Element<Message> = match entries.is_empty() {
+        true => text("Nothing watched yet...").into(),
+        false => Column::with_children(
+            entries
+                .iter()
+                .rev()
+                .take(HISTORY_SIZE)
+                .map(|entry| {
+                    let position = format!(
+                        "{} -> {}{}",
+                        format_time(entry.start),
+                        entry.end.map_or(String::from("?"), format_time),
+                        if entry.completed { ", completed" } else { "" }
+                    );
+
+                    col![
+                        text(format!("{} - {}", entry.title, entry.episode)),
+                        text(format!("{}    {position}", entry.date())).style(theme::Text::Watched),
+                    ]
+                    .spacing(5)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(15)
+        .into(),
+    };
+
+    container(
+        col![
+            row![
+                text("Watch history:")
+                    .style(theme::Text::Focused)
+                    .width(Length::Fill),
+                button("Export JSON")
+                    .on_press(Message::ExportHistory(HistoryFormat::Json))
+                    .style(theme::Button::Input),
+                button("Export CSV")
+                    .on_press(Message::ExportHistory(HistoryFormat::Csv))
+                    .style(theme::Button::Input),
+            ]
+            .align_items(alignment::Alignment::Center)
+            .spacing(15),
+            scrollable(list).width(Length::Fill).height(Length::Fill),
+        ]
+        .spacing(15)
+        .padding(15),
+    )
+    .width(Length::Fixed(615.0))
+    .height(Length::Fixed(600.0))
+    .style(theme::Container::Box)
+    .padding(15)
+    .into()
+}
+
+pub fn stats(stats: &Stats) -> Element<'_, Message> {
+    let ranking = |name: &str, ranked: &[(Arc<str>, usize)]| {
+        let ranked: Vec<_> = ranked
+            .iter()
+            .map(|(name, episodes)| format!("{name} ({episodes})"))
+            .collect();
+
+        match ranked.is_empty() {
+            true => format!("{name}: -"),
+            false => format!("{name}: {}", ranked.join(", ")),
+        }
+    };
+
+    let titles = Column::with_children(
+        stats
+            .titles
+            .iter()
+            .map(|(name, watched, episodes)| {
+                row![
+                    text(name.clone()).width(Length::Fill),
+                    text(format!(
+                        "{watched}/{episodes} ({:.0}%)",
+                        100.0 * *watched as f64 / (*episodes).max(1) as f64
+                    ))
+                    .style(theme::Text::Watched),
+                ]
+                .spacing(15)
+                .into()
+            })
+            .collect(),
+    )
+    .spacing(5);
+
+    let weekly: Vec<_> = stats.weekly.iter().map(|count| count.to_string()).collect();
+
+    container(
+        col![
+            text("Stats:")
+                .style(theme::Text::Focused)
+                .width(Length::Fill),
+            text(format!(
+                "Watched: {:.1} hours, {} episodes",
+                stats.watched / 3600.0,
+                stats.episodes
+            )),
+            text(format!(
+                "Episodes per week: {:.1} ({})",
+                stats.per_week(),
+                weekly.join(", ")
+            )),
+            canvas(ActivityCalendar::new(&stats.days))
+                .width(Length::Fill)
+                .height(Length::Fixed(75.0)),
+            text(ranking("Top genres", &stats.genres)),
+            text(ranking("Top studios", &stats.studios)),
+            text("Completion:").style(theme::Text::Focused),
+            scrollable(titles).height(Length::Fill),
+        ]
+        .spacing(15)
+        .padding(15),
+    )
+    .width(Length::Fixed(615.0))
+    .height(Length::Fixed(600.0))
+    .style(theme::Container::Box)
+    .padding(15)
+    .into()
+}
+
 pub fn help<'a>() -> Element<'a, Message> {
     container(
         col![
@@ -102,7 +414,11 @@ pub fn help<'a>() -> Element<'a, Message> {
                 .vertical_alignment(alignment::Vertical::Top)
                 .horizontal_alignment(alignment::Horizontal::Left)
                 .width(Length::Fill),
-            scrollable(text(HELP_KEYBINDINGS)).width(Length::Fill),
+            scrollable(text(format!(
+                "{}{HELP_SEARCH}",
+                keybindings::keymap().help()
+            )))
+            .width(Length::Fill),
         ]
         .align_items(alignment::Alignment::Center)
         .spacing(15)
@@ -117,22 +433,8 @@ pub fn help<'a>() -> Element<'a, Message> {
     .into()
 }
 
-const HELP_KEYBINDINGS: &str = "
--- General:
-K / UpArrow -> Move one item up
-J / DownArror -> Move one item down
-PageUp -> Move 5 items up
-PageDown -> Move 5 items down
-Home -> Go to the first item
-End -> Go to the last item
-5th MB ->
-L / Enter / RightArrow -> Enter to Title/Watch Episode
-Right MB / 4th MB ->
-H / LeftArrow -> Go back to Titles
-Q -> Exit yama
-
--- Episodes:
-R -> Refresh Title episodes list
-W -> Mark selected episode as watched/unwatched
-Shift + W -> Mark previous episodes to the selected as watched/unwatched
+const HELP_SEARCH: &str = "
+-- Search:
+genre:action studio:bones is:unwatched status:on-hold -> Filter the search
+Enter -> Close the search and enter the Title
 ";
@@ -9,40 +9,51 @@ use crate::embedded::*;
 use crate::frontend::State;
 use crate::widgets::{theme, Element};
 
+use backend::{TitleSort, WatchStatus};
 use bridge::{cache::*, FrontendMessage};
 use bridge::{BackendMessage, PanelAction as Message};
 
 use iced::font::Family;
 use iced::futures::channel::mpsc::Sender;
 use iced::widget::pane_grid::{self, Direction, PaneGrid};
-use iced::widget::{button, row, scrollable, svg, text, tooltip};
+use iced::widget::{
+    button, container, horizontal_space, row, scrollable, svg, text, text_input, tooltip,
+};
 use iced::{Command, Font, Length};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
 use std::vec;
 
+static SEARCH_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+
 #[derive(Debug)]
 pub struct Panels {
     panes: pane_grid::State<InnerPanel>,
     focus: pane_grid::Pane,
     sender: Sender<BackendMessage>,
     data: InnerData,
+    /// The title search bar is shown.
+    searching: bool,
 }
 
 impl Panels {
-    pub fn new(cache: Cache, sender: Sender<BackendMessage>) -> Panels {
-        let title_meta = cache.get_title_cache(0);
+    pub fn new(cache: Cache, sender: Sender<BackendMessage>, sort: TitleSort) -> Panels {
+        // The cache may be empty, like after disabling every library, so nothing is indexed here.
+        let data = InnerData::new(cache, sort);
 
-        let (mut panes, focus) = pane_grid::State::new(InnerPanel::Listdata(FocusedType::Title(0)));
+        let (mut panes, focus) = pane_grid::State::new(InnerPanel::Listdata(data.get_type()));
         panes.split(
             pane_grid::Axis::Vertical,
             &focus,
-            InnerPanel::Metadata(title_meta),
+            InnerPanel::Metadata(data.get_metacache()),
         );
 
         Panels {
             panes,
             sender,
             focus,
-            data: InnerData::new(cache),
+            data,
+            searching: false,
         }
     }
 
@@ -59,6 +70,44 @@ impl Panels {
         )
     }
 
+    /// Refreshes the focused list and the metadata shown, after a change in the [`Cache`][Cache].
+    fn refresh_panes(&mut self) {
+        *self.panes.get_mut(&self.focus).unwrap() = InnerPanel::Listdata(self.data.get_type());
+
+        if let Some(adj) = self.panes.adjacent(&self.focus, Direction::Right) {
+            *self.panes.get_mut(&adj).unwrap() = InnerPanel::Metadata(self.data.get_metacache());
+        }
+    }
+
+    /// Replaces the [`Cache`][Cache] after titles were added to or removed from the libraries.
+    pub fn change_titles(&mut self, cache: Cache, moved: &[Option<usize>]) {
+        self.data.change_titles(cache, moved);
+        self.refresh_panes();
+    }
+
+    /// Updates a title whose episodes were added or removed.
+    pub fn update_title(&mut self, title_number: usize, title_cache: TitleCache) {
+        self.data.update_title(title_cache, title_number);
+        self.refresh_panes();
+    }
+
+    /// Returns the [`Cache`][Cache] with the titles listed.
+    pub fn cache(&self) -> &Cache {
+        self.data.cache()
+    }
+
+    /// Lists the titles in the _sort_ order.
+    pub fn set_sort(&mut self, sort: TitleSort) {
+        self.data.set_sort(sort);
+        self.refresh_panes();
+    }
+
+    /// Updates a title whose metadata changed, like after choosing its match.
+    pub fn change_title(&mut self, title_number: usize, name: Arc<str>, title_cache: TitleCache) {
+        self.data.change_title(name, title_cache, title_number);
+        self.refresh_panes();
+    }
+
     pub fn update(&mut self, message: Message, state: &mut State) -> Command<FrontendMessage> {
         match message {
             Message::EpisodesLoaded(title_number, title_cache) => {
@@ -66,7 +115,7 @@ impl Panels {
 
                 self.data.set_title_cache(title_cache, title_number);
                 *self.panes.get_mut(&self.focus).unwrap() =
-                    InnerPanel::Listdata(FocusedType::Episode(0, 0));
+                    InnerPanel::Listdata(self.data.get_type());
 
                 return Command::perform(
                     async { Message::FocusItem(Direction::Left) },
@@ -104,6 +153,8 @@ impl Panels {
             }
 
             Message::Enter => match self.data.get_type() {
+                FocusedType::Title(_) if self.data.is_empty() => (),
+
                 FocusedType::Title(title_number) => {
                     *state = State::Loading;
                     let _ = self
@@ -111,8 +162,22 @@ impl Panels {
                         .try_send(BackendMessage::LoadEpisodes(title_number, false));
                 }
 
+                FocusedType::Season(_, season) => {
+                    self.data.enter_season(season);
+                    *self.panes.get_mut(&self.focus).unwrap() =
+                        InnerPanel::Listdata(self.data.get_type());
+
+                    let y = self.data.plus(0);
+                    return self.next(y);
+                }
+
+                FocusedType::NextUp(..) => {
+                    if let Some(next) = self.data.get_next_up() {
+                        let _ = self.sender.try_send(next.message());
+                    }
+                }
+
                 FocusedType::Episode(title_number, episode_number) => {
-                    *state = State::Watching;
                     let _ = self
                         .sender
                         .try_send(BackendMessage::WatchEpisode(title_number, episode_number));
@@ -128,11 +193,13 @@ impl Panels {
                 }
 
                 *self.panes.get_mut(&self.focus).unwrap() =
-                    InnerPanel::Listdata(FocusedType::Title(self.data.focused));
+                    InnerPanel::Listdata(self.data.get_type());
             }
 
             Message::Refresh => {
-                if let FocusedType::Episode(title_number, _) = self.data.get_type() {
+                if let FocusedType::Season(title_number, _)
+                | FocusedType::Episode(title_number, _) = self.data.get_type()
+                {
                     *state = State::Loading;
                     let _ = self
                         .sender
@@ -141,7 +208,6 @@ impl Panels {
             }
 
             Message::UpdateEpisode(title_number, episodes_cache) => {
-                *state = State::Normal;
                 self.data.set_episodes_cache(title_number, episodes_cache);
 
                 return Command::perform(
@@ -167,6 +233,62 @@ impl Panels {
                 }
             }
 
+            Message::PickMatch if self.data.is_empty() => (),
+
+            Message::PickMatch => {
+                let (FocusedType::Title(title_number)
+                | FocusedType::NextUp(title_number, _)
+                | FocusedType::Season(title_number, _)
+                | FocusedType::Episode(title_number, _)) = self.data.get_type();
+
+                *state = State::Loading;
+                let _ = self
+                    .sender
+                    .try_send(BackendMessage::SearchMatches(title_number, None));
+            }
+
+            Message::NextStatus => {
+                if let FocusedType::Title(title_number) | FocusedType::NextUp(title_number, _) =
+                    self.data.get_type()
+                {
+                    if !self.data.is_empty() {
+                        let status = self.data.cache().get_title(title_number).status;
+                        let _ = self.sender.try_send(BackendMessage::SetStatus(
+                            title_number,
+                            WatchStatus::next(status),
+                        ));
+                    }
+                }
+            }
+
+            Message::Search => {
+                self.searching = true;
+                self.data.show_titles();
+                self.refresh_panes();
+
+                return text_input::focus(SEARCH_ID.clone());
+            }
+
+            Message::SearchChanged(query) => {
+                self.data.set_query(query);
+                self.refresh_panes();
+
+                let y = self.data.plus(0);
+                return self.next(y);
+            }
+
+            Message::SearchSubmit => {
+                self.searching = false;
+
+                return Command::perform(async { Message::Enter }, FrontendMessage::PaneAction);
+            }
+
+            Message::ClearSearch => {
+                self.searching = false;
+                self.data.set_query(String::new());
+                self.refresh_panes();
+            }
+
             Message::Resized(pane_grid::ResizeEvent { split, ratio }) => {
                 self.panes.resize(&split, ratio);
             }
@@ -175,7 +297,7 @@ impl Panels {
         Command::none()
     }
 
-    pub fn view(&self) -> Element<FrontendMessage> {
+    pub fn view(&self) -> Element<'_, FrontendMessage> {
         let focus = self.focus;
 
         PaneGrid::new(&self.panes, |id, pane, _| {
@@ -189,17 +311,39 @@ impl Panels {
 
             if let InnerPanel::Listdata(focused_type) = pane {
                 match focused_type {
-                    FocusedType::Title(_) => {
-                        let title_bar = pane_grid::TitleBar::new(
-                            row![
+                    FocusedType::Title(_) | FocusedType::NextUp(_, _) => {
+                        let header: Element<'_, FrontendMessage> = match self.searching {
+                            true => text_input(
+                                "Search titles, genre:action studio:bones is:unwatched status:watching",
+                                &self.data.query,
+                            )
+                            .id(SEARCH_ID.clone())
+                            .on_input(|query| {
+                                FrontendMessage::PaneAction(Message::SearchChanged(query))
+                            })
+                            .on_submit(FrontendMessage::PaneAction(Message::SearchSubmit))
+                            .padding(5)
+                            .into(),
+                            false => row![
                                 text("Titles")
                                     .font(Font {
                                         family: Family::Name("Kumbh Sans"),
                                         weight: iced::font::Weight::Semibold,
                                         ..Default::default()
                                     })
-                                    .size(26)
-                                    .width(Length::Fill),
+                                    .size(26),
+                                text(&self.data.query).style(theme::Text::Watched),
+                                horizontal_space(Length::Fill),
+                                text(format!("by {}", self.data.sort)).style(theme::Text::Watched),
+                            ]
+                            .align_items(iced::Alignment::Center)
+                            .spacing(15)
+                            .into(),
+                        };
+
+                        let title_bar = pane_grid::TitleBar::new(
+                            row![
+                                container(header).width(Length::Fill),
                                 button(
                                     svg(svg::Handle::from_memory(EMPTY_SVG))
                                         .width(Length::Fixed(25.0))
@@ -213,6 +357,38 @@ impl Panels {
                         content.title_bar(title_bar)
                     }
 
+                    FocusedType::Season(_, _) => {
+                        let reload_svg = svg(svg::Handle::from_memory(RELOAD_SVG))
+                            .width(Length::Fixed(25.0))
+                            .height(Length::Fixed(25.0));
+
+                        let title_bar = pane_grid::TitleBar::new(
+                            row![
+                                text("Seasons")
+                                    .font(Font {
+                                        family: Family::Name("Kumbh Sans"),
+                                        weight: iced::font::Weight::Semibold,
+                                        ..Default::default()
+                                    })
+                                    .size(26)
+                                    .width(Length::Fill),
+                                tooltip(
+                                    button(reload_svg)
+                                        .on_press(FrontendMessage::PaneAction(Message::Refresh))
+                                        .style(theme::Button::Menu),
+                                    "Refresh current title",
+                                    tooltip::Position::Top,
+                                )
+                                .style(theme::Container::Tooltip),
+                            ]
+                            .align_items(iced::Alignment::Center)
+                            .width(Length::Fill),
+                        )
+                        .padding(15);
+
+                        content.title_bar(title_bar)
+                    }
+
                     FocusedType::Episode(_, _) => {
                         let svg_size = 25.0;
 
@@ -1,13 +1,17 @@
 use super::pointer::{FocusedElement, Pointer};
 
-use crate::widgets::{theme, Element};
+use crate::widgets::{format_time, theme, Element};
 
+use backend::{TitleSort, WatchStatus};
 use bridge::{
     cache::{Cache, EpisodeCache, MetaCache, TitleCache},
+    next_up::NextUpCache,
+    search::TitleFilter,
     FrontendMessage,
 };
 
 use iced::widget::pane_grid::Direction;
+use std::ops::Range;
 use std::sync::Arc;
 
 /// The [`FocusedElement`] type.
@@ -15,6 +19,11 @@ use std::sync::Arc;
 pub enum FocusedType {
     /// Args: (Title Index)
     Title(usize),
+    /// An episode of the Continue Watching list, at the top of the title list.
+    /// Args: (Title Index, Episode Number)
+    NextUp(usize, usize),
+    /// Args: (Title Index, Season Index)
+    Season(usize, usize),
     /// Args: (Title Index, Episode Number)
     Episode(usize, usize),
 }
@@ -25,23 +34,68 @@ pub struct InnerData {
     pub focused: FocusedElement,
     /// First element is root.
     pointers: Vec<(Pointer, FocusedType)>,
+    /// Season list of each title, only used by titles with more than one season.
+    seasons: Vec<Pointer>,
+    /// Season whose episodes are listed, if the focused title has seasons.
+    season: Option<usize>,
+    showing_seasons: bool,
+    /// Episodes to continue watching, listed before the titles while not searching.
+    next_up: Vec<NextUpCache>,
+    /// Indexes of the titles listed, the ones that match the search [`query`][InnerData::query].
+    titles: Vec<usize>,
+    pub query: String,
+    filter: TitleFilter,
+    pub sort: TitleSort,
     data: Cache,
 }
 
 impl InnerData {
-    /// Creates a new [`InnerData`] with the passed [`Cache`].
-    pub fn new(data: Cache) -> Self {
+    /// Creates a new [`InnerData`] with the passed [`Cache`], its titles listed in the _sort_ order.
+    pub fn new(data: Cache, sort: TitleSort) -> Self {
+        let next_up = data.next_up();
+
         let mut pointers = Vec::with_capacity(data.size + 1);
-        pointers.push((Pointer::new(data.size), FocusedType::Title(0)));
+        pointers.push((
+            Pointer::new(next_up.len() + data.size),
+            FocusedType::Title(0),
+        ));
 
         for _ in 0..data.size {
             pointers.push((Pointer::new(0), FocusedType::Episode(0, 0)))
         }
 
         Self {
+            seasons: vec![Pointer::new(0); data.size],
+            next_up,
+            titles: data.sorted(sort),
+            sort,
+            query: String::new(),
+            filter: TitleFilter::default(),
             pointers,
             data,
             focused: 0,
+            season: None,
+            showing_seasons: false,
+        }
+    }
+
+    /// Returns the [`Cache`] with the titles listed.
+    pub fn cache(&self) -> &Cache {
+        &self.data
+    }
+
+    /// Returns the [`Pointer`] of the focused list.
+    fn pointer(&self) -> &Pointer {
+        match self.showing_seasons {
+            true => &self.seasons[self.focused - 1],
+            false => &self.pointers[self.focused].0,
+        }
+    }
+
+    fn pointer_mut(&mut self) -> &mut Pointer {
+        match self.showing_seasons {
+            true => &mut self.seasons[self.focused - 1],
+            false => &mut self.pointers[self.focused].0,
         }
     }
 
@@ -50,35 +104,212 @@ impl InnerData {
     ///
     /// Returns a vertical offset to align a [`scrollable`][iced::widget::scrollable].
     pub fn update(&mut self, direction: Direction) -> f32 {
-        self.pointers[self.focused].0.update(direction)
+        self.pointer_mut().update(direction)
     }
 
     pub fn jump_to(&mut self, to: usize) -> f32 {
-        self.pointers[self.focused].0.jump_to(to)
+        self.pointer_mut().jump_to(to)
     }
 
     pub fn plus(&mut self, to_add: isize) -> f32 {
-        self.pointers[self.focused].0.plus(to_add)
+        self.pointer_mut().plus(to_add)
     }
 
     pub fn start(&mut self) -> f32 {
-        self.pointers[self.focused].0.start()
+        self.pointer_mut().start()
     }
 
     pub fn end(&mut self) -> f32 {
-        self.pointers[self.focused].0.end()
+        self.pointer_mut().end()
     }
 
     /// Sets the [`TitleCache`][TitleCache] of the indexed [`TitleCache`][TitleCache].
     /// ## Panics
     /// May panic if `number` is out of bounds.
+    ///
+    /// Titles with more than one season start on their season list.
     pub fn set_title_cache(&mut self, title_cache: TitleCache, title_number: usize) {
         self.focused = title_number + 1;
+        self.season = None;
+        self.showing_seasons = title_cache.seasons.is_some();
+
+        let pointer = &mut self.seasons[title_number];
+        pointer.size = title_cache.seasons.as_ref().map_or(0, |s| s.len());
+        pointer.focused = pointer.focused.min(pointer.size.saturating_sub(1));
 
         let pointer = &mut self.pointers[self.focused].0;
         pointer.size = title_cache.size;
+        pointer.focused = pointer.focused.min(pointer.size.saturating_sub(1));
+
+        self.data.set_title_cache(title_cache, title_number);
+        self.apply_filter();
+    }
+
+    /// Replaces the [`Cache`][Cache] after titles were added or removed, _moved_ has
+    /// the new index of each previous title. The focus stays on the same title if it's still there.
+    pub fn change_titles(&mut self, data: Cache, moved: &[Option<usize>]) {
+        let mut pointers: Vec<(Pointer, FocusedType)> = Vec::with_capacity(data.size + 1);
+        pointers.push((self.pointers[0].0, FocusedType::Title(0)));
+        pointers.extend((0..data.size).map(|_| (Pointer::new(0), FocusedType::Episode(0, 0))));
+        let mut seasons = vec![Pointer::new(0); data.size];
+
+        for (previous, &title_number) in moved.iter().enumerate() {
+            if let Some(title_number) = title_number {
+                pointers[title_number + 1].0 = self.pointers[previous + 1].0;
+                seasons[title_number] = self.seasons[previous];
+            }
+        }
+
+        let focused_title = self.focused_title().and_then(|idx| moved[idx]);
+        self.focused = match self.focused.checked_sub(1) {
+            Some(previous) => moved[previous].map_or(0, |title_number| title_number + 1),
+            None => 0,
+        };
+
+        if self.focused == 0 {
+            self.season = None;
+            self.showing_seasons = false;
+        }
+
+        self.pointers = pointers;
+        self.seasons = seasons;
+        self.data = data;
+        self.list_titles(focused_title);
+    }
+
+    /// Lists only the titles that match the search _query_, see [`TitleFilter`][TitleFilter].
+    pub fn set_query(&mut self, query: String) {
+        self.filter = TitleFilter::parse(&query);
+        self.query = query;
+        self.apply_filter();
+    }
+
+    /// Lists the titles in the _sort_ order.
+    pub fn set_sort(&mut self, sort: TitleSort) {
+        self.sort = sort;
+        self.apply_filter();
+    }
+
+    /// Updates the listed titles, keeping the focused one if it's still listed.
+    fn apply_filter(&mut self) {
+        self.list_titles(self.focused_title());
+    }
+
+    /// Returns the index of the title focused in the title list, if a title is focused there.
+    fn focused_title(&self) -> Option<usize> {
+        self.pointers[0]
+            .0
+            .focused
+            .checked_sub(self.next_up.len())
+            .and_then(|idx| self.titles.get(idx))
+            .copied()
+    }
+
+    /// Updates the listed titles, focusing the _focused_title_ if it's listed.
+    fn list_titles(&mut self, focused_title: Option<usize>) {
+        let focused = self.pointers[0].0.focused;
+
+        (self.next_up, self.titles) = match self.filter.is_empty() {
+            true => (self.data.next_up(), self.data.sorted(self.sort)),
+            false => (Vec::new(), self.data.search(&self.filter, self.sort)),
+        };
+
+        let pointer = &mut self.pointers[0].0;
+        pointer.size = self.next_up.len() + self.titles.len();
+        pointer.focused = match focused_title {
+            Some(focused) => self
+                .titles
+                .iter()
+                .position(|&idx| idx == focused)
+                .map_or(0, |idx| self.next_up.len() + idx),
+            None => focused.min(pointer.size.saturating_sub(1)),
+        };
+    }
+
+    /// Goes back to the title list.
+    pub fn show_titles(&mut self) {
+        self.focused = 0;
+        self.showing_seasons = false;
+    }
+
+    /// Checks if the focused list is the title list and no title matches the search.
+    pub fn is_empty(&self) -> bool {
+        self.focused == 0 && self.next_up.is_empty() && self.titles.is_empty()
+    }
+
+    /// Replaces the [`TitleCache`][TitleCache] of a title whose episodes changed,
+    /// keeping the focus where it was as far as possible.
+    /// ## Panics
+    /// May panic if `number` is out of bounds.
+    pub fn update_title(&mut self, title_cache: TitleCache, title_number: usize) {
+        self.update_title_cache(title_cache, title_number);
+        self.apply_filter();
+    }
+
+    fn update_title_cache(&mut self, title_cache: TitleCache, title_number: usize) {
+        let seasons = title_cache.seasons.as_ref().map_or(0, |s| s.len());
+        let size = title_cache.size;
+
+        let pointer = &mut self.seasons[title_number];
+        pointer.size = seasons;
+        pointer.focused = pointer.focused.min(seasons.saturating_sub(1));
+
+        self.data.set_title_cache(title_cache, title_number);
 
-        self.data.set_title_cache(title_cache, title_number)
+        let is_focused = self.focused == title_number + 1;
+        let range = match is_focused {
+            true => self.season_range(self.season),
+            false => None,
+        };
+
+        let pointer = &mut self.pointers[title_number + 1].0;
+        pointer.size = range.map_or(size, |range| range.len());
+        pointer.focused = pointer.focused.min(pointer.size.saturating_sub(1));
+
+        if is_focused && self.season.is_some_and(|season| season >= seasons) {
+            self.season = None;
+        }
+
+        if is_focused && self.season.is_none() {
+            self.showing_seasons = seasons > 0;
+        }
+    }
+
+    /// Replaces the name and [`TitleCache`][TitleCache] of a title whose metadata changed.
+    /// ## Panics
+    /// May panic if `number` is out of bounds.
+    pub fn change_title(&mut self, name: Arc<str>, title_cache: TitleCache, title_number: usize) {
+        self.data.set_title_name(name, title_number);
+        self.update_title(title_cache, title_number);
+    }
+
+    /// Lists the episodes of the indexed season of the focused title.
+    pub fn enter_season(&mut self, season: usize) {
+        let Some(range) = self.season_range(Some(season)) else {
+            return;
+        };
+
+        let pointer = &mut self.pointers[self.focused].0;
+        pointer.size = range.len();
+
+        if self.season != Some(season) {
+            pointer.focused = 0;
+        }
+
+        self.season = Some(season);
+        self.showing_seasons = false;
+    }
+
+    /// Returns the range of episodes of the indexed season of the focused title.
+    fn season_range(&self, season: Option<usize>) -> Option<Range<usize>> {
+        let seasons = self.data.get_title(self.focused - 1).seasons.as_ref()?;
+        Some(seasons.get(season?)?.episodes.clone())
+    }
+
+    /// Returns the index of the first episode in the focused list.
+    fn offset(&self) -> usize {
+        self.season_range(self.season)
+            .map_or(0, |range| range.start)
     }
 
     /// Sets the [`EpisodeCache`][EpisodeCache] of the indexed [`EpisodeCache`][EpisodeCache].
@@ -88,51 +319,134 @@ impl InnerData {
         for ep in episodes_cache {
             title.set_episode_cache(ep)
         }
+
+        // Watching an episode may change the order of the titles.
+        self.apply_filter();
     }
 
     /// Return the [`MetaCache`] of the focused element.
     pub fn get_metacache(&self) -> Arc<MetaCache> {
-        match self.pointers[self.focused] {
-            (pointer, FocusedType::Title(_)) => self.data.get_title_cache(pointer.focused),
-            (pointer, FocusedType::Episode(_, _)) => match self
+        let metacache = match self.get_type() {
+            FocusedType::Title(_) if self.is_empty() => None,
+            FocusedType::Title(title_number) => Some(self.data.get_title_cache(title_number)),
+            FocusedType::NextUp(title_number, episode_number) => {
+                let title = self.data.get_title(title_number);
+                Some(
+                    title
+                        .get_episode_cache(episode_number)
+                        .unwrap_or_else(|| self.data.get_title_cache(title_number)),
+                )
+            }
+            FocusedType::Season(title_number, season) => self
                 .data
-                .get_title(self.focused - 1)
-                .get_episode_cache(pointer.focused)
-            {
-                Some(metacache) => metacache,
-                None => Arc::from(MetaCache::empty()),
-            },
-        }
+                .get_title(title_number)
+                .seasons
+                .as_ref()
+                .and_then(|seasons| Some(seasons.get(season)?.cache.clone())),
+            FocusedType::Episode(title_number, episode_number) => self
+                .data
+                .get_title(title_number)
+                .get_episode_cache(episode_number),
+        };
+
+        metacache.unwrap_or_else(|| Arc::from(MetaCache::empty()))
     }
 
     /// Returns the [`FocusedType`] of the focused element.
     pub fn get_type(&self) -> FocusedType {
+        let pointer = self.pointer();
+
         match self.pointers[self.focused] {
-            (pointer, FocusedType::Title(_)) => FocusedType::Title(pointer.focused),
-            (pointer, FocusedType::Episode(_, _)) => {
-                FocusedType::Episode(self.focused - 1, pointer.focused)
-            }
+            (_, FocusedType::Title(_)) => match self.next_up.get(pointer.focused) {
+                Some(next) => FocusedType::NextUp(next.title_number, next.episode_number),
+                None => FocusedType::Title(
+                    self.titles
+                        .get(pointer.focused - self.next_up.len())
+                        .copied()
+                        .unwrap_or(0),
+                ),
+            },
+            _ if self.showing_seasons => FocusedType::Season(self.focused - 1, pointer.focused),
+            _ => FocusedType::Episode(self.focused - 1, self.offset() + pointer.focused),
         }
     }
 
-    /// Goes back to the [`FocusedType::Title`] list.
+    /// Returns the focused episode of the Continue Watching list, if one is focused.
+    pub fn get_next_up(&self) -> Option<&NextUpCache> {
+        match self.pointers[self.focused] {
+            (_, FocusedType::Title(_)) => self.next_up.get(self.pointer().focused),
+            _ => None,
+        }
+    }
+
+    /// Goes back to the [`FocusedType::Season`] list, if the title has seasons,
+    /// or the [`FocusedType::Title`] list.
     pub fn back(&mut self) {
-        self.focused = 0;
+        if self.focused > 0 && !self.showing_seasons && self.season.is_some() {
+            self.showing_seasons = true;
+        } else {
+            self.show_titles();
+        }
     }
 
     /// Returns a [`Column`][iced_native::widget::Column] with a [`Button`][iced_native::widget::Button]
     /// for each element in the focused list.
     pub fn view<'a>(&self) -> Element<'a, FrontendMessage> {
-        match self.pointers[self.focused] {
-            (pointer, FocusedType::Title(_)) => {
-                pointer.view(&self.data.titles_names, |_, _| theme::Text::Default)
+        let pointer = self.pointer();
+
+        match self.get_type() {
+            FocusedType::Title(_) | FocusedType::NextUp(_, _) => {
+                let next_up = self.next_up.iter().map(|next| {
+                    let title = &self.data.titles_names[next.title_number];
+
+                    match next.in_progress() {
+                        true => format!(
+                            "Continue: {title} - {} ({})",
+                            next.name,
+                            format_time(next.current)
+                        ),
+                        false => format!("Up next: {title} - {}", next.name),
+                    }
+                });
+
+                let names: Vec<_> = next_up
+                    .chain(self.titles.iter().map(|&idx| {
+                        let title = self.data.get_title(idx);
+                        let name = &self.data.titles_names[idx];
+
+                        match title.watch_status() {
+                            // Titles never started don't need a badge.
+                            WatchStatus::PlanToWatch if title.status.is_none() => name.to_string(),
+                            status => format!("{name}  [{status}]"),
+                        }
+                    }))
+                    .collect();
+
+                pointer.view(&names, |focused, id| {
+                    match id.checked_sub(self.next_up.len()) {
+                        None if id == focused => theme::Text::Default,
+                        None => theme::Text::Focused,
+                        Some(idx) => match self.data.get_title(self.titles[idx]).offline {
+                            true => theme::Text::Watched,
+                            false => theme::Text::Default,
+                        },
+                    }
+                })
+            }
+            FocusedType::Season(title_number, _) => {
+                let seasons = self.data.get_title(title_number).seasons.as_ref().unwrap();
+                let names: Vec<_> = seasons.iter().map(|season| season.name.clone()).collect();
+
+                pointer.view(&names, |_, _| theme::Text::Default)
             }
-            (pointer, FocusedType::Episode(_, _)) => {
-                let title = self.data.get_title(self.focused - 1);
+            FocusedType::Episode(title_number, _) => {
+                let title = self.data.get_title(title_number);
+                let offset = self.offset();
                 let names = title.episodes_names.as_ref().unwrap();
+                let names = &names[offset..offset + pointer.size];
 
                 pointer.view(names, |focused, id| {
-                    let watched = title.get_episode(id).unwrap().watched;
+                    let watched = title.get_episode(offset + id).unwrap().watched;
 
                     match id == focused {
                         true if watched => theme::Text::WatchedFocus,
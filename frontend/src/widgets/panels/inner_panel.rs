@@ -25,7 +25,7 @@ impl InnerPanel {
     pub fn view<'a>(&self, data: &InnerData) -> Element<'a, FrontendMessage> {
         match self {
             Self::Listdata(ftype) => match ftype {
-                FocusedType::Title(_) => {
+                FocusedType::Title(_) | FocusedType::NextUp(_, _) => {
                     container(scrollable(data.view()).id(SCROLLABLE_ID.clone()))
                         .width(Length::Fill)
                         .padding(15)
@@ -33,7 +33,7 @@ impl InnerPanel {
                         .into()
                 }
 
-                FocusedType::Episode(_, _) => {
+                FocusedType::Season(_, _) | FocusedType::Episode(_, _) => {
                     container(scrollable(data.view()).id(SCROLLABLE_ID.clone()))
                         .width(Length::Fill)
                         .padding(15)
@@ -52,7 +52,7 @@ impl InnerPanel {
                     MetaType::Title => container(
                         scrollable(image::Image::new(handle)).height(Length::Fixed(167.0)),
                     ),
-                    MetaType::Episode => container(image::Image::new(handle)),
+                    MetaType::Season | MetaType::Episode => container(image::Image::new(handle)),
                 };
 
                 container(scrollable(
@@ -1,11 +1,10 @@
-use backend::Config;
-
 use iced::theme::TextInput;
 use iced::widget::{button, container, pane_grid, scrollable, text};
 use iced::widget::{svg, text_input};
 use iced::{application, color, Color};
 use iced_aw::style::{number_input, NumberInputStyles};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 // Always import widget types from this module since it
 // uses our custom theme instead of the built-in iced::Theme.
@@ -44,18 +43,28 @@ struct ThemeParser {
 }
 
 impl Default for Theme {
+    /// The theme shipped with yama.
     fn default() -> Self {
-        let cfg: Config = confy::load("yama", "config").expect("Could not load config file.");
-        let content = std::fs::read_to_string(cfg.theme_path).unwrap();
-        let tp: ThemeParser = serde_json::from_str(&content).unwrap();
+        Self::from_json(crate::embedded::DEFAULT_THEME).expect("The default theme is valid")
+    }
+}
 
-        Self {
+impl Theme {
+    /// Loads the [`Theme`] from the json file at _path_.
+    pub fn from_path(path: &Path) -> crate::Result<Self> {
+        Self::from_json(&std::fs::read(path)?)
+    }
+
+    fn from_json(content: &[u8]) -> crate::Result<Self> {
+        let tp: ThemeParser = serde_json::from_slice(content)?;
+
+        Ok(Self {
             background: Color::from_rgb8(tp.background[0], tp.background[1], tp.background[2]),
             text: Color::from_rgb8(tp.text[0], tp.text[1], tp.text[2]),
             focus: Color::from_rgb8(tp.focus[0], tp.focus[1], tp.focus[2]),
             unfocus: Color::from_rgb8(tp.unfocus[0], tp.unfocus[1], tp.unfocus[2]),
             watched: Color::from_rgb8(tp.watched[0], tp.watched[1], tp.watched[2]),
-        }
+        })
     }
 }
 
@@ -0,0 +1,108 @@
+use frontend::keybindings::{Action, Chord, Input, Keymap, KeymapFile};
+use iced::keyboard::{KeyCode, Modifiers};
+use iced::mouse;
+use std::collections::BTreeMap;
+
+fn keymap(keys: &[(&str, &[&str])]) -> frontend::Result<Keymap> {
+    let keys = keys
+        .iter()
+        .map(|(action, chords)| {
+            let chords = chords.iter().map(|chord| chord.to_string()).collect();
+            (action.to_string(), chords)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    Keymap::new(&KeymapFile { keys })
+}
+
+fn key(modifiers: Modifiers, key_code: KeyCode) -> Chord {
+    Chord::new(modifiers, Input::Key(key_code))
+}
+
+#[test]
+fn parses_chords() {
+    let chords = [
+        ("k", key(Modifiers::empty(), KeyCode::K)),
+        ("Shift+W", key(Modifiers::SHIFT, KeyCode::W)),
+        (
+            "ctrl + alt + p",
+            key(Modifiers::CTRL | Modifiers::ALT, KeyCode::P),
+        ),
+        ("page_down", key(Modifiers::empty(), KeyCode::PageDown)),
+        ("esc", key(Modifiers::empty(), KeyCode::Escape)),
+        ("1", key(Modifiers::empty(), KeyCode::Key1)),
+        (
+            "mouse_back",
+            Chord::new(Modifiers::empty(), Input::Mouse(mouse::Button::Other(8))),
+        ),
+        (
+            "wheel_up",
+            Chord::new(Modifiers::empty(), Input::Wheel(true)),
+        ),
+    ];
+
+    for (chord, expected) in chords {
+        assert_eq!(Chord::parse(chord).unwrap(), expected, "{chord}");
+    }
+
+    assert!(Chord::parse("hyper+k").is_err());
+    assert!(Chord::parse("not_a_key").is_err());
+    assert_eq!(Chord::parse("shift+w").unwrap().to_string(), "Shift + W");
+}
+
+#[test]
+fn overrides_the_default_keymap() {
+    let keymap = keymap(&[("up", &["i"]), ("down", &["shift+j"])]).unwrap();
+
+    assert_eq!(
+        keymap.action(key(Modifiers::empty(), KeyCode::I)),
+        Some(Action::Up)
+    );
+    assert_eq!(keymap.action(key(Modifiers::empty(), KeyCode::K)), None);
+    assert_eq!(keymap.action(key(Modifiers::empty(), KeyCode::J)), None);
+    assert_eq!(
+        keymap.action(key(Modifiers::SHIFT, KeyCode::J)),
+        Some(Action::Down)
+    );
+    // Actions missing in the file keep their defaults.
+    assert_eq!(
+        keymap.action(key(Modifiers::empty(), KeyCode::Q)),
+        Some(Action::Quit)
+    );
+    assert!(keymap.help().contains("Shift + J -> Move one item down"));
+}
+
+#[test]
+fn rejects_invalid_keymaps() {
+    assert!(keymap(&[("fly", &["f"])]).is_err());
+    assert!(keymap(&[("up", &["nope"])]).is_err());
+
+    // `q` is already bound to quit.
+    let err = keymap(&[("refresh", &["q"])]).unwrap_err().to_string();
+    assert!(err.contains("quit") && err.contains("refresh"), "{err}");
+
+    assert_eq!(
+        Keymap::new(&KeymapFile::default()).unwrap(),
+        Keymap::default()
+    );
+}
+
+#[test]
+fn loads_the_keymap_file() {
+    let dir = std::env::temp_dir().join(format!("yama-keymap-{}", std::process::id()));
+    let path = dir.join("keymap.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // The file is created with the defaults if it doesn't exist.
+    assert_eq!(Keymap::load(&path).unwrap(), Keymap::default());
+    assert!(path.is_file());
+
+    std::fs::write(&path, "[keys]\nquit = [\"ctrl+q\"]\n").unwrap();
+    let keymap = Keymap::load(&path).unwrap();
+    assert_eq!(
+        keymap.action(key(Modifiers::CTRL, KeyCode::Q)),
+        Some(Action::Quit)
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
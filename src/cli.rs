@@ -0,0 +1,441 @@
+use backend::{next_episode, Backend, Config, Diagnostics, Episode, Title};
+use bridge::api::title_json;
+use frontend::Result;
+
+use anyhow::{anyhow, bail};
+use std::ops::RangeInclusive;
+use std::time::SystemTime;
+
+pub const USAGE: &str = "\
+Usage: yama [COMMAND]
+
+Without a command the window of yama is opened.
+
+Commands:
+  list [--json]                                  List the titles and their progress
+  show <title>                                   Show the info and episodes of a title
+  play <title> [--next | --episode <N>]          Play the next episode, or the Nth one, of a title
+  mark <title> <N | FIRST-LAST> [--unwatched]    Mark episodes of a title as watched, or unwatched
+  refresh                                        Refresh the episodes of every title
+  tui                                            Open yama in the terminal, like over SSH
+  doctor                                         Check the programs, folders and config yama needs
+  help                                           Show this help
+
+Titles are found by their name or folder name, ignoring case, or by a part of it.
+Episodes are numbered from 1.";
+
+/// Subcommands of the [yama] binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    List {
+        json: bool,
+    },
+    Show {
+        title: String,
+    },
+    /// Args: (Title, Episode number or the next one if it's none)
+    Play {
+        title: String,
+        episode: Option<usize>,
+    },
+    Mark {
+        title: String,
+        episodes: RangeInclusive<usize>,
+        watched: bool,
+    },
+    Refresh,
+    Tui,
+    Doctor,
+    Help,
+}
+
+impl Command {
+    /// Parses the _args_, without the name of the binary.
+    ///
+    /// Returns [`None`][None] if there's no command, to open the window.
+    pub fn parse(args: &[String]) -> Result<Option<Command>> {
+        let Some((command, args)) = args.split_first() else {
+            return Ok(None);
+        };
+
+        let (flags, positional): (Vec<&str>, Vec<&str>) = args
+            .iter()
+            .map(String::as_str)
+            .partition(|arg| arg.starts_with("--"));
+        let title = || {
+            positional
+                .first()
+                .map(|title| title.to_string())
+                .ok_or_else(|| anyhow!("Missing the title\n\n{USAGE}"))
+        };
+        // A mistyped option would be ignored, like `--unwatchd` marking as watched.
+        let known_flags = |known: &[&str]| match flags.iter().find(|flag| !known.contains(flag)) {
+            Some(flag) => Err(anyhow!(
+                "Unknown option `{flag}` for `{command}`\n\n{USAGE}"
+            )),
+            None => Ok(()),
+        };
+
+        let command = match command.as_str() {
+            "list" => {
+                known_flags(&["--json"])?;
+                Command::List {
+                    json: flags.contains(&"--json"),
+                }
+            }
+            "show" => {
+                known_flags(&[])?;
+                Command::Show { title: title()? }
+            }
+            "play" => {
+                known_flags(&["--next", "--episode"])?;
+                let episode = match flags.as_slice() {
+                    [] | ["--next"] => None,
+                    ["--episode"] => {
+                        let episode = positional
+                            .get(1)
+                            .ok_or_else(|| anyhow!("Missing the episode number of --episode"))?;
+                        Some(parse_number(episode)?)
+                    }
+                    _ => bail!("Use either --next or --episode <N>\n\n{USAGE}"),
+                };
+
+                Command::Play {
+                    title: title()?,
+                    episode,
+                }
+            }
+            "mark" => {
+                known_flags(&["--unwatched"])?;
+                let episodes = positional
+                    .get(1)
+                    .ok_or_else(|| anyhow!("Missing the episodes to mark\n\n{USAGE}"))?;
+
+                Command::Mark {
+                    title: title()?,
+                    episodes: parse_range(episodes)?,
+                    watched: !flags.contains(&"--unwatched"),
+                }
+            }
+            "refresh" => {
+                known_flags(&[])?;
+                Command::Refresh
+            }
+            "tui" => {
+                known_flags(&[])?;
+                Command::Tui
+            }
+            "doctor" => {
+                known_flags(&[])?;
+                Command::Doctor
+            }
+            "help" | "--help" | "-h" => Command::Help,
+            command => bail!("Unknown command `{command}`\n\n{USAGE}"),
+        };
+
+        Ok(Some(command))
+    }
+}
+
+fn parse_number(number: &str) -> Result<usize> {
+    match number.trim().parse() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => bail!("`{number}` is not an episode number, they start at 1"),
+    }
+}
+
+/// Parses the episodes to mark, like _3_ or _1-12_.
+fn parse_range(range: &str) -> Result<RangeInclusive<usize>> {
+    let (first, last) = range.split_once('-').unwrap_or((range, range));
+    let (first, last) = (parse_number(first)?, parse_number(last)?);
+
+    if first > last {
+        bail!("The range `{range}` is backwards");
+    }
+
+    Ok(first..=last)
+}
+
+/// Prints the [`Diagnostics`], exits with an error code if any check failed.
+pub fn doctor() -> Result<()> {
+    let path = confy::get_configuration_file_path("yama", "config")?;
+
+    // A config that can't be loaded fails its own check, and then its libraries aren't checked.
+    let config = if path.is_file() {
+        confy::load_path(&path).unwrap_or_default()
+    } else {
+        Config::default()
+    };
+    let diagnostics = Diagnostics::run(&config);
+
+    print!("{diagnostics}");
+
+    if !diagnostics.passed() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Runs the _command_ with a [`Backend`] of its own, without a window.
+pub async fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Doctor => doctor(),
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Command::List { json } => {
+            let backend = Backend::without_discord().await?;
+            list(&backend, json)
+        }
+        Command::Show { title } => {
+            let mut backend = Backend::without_discord().await?;
+            let title_number = find_title(&backend, &title)?;
+            show(&mut backend.titles[title_number]).await
+        }
+        Command::Play { title, episode } => {
+            let mut backend = Backend::new().await?;
+            let title_number = find_title(&backend, &title)?;
+            let res = play(&mut backend, title_number, episode).await;
+
+            if let Some(ds_client) = backend.ds_client {
+                let _ = ds_client.cleanup().await;
+            }
+
+            res
+        }
+        Command::Mark {
+            title,
+            episodes,
+            watched,
+        } => {
+            let mut backend = Backend::without_discord().await?;
+            let title_number = find_title(&backend, &title)?;
+            mark(&mut backend.titles[title_number], episodes, watched).await
+        }
+        Command::Refresh => {
+            let mut backend = Backend::without_discord().await?;
+            refresh(&mut backend).await
+        }
+        Command::Tui => tui::run(confy::load("yama", "config")?).await,
+    }
+}
+
+/// Returns the index of the [`Title`] named like the _query_, or the only one that contains it.
+fn find_title(backend: &Backend, query: &str) -> Result<usize> {
+    let query = query.to_lowercase();
+    let names = |title: &Title| {
+        [
+            title.display_name().to_lowercase(),
+            title.name.to_lowercase(),
+        ]
+    };
+
+    if let Some(idx) = backend
+        .titles
+        .iter()
+        .position(|title| names(title).contains(&query))
+    {
+        return Ok(idx);
+    }
+
+    let found: Vec<usize> = (0..backend.count)
+        .filter(|&idx| {
+            names(&backend.titles[idx])
+                .iter()
+                .any(|name| name.contains(&query))
+        })
+        .collect();
+
+    match found.as_slice() {
+        [idx] => Ok(*idx),
+        [] => bail!("No title found for `{query}`"),
+        found => {
+            let names: Vec<String> = found
+                .iter()
+                .map(|&idx| format!("  {}", backend.titles[idx].display_name()))
+                .collect();
+            bail!("`{query}` matches several titles:\n{}", names.join("\n"))
+        }
+    }
+}
+
+fn list(backend: &Backend, json: bool) -> Result<()> {
+    if json {
+        let titles: Vec<serde_json::Value> = backend.titles.iter().map(title_json).collect();
+
+        println!("{}", serde_json::to_string_pretty(&titles)?);
+        return Ok(());
+    }
+
+    for title in &backend.titles {
+        let progress = match &title.progress {
+            Some(progress) => format!("{}/{}", progress.watched, progress.episodes),
+            None => String::from("-"),
+        };
+
+        println!("{}\t{progress}\t{}", title.display_name(), title.status());
+    }
+
+    Ok(())
+}
+
+async fn show(title: &mut Title) -> Result<()> {
+    title.load_episodes(false).await?;
+
+    println!("{}", title.display_name());
+    if *title.name != *title.display_name() {
+        println!("Folder: {}", title.name);
+    }
+    println!("Path: {}", title.path.display());
+    println!("Status: {}", title.status());
+
+    if let Some(info) = &title.info {
+        if !info.genres.is_empty() {
+            println!("Genres: {}", info.genres.join(", "));
+        }
+        if let Some(studio) = &info.studio {
+            println!("Studio: {studio}");
+        }
+        if let Some(year) = info.year {
+            println!("Year: {year}");
+        }
+        if let Some(score) = info.score {
+            println!("Score: {score}%");
+        }
+        if !info.synopsis.is_empty() {
+            println!("\n{}", info.synopsis.trim());
+        }
+    }
+
+    println!();
+    for (idx, episode) in title.episodes.iter().flatten().enumerate() {
+        println!(
+            "{:>4}  {}  {}",
+            idx + 1,
+            episode_state(episode),
+            episode.name
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns if the _episode_ is watched, or where it was left.
+fn episode_state(episode: &Episode) -> String {
+    let metadata = &episode.metadata;
+
+    match (metadata.watched, metadata.current) {
+        (true, _) => String::from("[x]     "),
+        (false, current) if current > 1.0 => format!("[{}]", format_time(current)),
+        _ => String::from("[ ]     "),
+    }
+}
+
+fn format_time(time: f64) -> String {
+    let time = time as u64;
+    format!("{:02}:{:02}", time / 60, time % 60)
+}
+
+async fn play(backend: &mut Backend, title_number: usize, episode: Option<usize>) -> Result<()> {
+    let title = &mut backend.titles[title_number];
+    title.load_episodes(false).await?;
+    let title_name = title.display_name();
+    let count = title.episodes.as_ref().map_or(0, Vec::len);
+
+    let episode_number = match episode {
+        Some(number) if number <= count => number - 1,
+        Some(number) => bail!("{title_name} has {count} episodes, there's no episode {number}"),
+        None => next_episode(
+            title
+                .episodes
+                .iter()
+                .flatten()
+                .map(|e| (e.metadata.watched, e.metadata.last_watched)),
+        )
+        .ok_or_else(|| {
+            anyhow!("Every episode of {title_name} is watched, choose one with --episode <N>")
+        })?,
+    };
+
+    let player = backend.player(title_number)?;
+    let episode = backend
+        .get_episode(title_number, episode_number)
+        .ok_or_else(|| anyhow!("No episode found"))?;
+    let (episode_name, path) = (episode.name.clone(), episode.path.clone());
+    let playback = episode.play(player.as_ref());
+
+    println!("Playing {title_name} - {episode_name}");
+    if let (Some(ds_client), Some((_, remaining_time))) = (
+        &backend.ds_client,
+        backend.get_episode_data(title_number, episode_number),
+    ) {
+        ds_client
+            .watch_activity(title_name.clone(), episode_name.clone(), remaining_time)
+            .await;
+    }
+
+    let started = SystemTime::now();
+    let playback = playback.await?;
+    backend.finish_playback(title_number, &path, started, &playback)?;
+
+    if let Some(episode) = backend.get_episode(title_number, episode_number) {
+        match episode.metadata.watched {
+            true => println!("Watched {episode_name}"),
+            false => println!(
+                "Left {episode_name} at {}",
+                format_time(episode.metadata.current)
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+async fn mark(title: &mut Title, episodes: RangeInclusive<usize>, watched: bool) -> Result<()> {
+    title.load_episodes(false).await?;
+    let count = title.episodes.as_ref().map_or(0, Vec::len);
+
+    if *episodes.end() > count {
+        bail!("{} has {count} episodes", title.display_name());
+    }
+
+    let mut marked = 0;
+    for number in episodes {
+        if let Some(episode) = title.get_episode(number - 1) {
+            if episode.metadata.watched != watched {
+                episode.as_watched()?;
+                marked += 1;
+            }
+        }
+    }
+    title.update_progress();
+
+    println!(
+        "Marked {marked} episodes of {} as {}",
+        title.display_name(),
+        if watched { "watched" } else { "unwatched" }
+    );
+    Ok(())
+}
+
+async fn refresh(backend: &mut Backend) -> Result<()> {
+    let mut failed = 0;
+
+    for title in backend.titles.iter_mut().filter(|title| !title.offline) {
+        eprintln!("Refreshing {}...", title.display_name());
+
+        if let Err(e) = title.load_episodes(true).await {
+            eprintln!("Could not refresh {}: {e}", title.display_name());
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        bail!("{failed} titles could not be refreshed");
+    }
+
+    println!("Refreshed {} titles", backend.count);
+    Ok(())
+}
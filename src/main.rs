@@ -1,11 +1,12 @@
-use frontend::{Frontend, Result};
+mod cli;
+
+use backend::Config;
+use cli::Command;
+use frontend::{embedded, Frontend, Result};
 use once_cell::sync::Lazy;
 use std::path::PathBuf;
 use tracing::info;
 
-static SAVEINFO_LUA: &[u8] = include_bytes!("../scripts/save_info.lua");
-static DEFAULT_THEME: &[u8] = include_bytes!("../res/iced.json");
-
 static CFG_PATH: Lazy<PathBuf> = Lazy::new(|| {
     confy::get_configuration_file_path("yama", "config")
         .expect("No configuration path found.")
@@ -18,22 +19,37 @@ pub fn main() -> Result<()> {
     #[cfg(target_os = "windows")]
     windows_setup()?;
 
-    setup_logger();
-    info!("Starting up...\n{:-^1$}", " yama ", 80);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = Command::parse(&args)?;
 
-    let config = confy::load("yama", "config")?;
+    match command {
+        // The doctor must work even if the logs folder can't be created.
+        Some(Command::Doctor) => return cli::doctor(),
+        Some(command) => {
+            setup_logger();
+            info!("Running `{}`...", args.join(" "));
 
-    if !CFG_PATH.join("scripts/save_info.lua").is_dir() {
-        std::fs::create_dir_all(CFG_PATH.join("scripts"))?;
-        std::fs::write(CFG_PATH.join("scripts/save_info.lua"), SAVEINFO_LUA)?;
+            return tokio::runtime::Runtime::new()?.block_on(cli::run(command));
+        }
+        None => (),
     }
 
+    setup_logger();
+    info!("Starting up...\n{:-^1$}", " yama ", 80);
+
     if !CFG_PATH.join("themes").is_dir() {
         std::fs::create_dir_all(CFG_PATH.join("themes"))?;
-        std::fs::write(CFG_PATH.join("themes/iced.json"), DEFAULT_THEME)?;
+        std::fs::write(CFG_PATH.join("themes/iced.json"), embedded::DEFAULT_THEME)?;
     }
 
-    Frontend::execute(config)
+    // The config is written by the first launch setup once it's finished.
+    let first_run = !confy::get_configuration_file_path("yama", "config")?.is_file();
+    let config = match first_run {
+        true => Config::default(),
+        false => confy::load("yama", "config")?,
+    };
+
+    Frontend::execute(config, first_run)
 }
 
 fn setup_logger() {
@@ -0,0 +1,164 @@
+// The config folder of the command is set with XDG_CONFIG_HOME, only used on Linux.
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use backend::{EpisodeId, VideoMetadata};
+use common::temp_dir;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Creates a title with the given episodes, their metadata is already there
+/// so neither ffprobe nor the network are needed.
+fn title(library: &Path, name: &str, episodes: &[&str]) {
+    let title = library.join(name);
+    let metadata = title.join(".metadata");
+
+    for episode in episodes {
+        fs::create_dir_all(&title).unwrap();
+        let path = title.join(format!("{episode}.mkv"));
+        fs::write(&path, format!("{name} - {episode}")).unwrap();
+
+        let id = EpisodeId::new(&path).unwrap();
+        let episode_metadata = metadata.join("episodes").join(id.as_str());
+        fs::create_dir_all(&episode_metadata).unwrap();
+        fs::write(episode_metadata.join("thumbnail.jpg"), b"").unwrap();
+        VideoMetadata::default_file(1_440.0, &episode_metadata.join("metadata.md")).unwrap();
+    }
+
+    fs::write(
+        metadata.join("data.json"),
+        format!(
+            r#"{{"data":{{"Media":{{"id":1,"title":{{"romaji":"{name}"}},"studios":{{"edges":[]}}}}}}}}"#
+        ),
+    )
+    .unwrap();
+}
+
+/// Creates a config folder with a library of two titles, returns the folder to use as XDG_CONFIG_HOME.
+fn config(root: &Path) -> PathBuf {
+    let library = root.join("library");
+    title(
+        &library,
+        "Cowboy Bebop",
+        &["Asteroid Blues", "Stray Dog Strut", "Honky Tonk Women"],
+    );
+    title(
+        &library,
+        "Cowboy Bebop The Movie",
+        &["Knockin' on Heaven's Door"],
+    );
+
+    let config = root.join("config");
+    fs::create_dir_all(config.join("yama")).unwrap();
+    fs::write(
+        config.join("yama").join("config.toml"),
+        format!(
+            r#"
+            theme_path = "themes/iced.json"
+            min_time = 10.0
+
+            [[libraries]]
+            name = "Anime"
+            path = "{}"
+            enabled = true
+            "#,
+            library.display()
+        ),
+    )
+    .unwrap();
+
+    config
+}
+
+fn yama(config: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_yama"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config)
+        .output()
+        .unwrap()
+}
+
+/// Runs yama expecting it to fail, returns its error.
+fn fails(config: &Path, args: &[&str]) -> String {
+    let output = yama(config, args);
+    assert!(!output.status.success(), "yama {args:?} should fail");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// Runs yama expecting it to succeed, returns what it printed.
+fn succeeds(config: &Path, args: &[&str]) -> String {
+    let output = yama(config, args);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "yama {args:?} failed: {stderr}");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn episode_ranges() {
+    let root = temp_dir("cli-ranges");
+    let config = config(&root);
+
+    assert_eq!(
+        succeeds(&config, &["mark", "Cowboy Bebop", "1-3"]),
+        "Marked 3 episodes of Cowboy Bebop as watched\n"
+    );
+    assert_eq!(
+        succeeds(&config, &["mark", "Cowboy Bebop", "3", "--unwatched"]),
+        "Marked 1 episodes of Cowboy Bebop as unwatched\n"
+    );
+
+    assert!(
+        fails(&config, &["mark", "Cowboy Bebop", "3-1"]).contains("The range `3-1` is backwards")
+    );
+    assert!(fails(&config, &["mark", "Cowboy Bebop", "0"]).contains("`0` is not an episode number"));
+    assert!(
+        fails(&config, &["mark", "Cowboy Bebop", "a-b"]).contains("`a` is not an episode number")
+    );
+    assert!(
+        fails(&config, &["mark", "Cowboy Bebop", "2-4"]).contains("Cowboy Bebop has 3 episodes")
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn title_names() {
+    let root = temp_dir("cli-titles");
+    let config = config(&root);
+
+    // A whole name is found even if it's part of another one.
+    assert!(succeeds(&config, &["show", "cowboy bebop"]).contains("Asteroid Blues"));
+    assert!(succeeds(&config, &["show", "movie"]).contains("Knockin' on Heaven's Door"));
+
+    let error = fails(&config, &["show", "cowboy"]);
+    assert!(error.contains("`cowboy` matches several titles"), "{error}");
+    assert!(error.contains("Cowboy Bebop The Movie"), "{error}");
+
+    assert!(fails(&config, &["show", "frieren"]).contains("No title found for `frieren`"));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn unknown_options() {
+    let root = temp_dir("cli-options");
+    let config = config(&root);
+
+    let error = fails(&config, &["mark", "Cowboy Bebop", "3", "--unwatchd"]);
+    assert!(
+        error.contains("Unknown option `--unwatchd` for `mark`"),
+        "{error}"
+    );
+    assert!(fails(&config, &["list", "--jsn"]).contains("Unknown option `--jsn` for `list`"));
+    assert!(fails(&config, &["refresh", "--all"]).contains("Unknown option `--all` for `refresh`"));
+    assert!(fails(&config, &["play", "Cowboy Bebop", "--nxt"]).contains("Unknown option `--nxt`"));
+    assert!(fails(&config, &["watch"]).contains("Unknown command `watch`"));
+
+    // Nothing was marked.
+    assert!(!succeeds(&config, &["show", "Cowboy Bebop"]).contains("[x]"));
+
+    fs::remove_dir_all(&root).unwrap();
+}
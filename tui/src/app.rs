@@ -0,0 +1,388 @@
+use crate::keys;
+
+use backend::{Config, TitleSort, WatchStatus};
+use bridge::cache::{Cache, EpisodeCache, MetaCache, TitleCache};
+use bridge::next_up::NextUpCache;
+use bridge::search::TitleFilter;
+use bridge::{BackendMessage, FrontendMessage, PanelAction};
+use frontend::keybindings::Action;
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use iced::futures::channel::mpsc::Sender;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Items moved by [`PageUp`][Action::PageUp] and [`PageDown`][Action::PageDown], like in the window.
+const PAGE: isize = 5;
+
+/// A row of the title list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Row {
+    /// An episode of the Continue Watching list, at the top while not searching.
+    NextUp(NextUpCache),
+    /// Args: (Title index)
+    Title(usize),
+}
+
+/// The title list, or the episodes of a title, and what's focused in them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    /// Args: (Row index)
+    Titles(usize),
+    /// Args: (Title index, Episode number)
+    Episodes(usize, usize),
+}
+
+/// State of the terminal UI, updated by the [`FrontendMessages`][FrontendMessage] of the
+/// [`Session`][bridge::session::Session] and the keys pressed.
+#[derive(Debug)]
+pub struct App {
+    cache: Cache,
+    sender: Sender<BackendMessage>,
+    cfg: Config,
+    rows: Vec<Row>,
+    pub focus: Focus,
+    /// Row focused in the title list, kept while the episodes are listed.
+    title_row: usize,
+    /// The search query is being typed.
+    pub searching: bool,
+    pub query: String,
+    /// Waiting for the episodes of a title.
+    pub loading: bool,
+    /// Last thing that happened, like an episode being played or an error.
+    pub status: Option<Arc<str>>,
+    /// The backend closed, the terminal UI should exit.
+    pub exit: bool,
+}
+
+impl App {
+    pub fn new(cache: Cache, sender: Sender<BackendMessage>, cfg: Config) -> Self {
+        let mut app = Self {
+            cache,
+            sender,
+            cfg,
+            rows: Vec::new(),
+            focus: Focus::Titles(0),
+            title_row: 0,
+            searching: false,
+            query: String::new(),
+            loading: false,
+            status: None,
+            exit: false,
+        };
+
+        app.list_titles();
+        app
+    }
+
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    pub fn sort(&self) -> TitleSort {
+        self.cfg.title_sort
+    }
+
+    /// Returns the title of the focused row or episode, if any.
+    pub fn focused_title(&self) -> Option<usize> {
+        match self.focus {
+            Focus::Titles(row) => match self.rows.get(row)? {
+                Row::NextUp(next) => Some(next.title_number),
+                Row::Title(title_number) => Some(*title_number),
+            },
+            Focus::Episodes(title_number, _) => Some(title_number),
+        }
+    }
+
+    /// Returns the [`MetaCache`][MetaCache] of what's focused, to show its info.
+    pub fn focused_meta(&self) -> Option<Arc<MetaCache>> {
+        let (title_number, episode_number) = match self.focus {
+            Focus::Titles(row) => match self.rows.get(row)? {
+                Row::NextUp(next) => (next.title_number, Some(next.episode_number)),
+                Row::Title(title_number) => (*title_number, None),
+            },
+            Focus::Episodes(title_number, episode_number) => (title_number, Some(episode_number)),
+        };
+
+        episode_number
+            .and_then(|number| self.cache.get_title(title_number).get_episode_cache(number))
+            .or_else(|| Some(self.cache.get_title_cache(title_number)))
+    }
+
+    /// Lists the titles again after a change in the [`Cache`][Cache] or the search.
+    fn list_titles(&mut self) {
+        let filter = TitleFilter::parse(&self.query);
+
+        self.rows = match filter.is_empty() {
+            true => self
+                .cache
+                .next_up()
+                .into_iter()
+                .map(Row::NextUp)
+                .chain(
+                    self.cache
+                        .sorted(self.cfg.title_sort)
+                        .into_iter()
+                        .map(Row::Title),
+                )
+                .collect(),
+            false => self
+                .cache
+                .search(&filter, self.cfg.title_sort)
+                .into_iter()
+                .map(Row::Title)
+                .collect(),
+        };
+
+        self.title_row = self.title_row.min(self.rows.len().saturating_sub(1));
+        if let Focus::Titles(_) = self.focus {
+            self.focus = Focus::Titles(self.title_row);
+        }
+    }
+
+    fn send(&mut self, msg: BackendMessage) {
+        if let Err(e) = self.sender.try_send(msg) {
+            warn!("Could not send a message to the backend: {e}");
+            self.status = Some(Arc::from("Busy, try again!"));
+        }
+    }
+
+    /// Handles a terminal _event_, typing the search or running the [`Action`] of its keys.
+    pub fn handle_event(&mut self, event: Event) {
+        if self.searching {
+            if let Event::Key(key) = &event {
+                if key.kind == KeyEventKind::Release {
+                    return;
+                }
+
+                match key.code {
+                    KeyCode::Char(c) => self.query.push(c),
+                    KeyCode::Backspace => {
+                        self.query.pop();
+                    }
+                    KeyCode::Enter => self.searching = false,
+                    KeyCode::Esc => {
+                        self.searching = false;
+                        self.query.clear();
+                    }
+                    _ => return,
+                }
+
+                self.title_row = 0;
+                self.list_titles();
+                return;
+            }
+        }
+
+        if let Some(action) = keys::action(&event) {
+            self.run(action);
+        }
+    }
+
+    /// Runs an [`Action`] of the keymap, the same way as the window.
+    pub fn run(&mut self, action: Action) {
+        if self.loading && action != Action::Quit {
+            return;
+        }
+
+        match action {
+            Action::Up => self.move_focus(-1),
+            Action::Down => self.move_focus(1),
+            Action::PageUp => self.move_focus(-PAGE),
+            Action::PageDown => self.move_focus(PAGE),
+            Action::First => self.move_focus(isize::MIN),
+            Action::Last => self.move_focus(isize::MAX),
+            Action::Enter => match self.focus {
+                Focus::Titles(row) => match self.rows.get(row).cloned() {
+                    Some(Row::NextUp(next)) => self.send(next.message()),
+                    Some(Row::Title(title_number)) => {
+                        self.loading = true;
+                        self.send(BackendMessage::LoadEpisodes(title_number, false));
+                    }
+                    None => (),
+                },
+                Focus::Episodes(title_number, episode_number) => {
+                    self.send(BackendMessage::WatchEpisode(title_number, episode_number))
+                }
+            },
+            Action::Back => {
+                if let Focus::Episodes(..) = self.focus {
+                    self.focus = Focus::Titles(self.title_row);
+                }
+            }
+            Action::Refresh => {
+                if let Focus::Episodes(title_number, _) = self.focus {
+                    self.loading = true;
+                    self.send(BackendMessage::LoadEpisodes(title_number, true));
+                }
+            }
+            Action::MarkEpisode => {
+                if let Focus::Episodes(title_number, episode_number) = self.focus {
+                    self.send(BackendMessage::MarkEpisode(title_number, episode_number));
+                }
+            }
+            Action::MarkPreviousEpisodes => {
+                if let Focus::Episodes(title_number, episode_number) = self.focus {
+                    self.send(BackendMessage::MarkPreviousEpisodes(
+                        title_number,
+                        episode_number,
+                    ));
+                }
+            }
+            Action::NextStatus => {
+                if let Some(title_number) = self.focused_title() {
+                    let status = WatchStatus::next(self.cache.get_title(title_number).status);
+                    self.send(BackendMessage::SetStatus(title_number, status));
+                }
+            }
+            Action::Search => {
+                if let Focus::Titles(_) = self.focus {
+                    self.searching = true;
+                }
+            }
+            Action::ClearSearch => {
+                if !self.query.is_empty() {
+                    self.query.clear();
+                    self.list_titles();
+                }
+            }
+            Action::NextSort => {
+                self.cfg.title_sort = self.cfg.title_sort.next();
+
+                if let Err(e) = confy::store("yama", "config", &self.cfg) {
+                    warn!("Could not save config because: {:#?}", e)
+                }
+
+                self.list_titles();
+            }
+            Action::Quit => {
+                if self.sender.try_send(BackendMessage::CleanUp).is_err() {
+                    self.exit = true;
+                }
+            }
+            Action::PickMatch | Action::CommandPalette => {
+                self.status = Some(Arc::from("Only available in the window of yama"));
+            }
+        }
+    }
+
+    /// Moves the focus of the list by _to_add_ items, stopping at its ends.
+    fn move_focus(&mut self, to_add: isize) {
+        let len = match self.focus {
+            Focus::Titles(_) => self.rows.len(),
+            Focus::Episodes(title_number, _) => self.cache.get_title_size(title_number),
+        };
+        let focused = match &mut self.focus {
+            Focus::Titles(row) => row,
+            Focus::Episodes(_, episode_number) => episode_number,
+        };
+
+        *focused = focused
+            .saturating_add_signed(to_add)
+            .min(len.saturating_sub(1));
+
+        if let Focus::Titles(row) = self.focus {
+            self.title_row = row;
+        }
+    }
+
+    /// Updates the state with a [`FrontendMessage`][FrontendMessage] of the
+    /// [`Session`][bridge::session::Session].
+    pub fn update(&mut self, message: FrontendMessage) {
+        match message {
+            FrontendMessage::PaneAction(PanelAction::EpisodesLoaded(title_number, title_cache)) => {
+                self.loading = false;
+
+                let episode_number = match self.focus {
+                    Focus::Episodes(focused, episode_number) if focused == title_number => {
+                        episode_number.min(title_cache.size.saturating_sub(1))
+                    }
+                    _ => title_cache
+                        .next_episode()
+                        .map_or(0, |(episode_number, _, _)| episode_number),
+                };
+
+                self.cache.set_title_cache(title_cache, title_number);
+                self.focus = Focus::Episodes(title_number, episode_number);
+            }
+            FrontendMessage::PaneAction(PanelAction::UpdateEpisode(title_number, episodes)) => {
+                self.set_episodes(title_number, episodes);
+            }
+            FrontendMessage::PlaybackStarted(title_name, episode_name) => {
+                self.status = Some(Arc::from(format!("Playing {title_name} - {episode_name}")));
+            }
+            FrontendMessage::PlaybackFinished(title_number, result) => match result {
+                Ok(episode) => {
+                    self.status = None;
+                    self.set_episodes(title_number, vec![episode]);
+                }
+                Err(e) => self.status = Some(e),
+            },
+            FrontendMessage::TitlesChanged(added, removed, moved, cache) => {
+                let names = added
+                    .iter()
+                    .map(|name| format!("+{name}"))
+                    .chain(removed.iter().map(|name| format!("-{name}")));
+                self.status = Some(Arc::from(format!(
+                    "Titles changed: {}",
+                    names.collect::<Vec<_>>().join(", ")
+                )));
+
+                // The listed episodes are kept if their title is still there.
+                if let Focus::Episodes(title_number, episode_number) = self.focus {
+                    self.focus = match moved[title_number] {
+                        Some(title_number) => Focus::Episodes(title_number, episode_number),
+                        None => Focus::Titles(self.title_row),
+                    };
+                }
+
+                self.cache = cache;
+                self.list_titles();
+            }
+            FrontendMessage::EpisodesAdded(title_number, names, title_cache)
+            | FrontendMessage::EpisodesRemoved(title_number, names, title_cache) => {
+                self.status = Some(Arc::from(format!(
+                    "Episodes changed in {}: {}",
+                    self.cache.titles_names[title_number],
+                    names.join(", ")
+                )));
+                self.set_title(title_number, title_cache);
+            }
+            FrontendMessage::TitleChanged(title_number, name, title_cache) => {
+                self.cache.set_title_name(name, title_number);
+                self.set_title(title_number, title_cache);
+            }
+            FrontendMessage::Error(e) => {
+                self.loading = false;
+                self.status = Some(e);
+            }
+            FrontendMessage::Exit => self.exit = true,
+            _ => (),
+        }
+    }
+
+    fn set_episodes(&mut self, title_number: usize, episodes: Vec<EpisodeCache>) {
+        let title = self.cache.get_mut_title(title_number);
+
+        for episode in episodes {
+            title.set_episode_cache(episode);
+        }
+
+        self.list_titles();
+    }
+
+    fn set_title(&mut self, title_number: usize, title_cache: TitleCache) {
+        if let Focus::Episodes(focused, episode_number) = &mut self.focus {
+            if *focused == title_number {
+                *episode_number = (*episode_number).min(title_cache.size.saturating_sub(1));
+            }
+        }
+
+        self.cache.set_title_cache(title_cache, title_number);
+        self.list_titles();
+    }
+}
@@ -0,0 +1,124 @@
+use frontend::keybindings::{self, Action, Chord, Input};
+
+use crossterm::event::{
+    Event, KeyCode as TermKey, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
+};
+use iced::keyboard::{KeyCode, Modifiers};
+use iced::mouse;
+
+/// Letters in the order of the alphabet, to find their [`KeyCode`].
+const LETTERS: [KeyCode; 26] = {
+    use KeyCode::*;
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ]
+};
+
+const DIGITS: [KeyCode; 10] = {
+    use KeyCode::*;
+    [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9]
+};
+
+const FUNCTION_KEYS: [KeyCode; 12] = {
+    use KeyCode::*;
+    [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12]
+};
+
+/// Returns the [`Action`] bound in the keymap to the input of the terminal _event_, if any.
+pub fn action(event: &Event) -> Option<Action> {
+    keybindings::keymap().action(chord(event)?)
+}
+
+/// Returns the [`Chord`] of a terminal _event_, to find it in the keymap of the window.
+///
+/// Only key presses, the mouse buttons and the mouse wheel have one.
+pub fn chord(event: &Event) -> Option<Chord> {
+    match event {
+        Event::Key(key) => key_chord(key),
+        Event::Mouse(mouse_event) => {
+            let input = match mouse_event.kind {
+                MouseEventKind::Down(MouseButton::Left) => Input::Mouse(mouse::Button::Left),
+                MouseEventKind::Down(MouseButton::Right) => Input::Mouse(mouse::Button::Right),
+                MouseEventKind::Down(MouseButton::Middle) => Input::Mouse(mouse::Button::Middle),
+                MouseEventKind::ScrollUp => Input::Wheel(true),
+                MouseEventKind::ScrollDown => Input::Wheel(false),
+                _ => return None,
+            };
+
+            Some(Chord::new(modifiers(mouse_event.modifiers), input))
+        }
+        _ => None,
+    }
+}
+
+fn key_chord(key: &KeyEvent) -> Option<Chord> {
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+
+    let mut modifiers = modifiers(key.modifiers);
+
+    let key_code = match key.code {
+        TermKey::Char(c) if c.is_ascii_alphabetic() => {
+            // Shift is already in the case of the letter.
+            modifiers.set(Modifiers::SHIFT, c.is_ascii_uppercase());
+            LETTERS[(c.to_ascii_lowercase() as u8 - b'a') as usize]
+        }
+        TermKey::Char(c) => {
+            // The other characters already are the ones typed with shift, like _?_.
+            modifiers.remove(Modifiers::SHIFT);
+
+            match c {
+                '0'..='9' => DIGITS[(c as u8 - b'0') as usize],
+                ' ' => KeyCode::Space,
+                '\'' => KeyCode::Apostrophe,
+                '\\' => KeyCode::Backslash,
+                ',' => KeyCode::Comma,
+                '=' => KeyCode::Equals,
+                '`' => KeyCode::Grave,
+                '-' => KeyCode::Minus,
+                '.' => KeyCode::Period,
+                ';' => KeyCode::Semicolon,
+                '/' => KeyCode::Slash,
+                _ => return None,
+            }
+        }
+        TermKey::F(n @ 1..=12) => FUNCTION_KEYS[n as usize - 1],
+        TermKey::Esc => KeyCode::Escape,
+        TermKey::Insert => KeyCode::Insert,
+        TermKey::Home => KeyCode::Home,
+        TermKey::Delete => KeyCode::Delete,
+        TermKey::End => KeyCode::End,
+        TermKey::PageDown => KeyCode::PageDown,
+        TermKey::PageUp => KeyCode::PageUp,
+        TermKey::Left => KeyCode::Left,
+        TermKey::Up => KeyCode::Up,
+        TermKey::Right => KeyCode::Right,
+        TermKey::Down => KeyCode::Down,
+        TermKey::Backspace => KeyCode::Backspace,
+        TermKey::Enter => KeyCode::Enter,
+        TermKey::Tab => KeyCode::Tab,
+        TermKey::BackTab => {
+            modifiers.insert(Modifiers::SHIFT);
+            KeyCode::Tab
+        }
+        _ => return None,
+    };
+
+    Some(Chord::new(modifiers, Input::Key(key_code)))
+}
+
+fn modifiers(term: KeyModifiers) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+
+    for (term_modifier, modifier) in [
+        (KeyModifiers::SHIFT, Modifiers::SHIFT),
+        (KeyModifiers::CONTROL, Modifiers::CTRL),
+        (KeyModifiers::ALT, Modifiers::ALT),
+        (KeyModifiers::SUPER, Modifiers::LOGO),
+    ] {
+        modifiers.set(modifier, term.contains(term_modifier));
+    }
+
+    modifiers
+}
@@ -0,0 +1,106 @@
+mod app;
+pub mod keys;
+mod view;
+
+pub use app::App;
+
+use backend::Config;
+use bridge::session::Session;
+use bridge::FrontendMessage;
+
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, EventStream};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::ExecutableCommand;
+use iced::futures::channel::mpsc;
+use iced::futures::StreamExt;
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::{self, Stdout};
+use tokio::sync::mpsc as tokio_mpsc;
+use tracing::{error, info};
+
+pub type Result<T> = anyhow::Result<T>;
+
+type Term = Terminal<CrosstermBackend<Stdout>>;
+
+/// Runs [yama] in the terminal, with the same title list, episode list and info
+/// as the panels of the window.
+///
+/// It must be called inside a multi-threaded [tokio] runtime.
+pub async fn run(cfg: Config) -> Result<()> {
+    let mut terminal = setup_terminal()?;
+
+    // The terminal is restored even if it panics, otherwise the shell is left unusable.
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        hook(info);
+    }));
+
+    let res = execute(&mut terminal, cfg).await;
+    restore_terminal()?;
+
+    res
+}
+
+async fn execute(terminal: &mut Term, cfg: Config) -> Result<()> {
+    terminal.draw(|frame| view::draw_loading(frame, "Loading titles..."))?;
+
+    info!("Starting up the backend thread...");
+    let (mut session, cache) = Session::start().await?;
+    let (sender, mut receiver) = mpsc::channel(1);
+    let (messages_sender, mut messages) = tokio_mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        loop {
+            let msg = session.next(&mut receiver).await;
+            let exit = matches!(msg, FrontendMessage::Exit);
+
+            if messages_sender.send(msg).is_err() || exit {
+                break;
+            }
+        }
+    });
+
+    let mut app = App::new(cache, sender, cfg);
+    let mut events = EventStream::new();
+
+    while !app.exit {
+        terminal.draw(|frame| view::draw(frame, &app))?;
+
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(event)) => app.handle_event(event),
+                Some(Err(e)) => {
+                    error!("{e}");
+                    return Err(e.into());
+                }
+                None => break,
+            },
+            msg = messages.recv() => match msg {
+                Some(msg) => app.update(msg),
+                None => break,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+fn setup_terminal() -> Result<Term> {
+    terminal::enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    stdout.execute(EnterAlternateScreen)?;
+    stdout.execute(EnableMouseCapture)?;
+
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+fn restore_terminal() -> Result<()> {
+    let mut stdout = io::stdout();
+    stdout.execute(DisableMouseCapture)?;
+    stdout.execute(LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use crate::app::{App, Focus, Row};
+
+use frontend::keybindings::{self, Action};
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// Actions listed in the bottom bar, with their first key.
+const HINTS: [(Action, &str); 8] = [
+    (Action::Down, "down"),
+    (Action::Up, "up"),
+    (Action::Enter, "enter"),
+    (Action::Back, "back"),
+    (Action::MarkEpisode, "mark"),
+    (Action::Search, "search"),
+    (Action::NextSort, "sort"),
+    (Action::Quit, "quit"),
+];
+
+/// Draws the same layout as the panels of the window: the list on the left,
+/// the info of the focused item on the right and a bar at the bottom.
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, bar] = split(
+        Direction::Vertical,
+        frame.size(),
+        [Constraint::Min(3), Constraint::Length(1)],
+    );
+    let [list, info] = split(
+        Direction::Horizontal,
+        main,
+        [Constraint::Percentage(40), Constraint::Percentage(60)],
+    );
+
+    draw_list(frame, app, list);
+    draw_info(frame, app, info);
+    frame.render_widget(Paragraph::new(bar_text(app)), bar);
+}
+
+/// Draws a message while the titles are loaded.
+pub fn draw_loading(frame: &mut Frame, message: &str) {
+    let block = Block::default().borders(Borders::ALL).title(" yama ");
+    frame.render_widget(Paragraph::new(message).block(block), frame.size());
+}
+
+fn split<const N: usize>(
+    direction: Direction,
+    area: Rect,
+    constraints: [Constraint; N],
+) -> [Rect; N] {
+    let areas = Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area);
+
+    std::array::from_fn(|idx| areas[idx])
+}
+
+fn draw_list(frame: &mut Frame, app: &App, area: Rect) {
+    let cache = app.cache();
+
+    let (title, items, selected): (String, Vec<ListItem>, usize) = match app.focus {
+        Focus::Titles(row) => {
+            let items = app
+                .rows()
+                .iter()
+                .map(|row| match row {
+                    Row::NextUp(next) => {
+                        let state = match next.in_progress() {
+                            true => format!("[{}]", format_time(next.current)),
+                            false => String::from("[next]"),
+                        };
+
+                        ListItem::new(format!(
+                            "> {} - {} {state}",
+                            cache.titles_names[next.title_number], next.name
+                        ))
+                        .style(Style::default().add_modifier(Modifier::ITALIC))
+                    }
+                    Row::Title(title_number) => {
+                        let title = cache.get_title(*title_number);
+                        let progress = match title.progress() {
+                            Some((watched, episodes)) => format!("{watched}/{episodes}"),
+                            None => String::from("-"),
+                        };
+                        let offline = if title.offline { " (offline)" } else { "" };
+
+                        let item = ListItem::new(format!(
+                            "{}  {progress}  {}{offline}",
+                            cache.titles_names[*title_number],
+                            title.watch_status()
+                        ));
+
+                        match title.offline || !title.has_unwatched() {
+                            true => item.style(Style::default().add_modifier(Modifier::DIM)),
+                            false => item,
+                        }
+                    }
+                })
+                .collect();
+
+            let title = match app.query.is_empty() && !app.searching {
+                true => format!(" Titles ({}) ", app.sort()),
+                false => format!(" Search: {} ", app.query),
+            };
+
+            (title, items, row)
+        }
+        Focus::Episodes(title_number, episode_number) => {
+            let title = cache.get_title(title_number);
+            let names = title.episodes_names.as_deref().unwrap_or_default();
+
+            let items = names
+                .iter()
+                .enumerate()
+                .map(|(number, name)| {
+                    let episode = title.get_episode(number);
+                    let state = match episode {
+                        Some(episode) if episode.watched => String::from("[x]     "),
+                        Some(episode) if episode.current > 1.0 => {
+                            format!("[{}] ", format_time(episode.current))
+                        }
+                        _ => String::from("[ ]     "),
+                    };
+
+                    let shown_number = episode.map_or(number as f32 + 1.0, |e| e.shown_number);
+                    let item = ListItem::new(format!("{state}{shown_number:>3}  {name}"));
+                    match episode.is_some_and(|episode| episode.watched) {
+                        true => item.style(Style::default().add_modifier(Modifier::DIM)),
+                        false => item,
+                    }
+                })
+                .collect();
+
+            (
+                format!(" {} ", cache.titles_names[title_number]),
+                items,
+                episode_number,
+            )
+        }
+    };
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    let mut state = ListState::default().with_selected(Some(selected));
+
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Draws the info of the focused title or episode, the thumbnails are left out.
+fn draw_info(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(" Info ");
+
+    let (Some(title_number), Some(meta)) = (app.focused_title(), app.focused_meta()) else {
+        frame.render_widget(Paragraph::new("No titles found").block(block), area);
+        return;
+    };
+
+    let title = app.cache().get_title(title_number);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::from(Span::styled(meta.title.to_string(), bold))];
+
+    let episode_number = match app.focus {
+        Focus::Episodes(_, episode_number) => Some(episode_number),
+        Focus::Titles(row) => match app.rows().get(row) {
+            Some(Row::NextUp(next)) => Some(next.episode_number),
+            _ => None,
+        },
+    };
+
+    match episode_number {
+        Some(number) => {
+            let shown_number = title
+                .get_episode(number)
+                .map_or(number as f32 + 1.0, |episode| episode.shown_number);
+
+            lines.push(Line::from(format!(
+                "Episode {shown_number} of {}",
+                app.cache().titles_names[title_number]
+            )));
+
+            if let Some(season) = title
+                .seasons
+                .iter()
+                .flat_map(|seasons| seasons.iter())
+                .find(|season| season.episodes.contains(&number))
+            {
+                lines.push(Line::from(format!("Season: {}", season.name)));
+            }
+
+            if let Some(episode) = title.get_episode(number) {
+                lines.push(Line::from(match episode.watched {
+                    true => String::from("Watched"),
+                    false if episode.current > 1.0 => {
+                        format!("Left at {}", format_time(episode.current))
+                    }
+                    false => String::from("Not watched"),
+                }));
+            }
+        }
+        None => {
+            lines.push(Line::from(format!("Status: {}", title.watch_status())));
+
+            if let Some((watched, episodes)) = title.progress() {
+                lines.push(Line::from(format!(
+                    "Watched: {watched}/{episodes} episodes"
+                )));
+            }
+            if !title.genres.is_empty() {
+                lines.push(Line::from(format!("Genres: {}", title.genres.join(", "))));
+            }
+            if let Some(studio) = &title.studio {
+                lines.push(Line::from(format!("Studio: {studio}")));
+            }
+            if let Some(year) = title.year {
+                lines.push(Line::from(format!("Year: {year}")));
+            }
+            if let Some(score) = title.score {
+                lines.push(Line::from(format!("Score: {score}%")));
+            }
+            if title.offline {
+                lines.push(Line::from("Its library is not available"));
+            }
+        }
+    }
+
+    lines.push(Line::default());
+    lines.extend(
+        meta.description
+            .lines()
+            .map(|line| Line::from(line.to_string())),
+    );
+
+    let info = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(info, area);
+}
+
+/// Returns the status, or the keys of the main actions if there's none.
+fn bar_text(app: &App) -> String {
+    if app.searching {
+        return format!("/{}  (enter to keep, esc to clear)", app.query);
+    }
+
+    if app.loading {
+        return String::from("Loading episodes...");
+    }
+
+    if let Some(status) = &app.status {
+        return status.to_string();
+    }
+
+    let keymap = keybindings::keymap();
+    let hints: Vec<String> = HINTS
+        .iter()
+        .filter_map(|(action, hint)| {
+            let chord = keymap.chords(*action).next()?;
+            Some(format!("{chord}: {hint}"))
+        })
+        .collect();
+
+    hints.join("  ")
+}
+
+fn format_time(time: f64) -> String {
+    let time = time as u64;
+    format!("{:02}:{:02}", time / 60, time % 60)
+}
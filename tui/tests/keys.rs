@@ -0,0 +1,86 @@
+use frontend::keybindings::{Chord, Keymap};
+use tui::keys;
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+fn mouse(kind: MouseEventKind) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column: 0,
+        row: 0,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn terminal_keys_are_chords_of_the_keymap() {
+    let chord = |event: Event| keys::chord(&event);
+    let parsed = |chord: &str| Some(Chord::parse(chord).unwrap());
+
+    assert_eq!(
+        chord(key(KeyCode::Char('k'), KeyModifiers::NONE)),
+        parsed("k")
+    );
+    // Terminals send shift with the uppercase letter, or only the uppercase letter.
+    assert_eq!(
+        chord(key(KeyCode::Char('W'), KeyModifiers::SHIFT)),
+        parsed("shift+w")
+    );
+    assert_eq!(
+        chord(key(KeyCode::Char('W'), KeyModifiers::NONE)),
+        parsed("shift+w")
+    );
+    assert_eq!(
+        chord(key(KeyCode::Char('p'), KeyModifiers::CONTROL)),
+        parsed("ctrl+p")
+    );
+    assert_eq!(
+        chord(key(KeyCode::Char('/'), KeyModifiers::NONE)),
+        parsed("slash")
+    );
+    assert_eq!(
+        chord(key(KeyCode::Char('7'), KeyModifiers::NONE)),
+        parsed("7")
+    );
+    assert_eq!(
+        chord(key(KeyCode::PageDown, KeyModifiers::NONE)),
+        parsed("page_down")
+    );
+    assert_eq!(
+        chord(key(KeyCode::Esc, KeyModifiers::NONE)),
+        parsed("escape")
+    );
+    assert_eq!(chord(key(KeyCode::F(5), KeyModifiers::NONE)), parsed("f5"));
+    assert_eq!(chord(mouse(MouseEventKind::ScrollUp)), parsed("wheel_up"));
+    assert_eq!(
+        chord(mouse(MouseEventKind::Down(MouseButton::Right))),
+        parsed("mouse_right")
+    );
+
+    assert_eq!(chord(key(KeyCode::Char('ñ'), KeyModifiers::NONE)), None);
+    assert_eq!(chord(mouse(MouseEventKind::Moved)), None);
+
+    let mut release = KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE);
+    release.kind = KeyEventKind::Release;
+    assert_eq!(chord(Event::Key(release)), None);
+}
+
+#[test]
+fn vim_keys_are_kept() {
+    use frontend::keybindings::Action;
+
+    let keymap = Keymap::default();
+    let action = |code| keymap.action(keys::chord(&key(code, KeyModifiers::NONE)).unwrap());
+
+    assert_eq!(action(KeyCode::Char('j')), Some(Action::Down));
+    assert_eq!(action(KeyCode::Char('k')), Some(Action::Up));
+    assert_eq!(action(KeyCode::Char('l')), Some(Action::Enter));
+    assert_eq!(action(KeyCode::Char('h')), Some(Action::Back));
+    assert_eq!(action(KeyCode::Char(' ')), Some(Action::MarkEpisode));
+}